@@ -0,0 +1,97 @@
+use chrono::{Duration, NaiveDate, Utc};
+use criterion::{criterion_group, criterion_main, Criterion};
+use flowstate::app::*;
+
+const NUM_TEAMS: u64 = 5;
+const NUM_RESOURCES: u64 = 50;
+const NUM_TASKS: u64 = 5_000;
+const NUM_TASKS_WITH_WORKLOGS: u64 = 1_000;
+
+fn date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 9, 1).unwrap()
+}
+
+/// 5,000 tasks spread round-robin over 50 resources, a fifth of them with a week of
+/// worklogs in the past, and an absence for every resource.
+fn synthetic_project() -> FlowState {
+    let timestamp = Utc::now();
+    let mut flow_state = FlowState::new();
+    let mut execute = |details: CommandDetails| {
+        flow_state.execute_command_and_generate_inverse(Command { timestamp, details }).unwrap();
+    };
+    for team_id in 1..=NUM_TEAMS {
+        execute(CommandDetails::CreateTeam { id: team_id, name: format!("Team {}", team_id) });
+    }
+    for resource_id in 1..=NUM_RESOURCES {
+        execute(CommandDetails::CreateResource {
+            id: resource_id,
+            name: format!("Resource {}", resource_id),
+            team_id: resource_id % NUM_TEAMS + 1,
+        });
+        execute(CommandDetails::SetAbsence {
+            resource_id,
+            start_date: date() + Duration::days(7 * (resource_id % 8) as i64),
+            days: TaskDuration { days: 3, fraction: 0 },
+        });
+    }
+    for task_id in 1..=NUM_TASKS {
+        let resource_id = task_id % NUM_RESOURCES + 1;
+        execute(CommandDetails::CreateTask {
+            id: task_id,
+            ticket: format!("BENCH-{}", task_id),
+            title: format!("Task {}", task_id),
+            duration: TaskDuration { days: task_id % 5 + 1, fraction: 0 },
+            estimate: None,
+        });
+        execute(CommandDetails::AssignTask { task_id, resource_id });
+        if task_id <= NUM_TASKS_WITH_WORKLOGS {
+            for day in 1..=5 {
+                execute(CommandDetails::SetWorklog {
+                    task_id,
+                    date: date() - Duration::days(day * 7 + (task_id / NUM_RESOURCES) as i64 % 200),
+                    resource_id,
+                    fraction: 20,
+                });
+            }
+        }
+    }
+    flow_state.rebuild_cache(date());
+    flow_state
+}
+
+fn set_worklog(task_id: TaskId, fraction: Fraction) -> Command {
+    Command { timestamp: Utc::now(), details: CommandDetails::SetWorklog {
+        task_id,
+        date: date(),
+        resource_id: task_id % NUM_RESOURCES + 1,
+        fraction,
+    }}
+}
+
+fn bench_flow_state_cache(c: &mut Criterion) {
+    let mut flow_state = synthetic_project();
+    let task_id = NUM_TASKS / 2;
+
+    c.bench_function("full rebuild (5,000 tasks)", |b| {
+        b.iter(|| flow_state.rebuild_cache(date()))
+    });
+
+    c.bench_function("set worklog, full rebuild (5,000 tasks)", |b| {
+        b.iter(|| {
+            let undo = flow_state.execute_command_and_generate_inverse(set_worklog(task_id, 50)).unwrap();
+            flow_state.rebuild_cache(date());
+            flow_state.execute_command_and_generate_inverse(undo).unwrap();
+            flow_state.rebuild_cache(date());
+        })
+    });
+
+    c.bench_function("set worklog, incremental update (5,000 tasks)", |b| {
+        b.iter(|| {
+            let undo = flow_state.execute_command_generate_inverse_and_rebuild_cache(set_worklog(task_id, 50), date()).unwrap();
+            flow_state.execute_command_generate_inverse_and_rebuild_cache(undo, date()).unwrap();
+        })
+    });
+}
+
+criterion_group!(benches, bench_flow_state_cache);
+criterion_main!(benches);
@@ -0,0 +1,129 @@
+use crate::app::*;
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// One command or history event of the project, as shown in the audit log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    /// Index of the command in the command stack; history events have none.
+    pub index: Option<usize>,
+    pub timestamp: DateTime<Utc>,
+    pub author: Option<String>,
+    pub description: String,
+    pub subjects: Vec<(EntityKind, EntityKey)>,
+    /// Whether the command is applied, as opposed to undone and still redoable.
+    pub applied: bool,
+}
+
+/// Which commands the audit log shows. Unset fields match everything; `entity_key` only
+/// narrows `entity_kind` down and is ignored without it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditFilter {
+    pub author: Option<String>,
+    pub entity_kind: Option<EntityKind>,
+    pub entity_key: Option<EntityKey>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl AuditFilter {
+    /// Looks `text` up as an entity of `kind` in `flow_state`: an id, or the name of the team,
+    /// resource, label or filter, or the ticket of the task.
+    pub fn resolve_entity_key(flow_state: &FlowState, kind: EntityKind, text: &str) -> EntityKey {
+        if let Ok(id) = text.parse::<u64>() {
+            return EntityKey::Id(id);
+        }
+        let id = match kind {
+            EntityKind::Team => flow_state.get_team_id(text),
+            EntityKind::Resource => flow_state.get_resource_id(text),
+            EntityKind::Label => flow_state.get_label_id(text),
+            EntityKind::Filter => flow_state.get_filter_id(text),
+            EntityKind::Task => flow_state.tasks.iter()
+                .find(|(_, task)| task.ticket == text)
+                .map(|(task_id, _)| *task_id),
+            _ => None,
+        };
+        match id {
+            Some(id) => EntityKey::Id(id),
+            None => EntityKey::Name(text.to_string()),
+        }
+    }
+
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        let date = entry.timestamp.date_naive();
+        self.author.as_ref().is_none_or(|author| entry.author.as_ref() == Some(author))
+            && self.from.is_none_or(|from| date >= from)
+            && self.to.is_none_or(|to| date <= to)
+            && self.entity_kind.is_none_or(|kind| entry.subjects.iter().any(|(subject_kind, subject_key)| {
+                *subject_kind == kind && self.entity_key.as_ref().is_none_or(|key| key == subject_key)
+            }))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    pub entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    /// The records of `command_stack` and the `history_events` that pass `filter`, newest
+    /// first. Descriptions name entities as they are in `flow_state`.
+    pub fn build(command_stack: &[CommandRecord], num_commands_applied: usize, history_events: &[HistoryEvent], flow_state: &FlowState, filter: &AuditFilter) -> Self {
+        let commands = command_stack.iter().enumerate().rev()
+            .map(|(index, record)| AuditEntry {
+                index: Some(index),
+                timestamp: record.redo_command.timestamp,
+                author: record.author.clone(),
+                description: record.redo_command.details.describe(flow_state),
+                subjects: record.redo_command.details.subjects(),
+                applied: index < num_commands_applied,
+            });
+        let events = history_events.iter().rev()
+            .map(|event| AuditEntry {
+                index: None,
+                timestamp: event.timestamp,
+                author: event.author.clone(),
+                description: event.kind.describe(flow_state),
+                subjects: event.kind.subjects(),
+                applied: true,
+            });
+        let mut entries: Vec<AuditEntry> = commands.chain(events)
+            .filter(|entry| filter.matches(entry))
+            .collect();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.timestamp));
+        AuditLog { entries }
+    }
+
+    /// Everyone who authored a command of `command_stack` or one of the `history_events`, sorted.
+    pub fn authors(command_stack: &[CommandRecord], history_events: &[HistoryEvent]) -> Vec<String> {
+        let mut authors: Vec<String> = command_stack.iter()
+            .filter_map(|record| record.author.clone())
+            .chain(history_events.iter().filter_map(|event| event.author.clone()))
+            .collect();
+        authors.sort();
+        authors.dedup();
+        authors
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = csv_row(["Index", "Timestamp", "Author", "Command", "Entities", "Applied"]);
+        for entry in &self.entries {
+            let subjects = entry.subjects.iter()
+                .map(|(kind, key)| format!("{kind} {key}"))
+                .collect::<Vec<_>>()
+                .join("; ");
+            csv.push_str(&csv_row([
+                entry.index.map(|index| index.to_string()).unwrap_or_default(),
+                entry.timestamp.to_rfc3339(),
+                entry.author.clone().unwrap_or_default(),
+                entry.description.clone(),
+                subjects,
+                entry.applied.to_string(),
+            ]));
+        }
+        csv
+    }
+
+    pub fn export_csv(&self, filename: &str) -> FlowResult<()> {
+        std::fs::write(filename, self.to_csv()).map_err(|e| FlowError::io(filename, e))
+    }
+}
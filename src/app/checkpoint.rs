@@ -0,0 +1,104 @@
+use crate::app::*;
+use serde::{Deserialize, Serialize};
+
+/// A checkpoint is taken every `CHECKPOINT_INTERVAL` commands of the command stack.
+pub const CHECKPOINT_INTERVAL: usize = 100;
+
+/// Number of most recent checkpoints kept. Older ones are kept only after a power of two of
+/// intervals, so a project holds a number of checkpoints logarithmic in its history.
+pub const RECENT_CHECKPOINTS: usize = 4;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Serialized `FlowState` as it was after replaying the first `command_index` commands.
+/// `commands_hash` covers those commands so a checkpoint is never applied to a history
+/// it was not built from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub command_index: usize,
+    pub commands_hash: u64,
+    pub flow_state: FlowState,
+}
+
+impl Checkpoint {
+    pub fn new(command_index: usize, commands_hash: u64, flow_state: FlowState) -> Self {
+        Self {
+            command_index,
+            commands_hash,
+            flow_state,
+        }
+    }
+
+    pub fn matches(&self, commands: &[Command]) -> bool {
+        self.command_index <= commands.len()
+            && Checkpoint::hash_commands(&commands[..self.command_index]) == self.commands_hash
+    }
+
+    /// Returns the checkpoint with the highest command index that is valid for `commands`.
+    pub fn nearest<'a>(checkpoints: &'a [Checkpoint], commands: &[Command]) -> Option<&'a Checkpoint> {
+        let mut candidates: Vec<&Checkpoint> = checkpoints.iter()
+            .filter(|checkpoint| checkpoint.command_index <= commands.len())
+            .collect();
+        candidates.sort_by_key(|checkpoint| std::cmp::Reverse(checkpoint.command_index));
+        candidates.into_iter().find(|checkpoint| checkpoint.matches(commands))
+    }
+
+    /// Keeps the leading run of checkpoints that are valid for `commands`, hashing the log only once.
+    pub fn retain_valid(mut checkpoints: Vec<Checkpoint>, commands: &[Command]) -> Vec<Checkpoint> {
+        checkpoints.sort_by_key(|checkpoint| checkpoint.command_index);
+        let mut hash = FNV_OFFSET_BASIS;
+        let mut hashed_until = 0;
+        let mut valid = Vec::new();
+        for checkpoint in checkpoints {
+            if checkpoint.command_index > commands.len() {
+                break;
+            }
+            hash = Checkpoint::extend_hash(hash, &commands[hashed_until..checkpoint.command_index]);
+            hashed_until = checkpoint.command_index;
+            if hash != checkpoint.commands_hash {
+                break;
+            }
+            valid.push(checkpoint);
+        }
+        valid
+    }
+
+    /// Drops the checkpoints that are neither among the `RECENT_CHECKPOINTS` latest nor after a
+    /// power of two of intervals.
+    pub fn thin_out(checkpoints: &mut Vec<Checkpoint>) {
+        let Some(recent_index) = checkpoints.len().checked_sub(RECENT_CHECKPOINTS) else {
+            return;
+        };
+        let mut index = 0;
+        checkpoints.retain(|checkpoint| {
+            index += 1;
+            index > recent_index || (checkpoint.command_index / CHECKPOINT_INTERVAL).is_power_of_two()
+        });
+    }
+
+    /// `hash_commands(commands)`, continued from the latest of `checkpoints` within `commands`
+    /// instead of hashing them all. The checkpoints must have been taken of `commands`, as
+    /// those of a project are of its applied commands.
+    pub fn hash_commands_from(checkpoints: &[Checkpoint], commands: &[Command]) -> u64 {
+        let latest = checkpoints.iter()
+            .filter(|checkpoint| checkpoint.command_index <= commands.len())
+            .max_by_key(|checkpoint| checkpoint.command_index);
+        match latest {
+            Some(checkpoint) => Checkpoint::extend_hash(checkpoint.commands_hash, &commands[checkpoint.command_index..]),
+            None => Checkpoint::hash_commands(commands),
+        }
+    }
+
+    pub fn hash_commands(commands: &[Command]) -> u64 {
+        Checkpoint::extend_hash(FNV_OFFSET_BASIS, commands)
+    }
+
+    /// Continues `hash` over `commands`; `extend_hash(hash_commands(a), b)` equals `hash_commands(a ++ b)`.
+    pub fn extend_hash(hash: u64, commands: &[Command]) -> u64 {
+        commands.iter().fold(hash, |hash, command| {
+            let encoded = serde_yaml::to_string(command).unwrap_or_default();
+            encoded.bytes().fold(hash, |hash, byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME))
+        })
+    }
+}
@@ -13,41 +13,47 @@ pub struct Command {
 pub enum CommandDetails {
     Void,
     CreateTeam{
+        id: TeamId,
         name: TeamName,
     },
     RenameTeam{
-        old_name: TeamName,
+        id: TeamId,
         new_name: TeamName,
     },
     DeleteTeam{
-        name: TeamName,
+        id: TeamId,
     },
     CreateResource{
+        id: ResourceId,
         name: ResourceName,
-        team_name: TeamName,
+        team_id: TeamId,
     },
     RenameResource{
-        old_name: ResourceName,
+        id: ResourceId,
         new_name: ResourceName,
     },
     SwitchTeam{
-        resource_name: ResourceName,
-        new_team_name: TeamName,
+        resource_id: ResourceId,
+        new_team_id: TeamId,
     },
     DeleteResource{
-        name: ResourceName,
+        id: ResourceId,
     },
     CreateTask{
         id: TaskId,
         ticket: String,
         title: String,
         duration: TaskDuration,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        estimate: Option<ThreePointEstimate>,
     },
     UpdateTask{
         id: TaskId,
         ticket: String,
         title: String,
         duration: TaskDuration,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        estimate: Option<ThreePointEstimate>,
     },
     DeleteTask{
         id: TaskId,
@@ -66,57 +72,60 @@ pub enum CommandDetails {
     },
     AssignTask{
         task_id: TaskId,
-        resource_name: ResourceName,
+        resource_id: ResourceId,
     },
     UnassignTask{
         task_id: TaskId,
     },
     AddWatcher{
         task_id: TaskId,
-        resource_name: ResourceName,
+        resource_id: ResourceId,
     },
     RemoveWatcher{
         task_id: TaskId,
-        resource_name: ResourceName,
+        resource_id: ResourceId,
     },
     CreateLabel{
-        name: String,
+        id: LabelId,
+        name: LabelName,
     },
     RenameLabel{
-        old_name: String,
-        new_name: String,
+        id: LabelId,
+        new_name: LabelName,
     },
     DeleteLabel{
-        name: String,
+        id: LabelId,
     },
     AddLabelToTask{
         task_id: TaskId,
-        label_name: LabelName,
+        label_id: LabelId,
     },
     RemoveLabelFromTask{
         task_id: TaskId,
-        label_name: LabelName,
+        label_id: LabelId,
     },
+    /// Creates the filter if `id` is not in use yet, otherwise replaces its name, labels and favorite flag.
     CreateModifyFilter{
+        id: FilterId,
         name: FilterName,
-        labels: Vec<LabelName>,
+        labels: Vec<LabelId>,
         is_favorite: bool,
     },
     RenameFilter{
-        old_name: FilterName,
+        id: FilterId,
         new_name: FilterName,
     },
     DeleteFilter{
-        name: FilterName,
+        id: FilterId,
     },
     SetWorklog{
         task_id: TaskId,
         date: NaiveDate,
-        resource_name: ResourceName,
+        resource_id: ResourceId,
         fraction: Fraction,
     },
     SetAbsence{
-        resource_name: ResourceName,
+        resource_id: ResourceId,
         start_date: NaiveDate,
         days: TaskDuration,
     },
@@ -127,6 +136,9 @@ pub enum CommandDetails {
     RemoveMilestone{
         title: String,
     },
+    SetEstimateBasis{
+        basis: EstimateBasis,
+    },
     CompoundCommand{
         commands: Vec<Command>,
     },
@@ -136,4 +148,131 @@ pub enum CommandDetails {
 pub struct CommandRecord {
     pub undo_command: Command,
     pub redo_command: Command,
-}
\ No newline at end of file
+    /// Who invoked the command; unknown for commands recorded before authors were tracked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
+impl CommandDetails {
+    /// One line saying what the command does, with entities named as they are in `flow_state`
+    /// and by id if they no longer exist there.
+    pub fn describe(&self, flow_state: &FlowState) -> String {
+        let team = |id: &TeamId| flow_state.teams.get(id)
+            .map(|team| format!("team {}", team.name))
+            .unwrap_or_else(|| format!("team #{id}"));
+        let resource = |id: &ResourceId| flow_state.resources.get(id)
+            .map(|resource| resource.name.clone())
+            .unwrap_or_else(|| format!("resource #{id}"));
+        let task = |id: &TaskId| flow_state.tasks.get(id)
+            .map(|task| task.ticket.clone())
+            .unwrap_or_else(|| format!("task #{id}"));
+        let label = |id: &LabelId| flow_state.labels.get(id)
+            .map(|label| format!("label {}", label.name))
+            .unwrap_or_else(|| format!("label #{id}"));
+        let filter = |id: &FilterId| flow_state.filters.get(id)
+            .map(|filter| format!("filter {}", filter.name))
+            .unwrap_or_else(|| format!("filter #{id}"));
+        let range = |estimate: &Option<ThreePointEstimate>| estimate
+            .map(|estimate| format!(" ({} to {})", estimate.optimistic, estimate.pessimistic))
+            .unwrap_or_default();
+
+        match self {
+            CommandDetails::Void => "Nothing".to_string(),
+            CommandDetails::CreateTeam { name, .. } => format!("Create team {name}"),
+            CommandDetails::RenameTeam { id, new_name } => format!("Rename {} to {new_name}", team(id)),
+            CommandDetails::DeleteTeam { id } => format!("Delete {}", team(id)),
+            CommandDetails::CreateResource { name, team_id, .. } => format!("Create resource {name} in {}", team(team_id)),
+            CommandDetails::RenameResource { id, new_name } => format!("Rename {} to {new_name}", resource(id)),
+            CommandDetails::SwitchTeam { resource_id, new_team_id } => format!("Move {} to {}", resource(resource_id), team(new_team_id)),
+            CommandDetails::DeleteResource { id } => format!("Delete {}", resource(id)),
+            CommandDetails::CreateTask { ticket, title, duration, estimate, .. } => {
+                format!("Create task {ticket} '{title}' of {duration}{}", range(estimate))
+            }
+            CommandDetails::UpdateTask { id, ticket, title, duration, estimate } => {
+                format!("Update {} to {ticket} '{title}' of {duration}{}", task(id), range(estimate))
+            }
+            CommandDetails::DeleteTask { id } => format!("Delete {}", task(id)),
+            CommandDetails::PrioritizeTask { task_id, to_top } => {
+                format!("Prioritize {}{}", task(task_id), if *to_top { " to the top" } else { "" })
+            }
+            CommandDetails::DeprioritizeTask { task_id, to_bottom } => {
+                format!("Deprioritize {}{}", task(task_id), if *to_bottom { " to the bottom" } else { "" })
+            }
+            CommandDetails::ChangeTaskPriority { task_id, delta } => format!("Move {} by {delta} in its queue", task(task_id)),
+            CommandDetails::AssignTask { task_id, resource_id } => format!("Assign {} to {}", task(task_id), resource(resource_id)),
+            CommandDetails::UnassignTask { task_id } => format!("Unassign {}", task(task_id)),
+            CommandDetails::AddWatcher { task_id, resource_id } => format!("Add {} as watcher of {}", resource(resource_id), task(task_id)),
+            CommandDetails::RemoveWatcher { task_id, resource_id } => format!("Remove {} as watcher of {}", resource(resource_id), task(task_id)),
+            CommandDetails::CreateLabel { name, .. } => format!("Create label {name}"),
+            CommandDetails::RenameLabel { id, new_name } => format!("Rename {} to {new_name}", label(id)),
+            CommandDetails::DeleteLabel { id } => format!("Delete {}", label(id)),
+            CommandDetails::AddLabelToTask { task_id, label_id } => format!("Add {} to {}", label(label_id), task(task_id)),
+            CommandDetails::RemoveLabelFromTask { task_id, label_id } => format!("Remove {} from {}", label(label_id), task(task_id)),
+            CommandDetails::CreateModifyFilter { id, name, .. } => {
+                if flow_state.filters.contains_key(id) {
+                    format!("Modify {}", filter(id))
+                } else {
+                    format!("Create filter {name}")
+                }
+            }
+            CommandDetails::RenameFilter { id, new_name } => format!("Rename {} to {new_name}", filter(id)),
+            CommandDetails::DeleteFilter { id } => format!("Delete {}", filter(id)),
+            CommandDetails::SetWorklog { task_id, date, resource_id, fraction } => {
+                format!("Log {:.2} days of {} on {} for {date}", *fraction as f32 / 100.0, resource(resource_id), task(task_id))
+            }
+            CommandDetails::SetAbsence { resource_id, start_date, days } => {
+                format!("Set absence of {} for {days} from {start_date}", resource(resource_id))
+            }
+            CommandDetails::AddMilestone { title, date } => format!("Add milestone {title} on {date}"),
+            CommandDetails::RemoveMilestone { title } => format!("Remove milestone {title}"),
+            CommandDetails::SetEstimateBasis { basis } => format!("Schedule tasks by their {} estimate", basis.name().to_lowercase()),
+            CommandDetails::CompoundCommand { commands } => {
+                let descriptions: Vec<String> = commands.iter().map(|command| command.details.describe(flow_state)).collect();
+                format!("{} commands: {}", commands.len(), descriptions.join("; "))
+            }
+        }
+    }
+
+    /// The entities the command is about, which a later command on the same entities may have
+    /// overridden.
+    pub fn subjects(&self) -> Vec<(EntityKind, EntityKey)> {
+        match self {
+            CommandDetails::Void => Vec::new(),
+            CommandDetails::SetEstimateBasis { .. } => vec![(EntityKind::Project, "estimate basis".into())],
+            CommandDetails::CreateTeam { id, .. }
+            | CommandDetails::RenameTeam { id, .. }
+            | CommandDetails::DeleteTeam { id } => vec![(EntityKind::Team, (*id).into())],
+            CommandDetails::CreateResource { id, .. }
+            | CommandDetails::RenameResource { id, .. }
+            | CommandDetails::DeleteResource { id } => vec![(EntityKind::Resource, (*id).into())],
+            CommandDetails::SwitchTeam { resource_id, .. }
+            | CommandDetails::SetAbsence { resource_id, .. } => vec![(EntityKind::Resource, (*resource_id).into())],
+            CommandDetails::CreateTask { id, .. }
+            | CommandDetails::UpdateTask { id, .. }
+            | CommandDetails::DeleteTask { id } => vec![(EntityKind::Task, (*id).into())],
+            CommandDetails::PrioritizeTask { task_id, .. }
+            | CommandDetails::DeprioritizeTask { task_id, .. }
+            | CommandDetails::ChangeTaskPriority { task_id, .. }
+            | CommandDetails::AssignTask { task_id, .. }
+            | CommandDetails::UnassignTask { task_id }
+            | CommandDetails::AddWatcher { task_id, .. }
+            | CommandDetails::RemoveWatcher { task_id, .. }
+            | CommandDetails::AddLabelToTask { task_id, .. }
+            | CommandDetails::RemoveLabelFromTask { task_id, .. } => vec![(EntityKind::Task, (*task_id).into())],
+            CommandDetails::CreateLabel { id, .. }
+            | CommandDetails::RenameLabel { id, .. }
+            | CommandDetails::DeleteLabel { id } => vec![(EntityKind::Label, (*id).into())],
+            CommandDetails::CreateModifyFilter { id, .. }
+            | CommandDetails::RenameFilter { id, .. }
+            | CommandDetails::DeleteFilter { id } => vec![(EntityKind::Filter, (*id).into())],
+            CommandDetails::SetWorklog { task_id, date, resource_id, .. } => {
+                vec![(EntityKind::Worklog, format!("{task_id}/{resource_id}/{date}").into())]
+            }
+            CommandDetails::AddMilestone { title, .. }
+            | CommandDetails::RemoveMilestone { title } => vec![(EntityKind::Milestone, title.into())],
+            CommandDetails::CompoundCommand { commands } => {
+                commands.iter().flat_map(|command| command.details.subjects()).collect()
+            }
+        }
+    }
+}
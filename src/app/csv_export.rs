@@ -0,0 +1,18 @@
+/// Quotes a CSV field if it contains a separator, a quote or a line break.
+pub fn escape_csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One CSV line of `fields`, including the line break.
+pub fn csv_row<S: AsRef<str>>(fields: impl IntoIterator<Item = S>) -> String {
+    let mut row = fields.into_iter()
+        .map(|field| escape_csv_field(field.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",");
+    row.push('\n');
+    row
+}
@@ -78,4 +78,76 @@ impl TaskDuration {
     pub fn zero() -> Self {
         TaskDuration { days: 0, fraction: 0 }
     }
+}
+
+impl std::fmt::Display for TaskDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{:02} days", self.days, self.fraction)
+    }
+}
+
+/// How long a task may take at best and at worst, its duration being the most likely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreePointEstimate {
+    pub optimistic: TaskDuration,
+    pub pessimistic: TaskDuration,
+}
+
+impl ThreePointEstimate {
+    pub fn contains(&self, likely: TaskDuration) -> bool {
+        self.optimistic <= likely && likely <= self.pessimistic
+    }
+
+    /// Widens the range just enough to contain `likely`, for when only the duration changed.
+    pub fn around(&self, likely: TaskDuration) -> Self {
+        ThreePointEstimate {
+            optimistic: self.optimistic.min(likely),
+            pessimistic: self.pessimistic.max(likely),
+        }
+    }
+
+    /// The weighted mean `(optimistic + 4 * likely + pessimistic) / 6`.
+    pub fn pert_mean(&self, likely: TaskDuration) -> TaskDuration {
+        let fractions = |duration: TaskDuration| duration.days * 100 + duration.fraction as u64;
+        let mean = (fractions(self.optimistic) + 4 * fractions(likely) + fractions(self.pessimistic) + 3) / 6;
+        TaskDuration { days: mean / 100, fraction: (mean % 100) as Fraction }
+    }
+}
+
+/// Which value of their estimates tasks are scheduled by. Tasks without a three-point
+/// estimate are always scheduled by their duration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EstimateBasis {
+    #[default]
+    MostLikely,
+    Optimistic,
+    Pessimistic,
+    PertMean,
+}
+
+impl EstimateBasis {
+    pub const ALL: [EstimateBasis; 4] = [
+        EstimateBasis::MostLikely,
+        EstimateBasis::Optimistic,
+        EstimateBasis::Pessimistic,
+        EstimateBasis::PertMean,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EstimateBasis::MostLikely => "Most likely",
+            EstimateBasis::Optimistic => "Optimistic",
+            EstimateBasis::Pessimistic => "Pessimistic",
+            EstimateBasis::PertMean => "PERT mean",
+        }
+    }
+
+    pub fn duration(&self, likely: TaskDuration, estimate: Option<&ThreePointEstimate>) -> TaskDuration {
+        match (self, estimate) {
+            (_, None) | (EstimateBasis::MostLikely, _) => likely,
+            (EstimateBasis::Optimistic, Some(estimate)) => estimate.optimistic,
+            (EstimateBasis::Pessimistic, Some(estimate)) => estimate.pessimistic,
+            (EstimateBasis::PertMean, Some(estimate)) => estimate.pert_mean(likely),
+        }
+    }
 }
\ No newline at end of file
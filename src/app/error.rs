@@ -0,0 +1,149 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityKind {
+    Team,
+    Resource,
+    Task,
+    Label,
+    Filter,
+    Milestone,
+    Worklog,
+    Command,
+    Project,
+    Scenario,
+    Member,
+}
+
+impl fmt::Display for EntityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            EntityKind::Team => "Team",
+            EntityKind::Resource => "Resource",
+            EntityKind::Task => "Task",
+            EntityKind::Label => "Label",
+            EntityKind::Filter => "Filter",
+            EntityKind::Milestone => "Milestone",
+            EntityKind::Worklog => "Worklog",
+            EntityKind::Command => "Command",
+            EntityKind::Project => "Project",
+            EntityKind::Scenario => "Scenario",
+            EntityKind::Member => "Member",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How the entity an error is about was referred to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntityKey {
+    Id(u64),
+    Name(String),
+}
+
+impl fmt::Display for EntityKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntityKey::Id(id) => write!(f, "#{}", id),
+            EntityKey::Name(name) => write!(f, "'{}'", name),
+        }
+    }
+}
+
+impl From<u64> for EntityKey {
+    fn from(id: u64) -> Self {
+        EntityKey::Id(id)
+    }
+}
+
+impl From<String> for EntityKey {
+    fn from(name: String) -> Self {
+        EntityKey::Name(name)
+    }
+}
+
+impl From<&str> for EntityKey {
+    fn from(name: &str) -> Self {
+        EntityKey::Name(name.to_string())
+    }
+}
+
+impl From<&String> for EntityKey {
+    fn from(name: &String) -> Self {
+        EntityKey::Name(name.clone())
+    }
+}
+
+/// Failure of a command, of the project history or of its storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlowError {
+    /// The referenced entity does not exist.
+    NotFound { kind: EntityKind, key: EntityKey },
+    /// The entity clashes with an existing one, e.g. a duplicate id or name.
+    Conflict { kind: EntityKind, key: EntityKey, reason: String },
+    /// The command is well-formed but would break a rule of the model, e.g. deleting an assigned task.
+    InvariantViolation { kind: EntityKind, key: EntityKey, reason: String },
+    /// Reading or writing the project file failed.
+    Io { path: String, reason: String },
+    /// The project file could not be decoded or migrated.
+    Parse { reason: String },
+    /// The acting user's role does not allow the change.
+    PermissionDenied { user: String, reason: String },
+    /// A user script failed to compile or run.
+    Script { name: String, reason: String },
+    /// A query could not be parsed or evaluated.
+    Query { reason: String },
+}
+
+pub type FlowResult<T> = Result<T, FlowError>;
+
+impl FlowError {
+    pub fn not_found(kind: EntityKind, key: impl Into<EntityKey>) -> Self {
+        FlowError::NotFound { kind, key: key.into() }
+    }
+
+    pub fn conflict(kind: EntityKind, key: impl Into<EntityKey>, reason: impl Into<String>) -> Self {
+        FlowError::Conflict { kind, key: key.into(), reason: reason.into() }
+    }
+
+    pub fn invariant(kind: EntityKind, key: impl Into<EntityKey>, reason: impl Into<String>) -> Self {
+        FlowError::InvariantViolation { kind, key: key.into(), reason: reason.into() }
+    }
+
+    pub fn io(path: impl Into<String>, reason: impl fmt::Display) -> Self {
+        FlowError::Io { path: path.into(), reason: reason.to_string() }
+    }
+
+    pub fn parse(reason: impl Into<String>) -> Self {
+        FlowError::Parse { reason: reason.into() }
+    }
+
+    pub fn permission_denied(user: impl Into<String>, reason: impl Into<String>) -> Self {
+        FlowError::PermissionDenied { user: user.into(), reason: reason.into() }
+    }
+
+    pub fn script(name: impl Into<String>, reason: impl Into<String>) -> Self {
+        FlowError::Script { name: name.into(), reason: reason.into() }
+    }
+
+    pub fn query(reason: impl Into<String>) -> Self {
+        FlowError::Query { reason: reason.into() }
+    }
+}
+
+impl fmt::Display for FlowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlowError::NotFound { kind, key } => write!(f, "{} {} not found", kind, key),
+            FlowError::Conflict { kind, key, reason } => write!(f, "{} {} {}", kind, key, reason),
+            FlowError::InvariantViolation { kind, key, reason } => write!(f, "{} {} {}", kind, key, reason),
+            FlowError::Io { path, reason } => write!(f, "I/O error on {}: {}", path, reason),
+            FlowError::Parse { reason } => write!(f, "{}", reason),
+            FlowError::PermissionDenied { user, reason } => write!(f, "Permission denied: {} {}", user, reason),
+            FlowError::Script { name, reason } => write!(f, "Script '{}' failed: {}", name, reason),
+            FlowError::Query { reason } => write!(f, "Invalid query: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for FlowError {}
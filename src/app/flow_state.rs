@@ -1,8 +1,9 @@
 use crate::app::*;
 use chrono::{NaiveDate, DateTime, Utc};
-use std::collections::{BTreeMap, HashMap, BTreeSet};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FlowState {
     pub teams: BTreeMap<TeamId, Team>,
     pub resources: BTreeMap<ResourceId, Resource>,
@@ -11,6 +12,9 @@ pub struct FlowState {
     pub filters: BTreeMap<FilterId, Filter>,
     pub worklogs: HashMap<TaskId, HashMap<ResourceId, HashMap<NaiveDate, Worklog>>>,
     pub milestones: Vec<Milestone>,
+    #[serde(default)]
+    pub estimate_basis: EstimateBasis,
+    #[serde(skip)]
     pub flow_state_cache: FlowStateCache,
 
     next_team_id: TeamId,
@@ -30,6 +34,7 @@ impl FlowState {
             filters: BTreeMap::new(),
             worklogs: HashMap::new(),
             milestones: Vec::new(),
+            estimate_basis: EstimateBasis::default(),
             flow_state_cache: FlowStateCache::new(),
 
             next_team_id: 1,
@@ -43,9 +48,17 @@ impl FlowState {
         flow_state
     }
 
-    pub fn from_commands(commands: &Vec<Command>, date: NaiveDate) -> Result<Self, String> {
-        let mut flow_state = FlowState::new();
-        for command in commands {
+    pub fn from_commands(commands: &[Command], date: NaiveDate) -> FlowResult<Self> {
+        FlowState::from_checkpoints(commands, &[], date)
+    }
+
+    /// Replays `commands` starting from the nearest valid checkpoint instead of from scratch.
+    pub fn from_checkpoints(commands: &[Command], checkpoints: &[Checkpoint], date: NaiveDate) -> FlowResult<Self> {
+        let (mut flow_state, start_index) = match Checkpoint::nearest(checkpoints, commands) {
+            Some(checkpoint) => (checkpoint.flow_state.clone(), checkpoint.command_index),
+            None => (FlowState::new(), 0),
+        };
+        for command in &commands[start_index..] {
             flow_state.execute_command_and_generate_inverse(command.clone())?;
         }
         flow_state.rebuild_cache(date);
@@ -53,209 +66,171 @@ impl FlowState {
         Ok(flow_state)
     }
 
-    pub fn execute_command_and_generate_inverse(&mut self, command: Command) -> Result<Command, String> {
+    pub fn execute_command_and_generate_inverse(&mut self, command: Command) -> FlowResult<Command> {
+        self.mark_cache_dirty(&command.details);
         let timestamp = command.timestamp;
         match command.details {
             CommandDetails::Void => Ok(Command{timestamp, details: CommandDetails::Void}),
-            CommandDetails::CreateTeam { name} => {
+            CommandDetails::CreateTeam { id, name } => {
+                if self.teams.contains_key(&id) {
+                    return Err(FlowError::conflict(EntityKind::Team, id, "already exists"));
+                }
                 if self.teams.values().any(|team| team.name == name) {
-                    return Err(format!("A team with the name '{}' already exists", name));
+                    return Err(FlowError::conflict(EntityKind::Team, name, "already exists"));
                 }
 
-                let team_id = self.next_team_id();
-                self.teams.insert(team_id, Team::new(timestamp, name.clone()));
-                Ok(Command {timestamp, details: CommandDetails::DeleteTeam { name }})
+                self.next_team_id = self.next_team_id.max(id + 1);
+                self.teams.insert(id, Team::new(timestamp, name));
+                Ok(Command {timestamp, details: CommandDetails::DeleteTeam { id }})
             }
-            CommandDetails::RenameTeam { old_name, new_name } => {
-                let team_id = self.teams.iter()
-                    .find(|(_, team)| team.name == old_name)
-                    .map(|(id, _)| *id);
-
-                if team_id.is_none() {
-                    return Err(format!("No team found with the name '{}'", old_name));
-                }
-
-                let team_id = team_id.unwrap();
-                if self.teams.values().any(|team| team.name == new_name) {
-                    return Err(format!("A team with the name '{}' already exists", new_name));
+            CommandDetails::RenameTeam { id, new_name } => {
+                if self.teams.iter().any(|(team_id, team)| *team_id != id && team.name == new_name) {
+                    return Err(FlowError::conflict(EntityKind::Team, new_name, "already exists"));
                 }
-                if let Some(team) = self.teams.get_mut(&team_id) {
-                    team.name = new_name.clone();
+                if let Some(team) = self.teams.get_mut(&id) {
+                    let old_name = std::mem::replace(&mut team.name, new_name);
+                    Ok(Command {timestamp, details: CommandDetails::RenameTeam { id, new_name: old_name }})
+                } else {
+                    Err(FlowError::not_found(EntityKind::Team, id))
                 }
-                Ok(Command {timestamp, details: CommandDetails::RenameTeam { old_name: new_name, new_name: old_name }})
             }
-            CommandDetails::DeleteTeam { name } => {
-                let team_id = self.teams.iter()
-                    .find(|(_, team)| team.name == name)
-                    .map(|(id, _)| *id);
-
-                if let Some(team_id) = team_id {
-                    self.teams.remove(&team_id);
+            CommandDetails::DeleteTeam { id } => {
+                if let Some(team) = self.teams.remove(&id) {
+                    Ok(Command { timestamp, details: CommandDetails::CreateTeam { id, name: team.name } })
                 } else {
-                    return Err(format!("No team found with the name '{}'", name));
+                    Err(FlowError::not_found(EntityKind::Team, id))
                 }
-                Ok(Command { timestamp, details: CommandDetails::CreateTeam { name } })
             }
-            CommandDetails::CreateResource { name, team_name } => {
+            CommandDetails::CreateResource { id, name, team_id } => {
+                if self.resources.contains_key(&id) {
+                    return Err(FlowError::conflict(EntityKind::Resource, id, "already exists"));
+                }
                 if self.resources.values().any(|res| res.name == name) {
-                    return Err(format!("A resource with the name '{}' already exists", name));
+                    return Err(FlowError::conflict(EntityKind::Resource, name, "already exists"));
                 }
 
-                let team_id = self.teams.iter()
-                    .find(|(_, team)| team.name == team_name)
-                    .map(|(id, _)| *id);
-
-                if team_id.is_none() {
-                    return Err(format!("No team found with the name '{}'", team_name));
+                if let Some(team) = self.teams.get_mut(&team_id) {
+                    team.resources.insert(id);
+                } else {
+                    return Err(FlowError::not_found(EntityKind::Team, team_id));
                 }
-
-                let resource_id = self.next_resource_id();
-                self.resources.insert(resource_id, Resource::new(timestamp, name.clone(), team_id.unwrap()));
-
-                if let Some(team) = self.teams.get_mut(&team_id.unwrap()) {
-                    team.resources.insert(resource_id);
+                self.next_resource_id = self.next_resource_id.max(id + 1);
+                self.resources.insert(id, Resource::new(timestamp, name, team_id));
+                Ok(Command { timestamp, details: CommandDetails::DeleteResource { id } })
+            }
+            CommandDetails::RenameResource { id, new_name } => {
+                if self.resources.iter().any(|(resource_id, res)| *resource_id != id && res.name == new_name) {
+                    return Err(FlowError::conflict(EntityKind::Resource, new_name, "already exists"));
+                }
+                if let Some(resource) = self.resources.get_mut(&id) {
+                    let old_name = std::mem::replace(&mut resource.name, new_name);
+                    Ok(Command { timestamp, details: CommandDetails::RenameResource { id, new_name: old_name } })
+                } else {
+                    Err(FlowError::not_found(EntityKind::Resource, id))
                 }
-                Ok(Command { timestamp, details: CommandDetails::DeleteResource { name } })
             }
-            CommandDetails::RenameResource { old_name, new_name } => {
-                let resource_id = self.resources.iter()
-                    .find(|(_, res)| res.name == old_name)
-                    .map(|(id, _)| *id);
-
-                if resource_id.is_none() {
-                    return Err(format!("No resource found with the name '{}'", old_name));
+            CommandDetails::SwitchTeam { resource_id, new_team_id } => {
+                if !self.teams.contains_key(&new_team_id) {
+                    return Err(FlowError::not_found(EntityKind::Team, new_team_id));
                 }
+                let current_team_id = self.resources.get(&resource_id)
+                    .map(|res| res.team_id)
+                    .ok_or_else(|| FlowError::not_found(EntityKind::Resource, resource_id))?;
 
-                let resource_id = resource_id.unwrap();
-                if self.resources.values().any(|res| res.name == new_name) {
-                    return Err(format!("A resource with the name '{}' already exists", new_name));
+                if let Some(current_team) = self.teams.get_mut(&current_team_id) {
+                    current_team.resources.remove(&resource_id);
+                }
+                if let Some(new_team) = self.teams.get_mut(&new_team_id) {
+                    new_team.resources.insert(resource_id);
                 }
                 if let Some(resource) = self.resources.get_mut(&resource_id) {
-                    resource.name = new_name.clone();
+                    resource.team_id = new_team_id;
                 }
-                Ok(Command { timestamp, details: CommandDetails::RenameResource { old_name: new_name, new_name: old_name } })
+                Ok(Command { timestamp, details: CommandDetails::SwitchTeam { resource_id, new_team_id: current_team_id } })
             }
-            CommandDetails::SwitchTeam { resource_name, new_team_name } => {
-                let resource_id = self.resources.iter()
-                    .find(|(_, res)| res.name == resource_name)
-                    .map(|(id, _)| *id);
-
-                if resource_id.is_none() {
-                    return Err(format!("No resource found with the name '{}'", resource_name));
-                }
-
-                let resource_id = resource_id.unwrap();
-
-                let new_team_id = self.teams.iter()
-                    .find(|(_, team)| team.name == new_team_name)
-                    .map(|(id, _)| *id);
-
-                if new_team_id.is_none() {
-                    return Err(format!("No team found with the name '{}'", new_team_name));
-                }
-
-                let new_team_id = new_team_id.unwrap();
-
-                let current_team_id = self.resources.get(&resource_id)
-                    .map(|res| res.team_id);
-
-                if let Some(current_team_id) = current_team_id {
-                    let old_team_name = self.teams.get(&current_team_id)
-                        .map(|team| team.name.clone())
-                        .ok_or_else(|| "Current team not found".to_string())?;
-                    
-                    if let Some(current_team) = self.teams.get_mut(&current_team_id) {
-                        current_team.resources.remove(&resource_id);
+            CommandDetails::DeleteResource { id } => {
+                if let Some(resource) = self.resources.get(&id) {
+                    if !resource.assigned_tasks.is_empty() {
+                        return Err(FlowError::invariant(EntityKind::Resource, id, "is assigned tasks and cannot be deleted"));
                     }
-                    if let Some(new_team) = self.teams.get_mut(&new_team_id) {
-                        new_team.resources.insert(resource_id);
+                    if !resource.watched_tasks.is_empty() {
+                        return Err(FlowError::invariant(EntityKind::Resource, id, "is watching tasks and cannot be deleted"));
                     }
-    
-                    if let Some(resource) = self.resources.get_mut(&resource_id) {
-                        resource.team_id = new_team_id;
+                    let has_worklogs = self.worklogs.values()
+                        .any(|resource_map| resource_map.contains_key(&id));
+                    if has_worklogs {
+                        return Err(FlowError::invariant(EntityKind::Resource, id, "has worklogs and cannot be deleted"));
                     }
-                    Ok(Command { timestamp, details: CommandDetails::SwitchTeam { resource_name, new_team_name: old_team_name } })
                 } else {
-                    Err("Resource has no current team".to_string())
+                    return Err(FlowError::not_found(EntityKind::Resource, id));
                 }
-            }
-            CommandDetails::DeleteResource { name } => {
-                let resource_id = self.resources.iter()
-                    .find(|(_, res)| res.name == name)
-                    .map(|(id, _)| *id);
-
-                if let Some(resource_id) = resource_id {
-                    if let Some(resource) = self.resources.get(&resource_id) {
-                        if !resource.assigned_tasks.is_empty() {
-                            return Err(format!("Resource '{}' is assigned tasks and cannot be deleted", name));
-                        }
-                        if !resource.watched_tasks.is_empty() {
-                            return Err(format!("Resource '{}' is watching tasks and cannot be deleted", name));
-                        }
-                        let has_worklogs = self.worklogs.values()
-                            .any(|resource_map| resource_map.contains_key(&resource_id));
-                        if has_worklogs {
-                            return Err(format!("Resource '{}' has worklogs and cannot be deleted", name));
-                        }
-                    }
-                    self.resources.remove(&resource_id)
-                        .and_then(|resource| {
-                            self.teams.get_mut(&resource.team_id)
-                                .map(|team| {
-                                    team.resources.remove(&resource_id);
-                                    Command { timestamp, details: CommandDetails::CreateResource {
-                                        name: resource.name,
-                                        team_name: team.name.clone()
-                                    }}
-                                })
-                        })
-                        .ok_or_else(|| format!("Failed to create resource '{}'", name))
-                } else {
-                    return Err(format!("No resource found with the name '{}'", name));
+                let resource = self.resources.remove(&id).unwrap();
+                if let Some(team) = self.teams.get_mut(&resource.team_id) {
+                    team.resources.remove(&id);
                 }
+                Ok(Command { timestamp, details: CommandDetails::CreateResource {
+                    id,
+                    name: resource.name,
+                    team_id: resource.team_id,
+                }})
             },
-            CommandDetails::CreateTask { id, ticket, title, duration } => {
-                let task = Task::new(timestamp, id, ticket, title, duration);
+            CommandDetails::CreateTask { id, ticket, title, duration, estimate } => {
+                if self.tasks.contains_key(&id) {
+                    return Err(FlowError::conflict(EntityKind::Task, id, "already exists"));
+                }
+                if estimate.is_some_and(|estimate| !estimate.contains(duration)) {
+                    return Err(FlowError::invariant(EntityKind::Task, id, "has a duration outside of its estimated range"));
+                }
+                let mut task = Task::new(timestamp, id, ticket, title, duration);
+                task.estimate = estimate;
                 self.tasks.insert(id, task);
+                self.next_task_id = self.next_task_id.max(id + 1);
                 Ok(Command { timestamp, details: CommandDetails::DeleteTask { id } })
             }
-            CommandDetails::UpdateTask { id, ticket, title, duration } => {
+            CommandDetails::UpdateTask { id, ticket, title, duration, estimate } => {
+                if estimate.is_some_and(|estimate| !estimate.contains(duration)) {
+                    return Err(FlowError::invariant(EntityKind::Task, id, "has a duration outside of its estimated range"));
+                }
                 if let Some(task) = self.tasks.get_mut(&id) {
                     let original_ticket = task.ticket.clone();
                     let original_title = task.title.clone();
                     let original_duration = task.duration.clone();
+                    let original_estimate = task.estimate;
 
                     task.ticket = ticket;
                     task.title = title;
                     task.duration = duration;
+                    task.estimate = estimate;
 
                     Ok(Command { timestamp, details: CommandDetails::UpdateTask {
                         id,
                         ticket: original_ticket,
                         title: original_title,
                         duration: original_duration,
+                        estimate: original_estimate,
                     }})
                 } else {
-                    return Err(format!("Task with id {} not found", id));
+                    return Err(FlowError::not_found(EntityKind::Task, id));
                 }
             }
             CommandDetails::DeleteTask { id } => {
                 if let Some(task) = self.tasks.get(&id) {
                     if task.assignee.is_some() {
-                        return Err(format!("Task with id {} is assigned to a resource and cannot be deleted", id));
+                        return Err(FlowError::invariant(EntityKind::Task, id, "is assigned to a resource and cannot be deleted"));
                     }
                     if !task.watchers.is_empty() {
-                        return Err(format!("Task with id {} has watchers and cannot be deleted", id));
+                        return Err(FlowError::invariant(EntityKind::Task, id, "has watchers and cannot be deleted"));
                     }
                     let has_worklogs = self.worklogs.contains_key(&id);
                     if has_worklogs {
-                        return Err(format!("Task with id {} has worklogs and cannot be deleted", id));
+                        return Err(FlowError::invariant(EntityKind::Task, id, "has worklogs and cannot be deleted"));
                     }
                     
                     // Clone the task data before removing it
                     let ticket = task.ticket.clone();
                     let title = task.title.clone();
                     let duration = task.duration.clone();
+                    let estimate = task.estimate;
                     
                     self.tasks.remove(&id);
                     Ok(Command { timestamp, details: CommandDetails::CreateTask {
@@ -263,9 +238,10 @@ impl FlowState {
                         ticket,
                         title,
                         duration,
+                        estimate,
                     }})
                 } else {
-                    return Err(format!("Task with id {} not found", id));
+                    return Err(FlowError::not_found(EntityKind::Task, id));
                 }
             }
             CommandDetails::PrioritizeTask { task_id, to_top } => {
@@ -280,16 +256,16 @@ impl FlowState {
                                     self.execute_command_and_generate_inverse(Command { timestamp, details: CommandDetails::ChangeTaskPriority { task_id, delta: if pos > 0 {-1} else {0} } })
                                 }
                             } else {
-                                Err(format!("Task with id {} is not assigned to any resource", task_id))
+                                Err(FlowError::invariant(EntityKind::Task, task_id, "is not assigned to any resource"))
                             }
                         } else {
-                            Err(format!("Resource with id {} not found", assignee_id))
+                            Err(FlowError::not_found(EntityKind::Resource, assignee_id))
                         }
                     } else {
-                        Err(format!("Task with id {} is not assigned to any resource", task_id))
+                        Err(FlowError::invariant(EntityKind::Task, task_id, "is not assigned to any resource"))
                     }
                 } else {
-                    Err(format!("Task with id {} not found", task_id))
+                    Err(FlowError::not_found(EntityKind::Task, task_id))
                 }
             }
             CommandDetails::DeprioritizeTask { task_id, to_bottom} => {
@@ -307,16 +283,16 @@ impl FlowState {
                                     self.execute_command_and_generate_inverse(Command { timestamp, details: CommandDetails::ChangeTaskPriority { task_id, delta: 0 } })
                                 }
                             } else {
-                                Err(format!("Task with id {} is not assigned to any resource", task_id))
+                                Err(FlowError::invariant(EntityKind::Task, task_id, "is not assigned to any resource"))
                             }
                         } else {
-                            Err(format!("Resource with id {} not found", assignee_id))
+                            Err(FlowError::not_found(EntityKind::Resource, assignee_id))
                         }
                     } else {
-                        Err(format!("Task with id {} is not assigned to any resource", task_id))
+                        Err(FlowError::invariant(EntityKind::Task, task_id, "is not assigned to any resource"))
                     }
                 } else {
-                    Err(format!("Task with id {} not found", task_id))
+                    Err(FlowError::not_found(EntityKind::Task, task_id))
                 }
             }
             CommandDetails::ChangeTaskPriority { task_id, delta } => {
@@ -327,48 +303,39 @@ impl FlowState {
                             if let Some(pos) = pos {
                                 let new_pos = pos as i32 + delta;
                                 if new_pos < 0 || new_pos >= resource.assigned_tasks.len() as i32 {
-                                    return Err(format!("New position {} is out of bounds for task list of length {}", new_pos, resource.assigned_tasks.len()));
+                                    return Err(FlowError::invariant(EntityKind::Task, task_id, format!("cannot move to position {} of a task list of length {}", new_pos, resource.assigned_tasks.len())));
                                 }
                                 let new_pos = new_pos as usize;
                                 resource.assigned_tasks.remove(pos);
                                 resource.assigned_tasks.insert(new_pos, task_id);
                                 Ok(Command { timestamp, details: CommandDetails::ChangeTaskPriority { task_id, delta: -delta } })
                             } else {
-                                Err(format!("Task with id {} is not assigned to any resource", task_id))
+                                Err(FlowError::invariant(EntityKind::Task, task_id, "is not assigned to any resource"))
                             }
                         } else {
-                            Err(format!("Resource with id {} not found", assignee_id))
+                            Err(FlowError::not_found(EntityKind::Resource, assignee_id))
                         }
                     } else {
-                        Err(format!("Task with id {} is not assigned to any resource", task_id))
+                        Err(FlowError::invariant(EntityKind::Task, task_id, "is not assigned to any resource"))
                     }
                 } else {
-                    Err(format!("Task with id {} not found", task_id))
+                    Err(FlowError::not_found(EntityKind::Task, task_id))
                 }
             }
-            CommandDetails::AssignTask { task_id, resource_name } => {
-                let resource_id = self.resources.iter()
-                    .find(|(_, res)| res.name == resource_name)
-                    .map(|(id, _)| *id);
-
-                if resource_id.is_none() {
-                    return Err(format!("No resource found with the name '{}'", resource_name));
+            CommandDetails::AssignTask { task_id, resource_id } => {
+                if !self.resources.contains_key(&resource_id) {
+                    return Err(FlowError::not_found(EntityKind::Resource, resource_id));
                 }
 
-                let resource_id = resource_id.unwrap();
-
                 if let Some(task) = self.tasks.get_mut(&task_id) {
                     match task.assignee {
                         Some(old_assignee_id) => {
                             if old_assignee_id == resource_id {
-                                return Err(format!(
-                                    "Task with id {} is already assigned to '{}'",
-                                    task_id, resource_name
+                                return Err(FlowError::conflict(
+                                    EntityKind::Task, task_id,
+                                    format!("is already assigned to '{}'", self.resources[&resource_id].name),
                                 ));
                             }
-                            let old_assignee_name = self.resources.get(&old_assignee_id)
-                                .map(|res| res.name.clone())
-                                .ok_or_else(|| format!("Old assignee resource with id {} not found", old_assignee_id))?;
                             if let Some(old_resource) = self.resources.get_mut(&old_assignee_id) {
                                 old_resource.assigned_tasks.retain(|&x| x != task_id);
                             }
@@ -376,7 +343,7 @@ impl FlowState {
                             if let Some(resource) = self.resources.get_mut(&resource_id) {
                                 resource.assigned_tasks.insert(0, task_id);
                             }
-                            Ok(Command { timestamp, details: CommandDetails::AssignTask { task_id, resource_name: old_assignee_name } })
+                            Ok(Command { timestamp, details: CommandDetails::AssignTask { task_id, resource_id: old_assignee_id } })
                         }
                         None => {
                             task.assignee = Some(resource_id);
@@ -387,206 +354,163 @@ impl FlowState {
                         }
                     }
                 } else {
-                    return Err(format!("Task with id {} not found", task_id));
+                    return Err(FlowError::not_found(EntityKind::Task, task_id));
                 }
             }
             CommandDetails::UnassignTask { task_id } => {
                 if let Some(task) = self.tasks.get_mut(&task_id) {
                     if let Some(old_assignee_id) = task.assignee.clone() {
-                        if let Some(old_assignee_name) = self.resources.get(&old_assignee_id)
-                            .map(|res| res.name.clone()) {
+                        if self.resources.contains_key(&old_assignee_id) {
                                 task.assignee = None;
                                 if let Some(resource) = self.resources.get_mut(&old_assignee_id) {
                                     resource.assigned_tasks.retain(|&x| x != task_id);
                                 }
-                                Ok(Command { timestamp, details: CommandDetails::AssignTask { task_id, resource_name: old_assignee_name } })
+                                Ok(Command { timestamp, details: CommandDetails::AssignTask { task_id, resource_id: old_assignee_id } })
                             } else {
-                                Err(format!("Resource with id {} not found", old_assignee_id))
+                                Err(FlowError::not_found(EntityKind::Resource, old_assignee_id))
                             }
                     } else {
-                        Err(format!("Task with id {} is not assigned to any resource", task_id))
+                        Err(FlowError::invariant(EntityKind::Task, task_id, "is not assigned to any resource"))
                     }
                 } else {
-                    return Err(format!("Task with id {} not found", task_id));
+                    return Err(FlowError::not_found(EntityKind::Task, task_id));
                 }
             }
-            CommandDetails::AddWatcher { task_id, resource_name } => {
-                let resource_id = self.resources.iter()
-                    .find(|(_, res)| res.name == resource_name)
-                    .map(|(id, _)| *id);
-
-                if resource_id.is_none() {
-                    return Err(format!("No resource found with the name '{}'", resource_name));
+            CommandDetails::AddWatcher { task_id, resource_id } => {
+                if !self.resources.contains_key(&resource_id) {
+                    return Err(FlowError::not_found(EntityKind::Resource, resource_id));
                 }
 
-                let resource_id = resource_id.unwrap();
-
                 if let Some(task) = self.tasks.get_mut(&task_id) {
                     task.watchers.insert(resource_id);
                     if let Some(resource) = self.resources.get_mut(&resource_id) {
                         resource.watched_tasks.insert(0, task_id);
                     }
-                    Ok(Command { timestamp, details: CommandDetails::RemoveWatcher { task_id, resource_name } })
+                    Ok(Command { timestamp, details: CommandDetails::RemoveWatcher { task_id, resource_id } })
                 } else {
-                    return Err(format!("Task with id {} not found", task_id));
+                    return Err(FlowError::not_found(EntityKind::Task, task_id));
                 }
             }
-            CommandDetails::RemoveWatcher { task_id, resource_name } => {
-                let resource_id = self.resources.iter()
-                    .find(|(_, res)| res.name == resource_name)
-                    .map(|(id, _)| *id);
-
-                if resource_id.is_none() {
-                    return Err(format!("No resource found with the name '{}'", resource_name));
+            CommandDetails::RemoveWatcher { task_id, resource_id } => {
+                if !self.resources.contains_key(&resource_id) {
+                    return Err(FlowError::not_found(EntityKind::Resource, resource_id));
                 }
 
-                let resource_id = resource_id.unwrap();
-
                 if let Some(task) = self.tasks.get_mut(&task_id) {
                     task.watchers.remove(&resource_id);
                     if let Some(resource) = self.resources.get_mut(&resource_id) {
                         resource.watched_tasks.retain(|&x| x != task_id);
                     }
-                    Ok(Command { timestamp, details: CommandDetails::AddWatcher { task_id, resource_name } })
+                    Ok(Command { timestamp, details: CommandDetails::AddWatcher { task_id, resource_id } })
                 } else {
-                    return Err(format!("Task with id {} not found", task_id));
+                    return Err(FlowError::not_found(EntityKind::Task, task_id));
                 }
             }
-            CommandDetails::CreateLabel { name } => {
+            CommandDetails::CreateLabel { id, name } => {
+                if self.labels.contains_key(&id) {
+                    return Err(FlowError::conflict(EntityKind::Label, id, "already exists"));
+                }
                 if self.labels.values().any(|label| label.name == name) {
-                    return Err(format!("A label with the name '{}' already exists", name));
+                    return Err(FlowError::conflict(EntityKind::Label, name, "already exists"));
                 }
 
-                let label_id = self.next_label_id();
-                self.labels.insert(label_id, Label { name: name.clone() });
-
-                Ok(Command { timestamp, details: CommandDetails::DeleteLabel { name } })
+                self.next_label_id = self.next_label_id.max(id + 1);
+                self.labels.insert(id, Label { name });
+                Ok(Command { timestamp, details: CommandDetails::DeleteLabel { id } })
             }
-            CommandDetails::RenameLabel { old_name, new_name } => {
-                let label_id = self.labels.iter()
-                    .find(|(_, label)| label.name == old_name)
-                    .map(|(id, _)| *id);
-                if label_id.is_none() {
-                    return Err(format!("No label found with the name '{}'", old_name));
+            CommandDetails::RenameLabel { id, new_name } => {
+                if self.labels.iter().any(|(label_id, label)| *label_id != id && label.name == new_name) {
+                    return Err(FlowError::conflict(EntityKind::Label, new_name, "already exists"));
+                }
+                if let Some(label) = self.labels.get_mut(&id) {
+                    let old_name = std::mem::replace(&mut label.name, new_name);
+                    Ok(Command { timestamp, details: CommandDetails::RenameLabel { id, new_name: old_name } })
+                } else {
+                    Err(FlowError::not_found(EntityKind::Label, id))
                 }
-                let label_id = label_id.unwrap();
-                self.labels.insert(label_id, Label { name: new_name.clone() });
-                Ok(Command { timestamp, details: CommandDetails::RenameLabel { new_name, old_name } })
             }
-
-            CommandDetails::DeleteLabel { name } => {
-                let label_id = self.labels.iter()
-                    .find(|(_, label)| label.name == name)
-                    .map(|(id, _)| *id);
-                if let Some(label_id) = label_id {
-                    self.labels.remove(&label_id);
-                    Ok(Command { timestamp, details: CommandDetails::CreateLabel { name } })
+            CommandDetails::DeleteLabel { id } => {
+                if let Some(label) = self.labels.remove(&id) {
+                    Ok(Command { timestamp, details: CommandDetails::CreateLabel { id, name: label.name } })
                 } else {
-                    return Err(format!("No label found with the name '{}'", name));
+                    Err(FlowError::not_found(EntityKind::Label, id))
                 }
             }
-            CommandDetails::AddLabelToTask { task_id, label_name } => {
-                let label_id = self.get_label_id(&label_name);
-                if label_id.is_none() {
-                    return Err(format!("No label found with the name '{}'", label_name));
+            CommandDetails::AddLabelToTask { task_id, label_id } => {
+                if !self.labels.contains_key(&label_id) {
+                    return Err(FlowError::not_found(EntityKind::Label, label_id));
                 }
-                let label_id = label_id.unwrap();
 
                 if let Some(task) = self.tasks.get_mut(&task_id) {
                     task.label_ids.insert(label_id);
-                    Ok(Command { timestamp, details: CommandDetails::RemoveLabelFromTask { task_id, label_name } })
+                    Ok(Command { timestamp, details: CommandDetails::RemoveLabelFromTask { task_id, label_id } })
                 } else {
-                    return Err(format!("Task with id {} not found", task_id));
+                    return Err(FlowError::not_found(EntityKind::Task, task_id));
                 }
             }
-            CommandDetails::RemoveLabelFromTask { task_id, label_name } => {
-                let label_id = self.get_label_id(&label_name);
-                if label_id.is_none() {
-                    return Err(format!("No label found with the name '{}'", label_name));
+            CommandDetails::RemoveLabelFromTask { task_id, label_id } => {
+                if !self.labels.contains_key(&label_id) {
+                    return Err(FlowError::not_found(EntityKind::Label, label_id));
                 }
-                let label_id = label_id.unwrap();
 
                 if let Some(task) = self.tasks.get_mut(&task_id) {
                     task.label_ids.remove(&label_id);
-                    Ok(Command { timestamp, details: CommandDetails::AddLabelToTask { task_id, label_name } })
+                    Ok(Command { timestamp, details: CommandDetails::AddLabelToTask { task_id, label_id } })
                 } else {
-                    return Err(format!("Task with id {} not found", task_id));
+                    return Err(FlowError::not_found(EntityKind::Task, task_id));
                 }
             }
-            CommandDetails::CreateModifyFilter { name, labels, is_favorite } => {
-                let existing_filter_id = self.filters.iter()
-                    .find(|(_, filter)| filter.name == name)
-                    .map(|(id, _)| *id);
-
-                let mut label_ids = BTreeSet::new();
-                for label_name in &labels {
-                    if let Some(label_id) = self.get_label_id(label_name) {
-                        label_ids.insert(label_id);
-                    } else {
-                        return Err(format!("Label '{}' not found", label_name));
-                    }
+            CommandDetails::CreateModifyFilter { id, name, labels, is_favorite } => {
+                if self.filters.iter().any(|(filter_id, filter)| *filter_id != id && filter.name == name) {
+                    return Err(FlowError::conflict(EntityKind::Filter, name, "already exists"));
+                }
+                if let Some(label_id) = labels.iter().find(|label_id| !self.labels.contains_key(label_id)) {
+                    return Err(FlowError::not_found(EntityKind::Label, *label_id));
                 }
 
-                if let Some(filter_id) = existing_filter_id {
-                    let old_labels = self.filters[&filter_id].labels.clone();
-                    let old_label_names = old_labels.into_iter()
-                        .filter_map(|id| self.labels.get(&id).map(|label| label.name.clone()))
-                        .collect();
-                    let old_is_favorite = self.filters[&filter_id].is_favorite;
-                    self.filters.insert(filter_id, Filter { name: name.clone(), labels: label_ids, is_favorite });
-                    Ok(Command { timestamp, details: CommandDetails::CreateModifyFilter { name, labels: old_label_names, is_favorite: old_is_favorite } })
+                let filter = Filter { name, labels: labels.into_iter().collect(), is_favorite };
+                if let Some(old_filter) = self.filters.insert(id, filter) {
+                    Ok(Command { timestamp, details: CommandDetails::CreateModifyFilter {
+                        id,
+                        name: old_filter.name,
+                        labels: old_filter.labels.into_iter().collect(),
+                        is_favorite: old_filter.is_favorite,
+                    }})
                 } else {
-                    let filter_id = self.next_filter_id();
-                    self.filters.insert(filter_id, Filter { name: name.clone(), labels: label_ids, is_favorite: false });
-                    Ok(Command { timestamp, details: CommandDetails::DeleteFilter { name } })
-                }
-            }
-            CommandDetails::RenameFilter { old_name, new_name } => {
-                let filter_id = self.filters.iter()
-                    .find(|(_, filter)| filter.name == old_name)
-                    .map(|(id, _)| *id);
-                if filter_id.is_none() {
-                    return Err(format!("No filter found with the name '{}'", old_name));
-                }
-                let filter_id = filter_id.unwrap();
-                if self.filters.values().any(|filter| filter.name == new_name) {
-                    return Err(format!("A filter with the name '{}' already exists", new_name));
-                }
-                if let Some(filter) = self.filters.get_mut(&filter_id) {
-                    filter.name = new_name.clone();
-                }
-                Ok(Command { timestamp, details: CommandDetails::RenameFilter { old_name: new_name, new_name: old_name } })
-            }
-            CommandDetails::DeleteFilter { name } => {
-                let filter_id = self.filters.iter()
-                    .find(|(_, filter)| filter.name == name)
-                    .map(|(id, _)| *id);
-                if let Some(filter_id) = filter_id {
-                    /* labels of the filter */
-                    let filter = self.filters.get(&filter_id).cloned().unwrap();
-                    let label_ids = filter.labels.clone();
-                    let labels = label_ids.into_iter()
-                        .filter_map(|id| self.labels.get(&id).map(|label| label.name.clone()))
-                        .collect();
-                    self.filters.remove(&filter_id);
-                    Ok(Command { timestamp, details: CommandDetails::CreateModifyFilter { name, labels, is_favorite: filter.is_favorite } })
+                    self.next_filter_id = self.next_filter_id.max(id + 1);
+                    Ok(Command { timestamp, details: CommandDetails::DeleteFilter { id } })
+                }
+            }
+            CommandDetails::RenameFilter { id, new_name } => {
+                if self.filters.iter().any(|(filter_id, filter)| *filter_id != id && filter.name == new_name) {
+                    return Err(FlowError::conflict(EntityKind::Filter, new_name, "already exists"));
+                }
+                if let Some(filter) = self.filters.get_mut(&id) {
+                    let old_name = std::mem::replace(&mut filter.name, new_name);
+                    Ok(Command { timestamp, details: CommandDetails::RenameFilter { id, new_name: old_name } })
                 } else {
-                    return Err(format!("No filter found with the name '{}'", name));
+                    Err(FlowError::not_found(EntityKind::Filter, id))
                 }
             }
-            CommandDetails::SetWorklog { task_id, date, resource_name, fraction } => {
-                let resource_id = self.resources.iter()
-                    .find(|(_, res)| res.name == resource_name)
-                    .map(|(id, _)| *id);
-
-                if resource_id.is_none() {
-                    return Err(format!("No resource found with the name '{}'", resource_name));
+            CommandDetails::DeleteFilter { id } => {
+                if let Some(filter) = self.filters.remove(&id) {
+                    Ok(Command { timestamp, details: CommandDetails::CreateModifyFilter {
+                        id,
+                        name: filter.name,
+                        labels: filter.labels.into_iter().collect(),
+                        is_favorite: filter.is_favorite,
+                    }})
+                } else {
+                    Err(FlowError::not_found(EntityKind::Filter, id))
+                }
+            }
+            CommandDetails::SetWorklog { task_id, date, resource_id, fraction } => {
+                if !self.resources.contains_key(&resource_id) {
+                    return Err(FlowError::not_found(EntityKind::Resource, resource_id));
                 }
-
-                let resource_id = resource_id.unwrap();
 
                 if !self.tasks.contains_key(&task_id) {
-                    return Err(format!("Task with id {} not found", task_id));
+                    return Err(FlowError::not_found(EntityKind::Task, task_id));
                 }
 
                 let worklog = Worklog {
@@ -606,7 +530,7 @@ impl FlowState {
 
                 if fraction == 0 {
                     if previous_fraction == 0 {
-                        return Err(format!("No worklog exists for task {} on {} for resource {}", task_id, date, resource_name));
+                        return Err(FlowError::not_found(EntityKind::Worklog, format!("task {} on {} for {}", task_id, date, self.resources[&resource_id].name)));
                     }
                     
                     if let Some(resource_map) = self.worklogs.get_mut(&task_id) {
@@ -632,20 +556,15 @@ impl FlowState {
                 Ok(Command { timestamp, details: CommandDetails::SetWorklog {
                     task_id,
                     date,
-                    resource_name,
+                    resource_id,
                     fraction: previous_fraction,
                 }})
             }
-            CommandDetails::SetAbsence { resource_name, start_date, days } => {
-                let resource_id = self.resources.iter()
-                    .find(|(_, res)| res.name == resource_name)
-                    .map(|(id, _)| *id);
-
-                if resource_id.is_none() {
-                    return Err(format!("No resource found with the name '{}'", resource_name));
+            CommandDetails::SetAbsence { resource_id, start_date, days } => {
+                if !self.resources.contains_key(&resource_id) {
+                    return Err(FlowError::not_found(EntityKind::Resource, resource_id));
                 }
 
-                let resource_id = resource_id.unwrap();
                 let absence = Absence {
                     create_timestamp: timestamp,
                     start_date,
@@ -658,7 +577,7 @@ impl FlowState {
                     }
                 }
                 Ok(Command { timestamp, details: CommandDetails::SetAbsence {
-                    resource_name,
+                    resource_id,
                     start_date,
                     days: if days > TaskDuration::zero() { TaskDuration::zero() } else { days },
                 }})
@@ -676,9 +595,13 @@ impl FlowState {
                     let milestone = self.milestones.remove(pos);
                     Ok(Command { timestamp, details: CommandDetails::AddMilestone { title: milestone.title, date: milestone.date } })
                 } else {
-                    return Err(format!("No milestone found with the title '{}'", title));
+                    return Err(FlowError::not_found(EntityKind::Milestone, title));
                 }
             }
+            CommandDetails::SetEstimateBasis { basis } => {
+                let original_basis = std::mem::replace(&mut self.estimate_basis, basis);
+                Ok(Command { timestamp, details: CommandDetails::SetEstimateBasis { basis: original_basis } })
+            }
             CommandDetails::CompoundCommand { commands } => {
                 let mut flow_state_clone = self.clone();
                 let mut undo_commands = Vec::new();
@@ -693,14 +616,59 @@ impl FlowState {
         }
     }
 
-    pub fn execute_command_generate_inverse_and_rebuild_cache(&mut self, command: Command, date: NaiveDate) -> Result<Command, String> {
+    pub fn execute_command_generate_inverse_and_rebuild_cache(&mut self, command: Command, date: NaiveDate) -> FlowResult<Command> {
         let undo_command = self.execute_command_and_generate_inverse(command)?;
-        self.rebuild_cache(date);
+        self.update_cache(date);
         Ok(undo_command)
     }
 
     pub fn rebuild_cache(&mut self, date: NaiveDate) {
-        self.flow_state_cache = FlowStateCache::from(self, date);
+        let mut cache = std::mem::take(&mut self.flow_state_cache);
+        cache.rebuild(self, date);
+        self.flow_state_cache = cache;
+    }
+
+    /// Re-allocates only what the commands executed since the last update have touched.
+    pub fn update_cache(&mut self, date: NaiveDate) {
+        let mut cache = std::mem::take(&mut self.flow_state_cache);
+        cache.update(self, date);
+        self.flow_state_cache = cache;
+    }
+
+    /// Called before `details` is executed, so that the current assignee of a task is the one
+    /// losing it from its queue.
+    fn mark_cache_dirty(&mut self, details: &CommandDetails) {
+        let task_id = match details {
+            CommandDetails::CreateResource { id, .. } | CommandDetails::DeleteResource { id } => {
+                self.flow_state_cache.mark_resource_dirty(*id);
+                return;
+            }
+            CommandDetails::SetAbsence { resource_id, .. } => {
+                self.flow_state_cache.mark_resource_dirty(*resource_id);
+                return;
+            }
+            CommandDetails::SetEstimateBasis { .. } => {
+                for (task_id, task) in &self.tasks {
+                    self.flow_state_cache.mark_task_dirty(*task_id, task.assignee);
+                }
+                return;
+            }
+            CommandDetails::AssignTask { task_id, resource_id } |
+            CommandDetails::SetWorklog { task_id, resource_id, .. } => {
+                self.flow_state_cache.mark_resource_dirty(*resource_id);
+                *task_id
+            }
+            CommandDetails::CreateTask { id, .. } |
+            CommandDetails::UpdateTask { id, .. } |
+            CommandDetails::DeleteTask { id } => *id,
+            CommandDetails::PrioritizeTask { task_id, .. } |
+            CommandDetails::DeprioritizeTask { task_id, .. } |
+            CommandDetails::ChangeTaskPriority { task_id, .. } |
+            CommandDetails::UnassignTask { task_id } => *task_id,
+            _ => return,
+        };
+        let assignee = self.tasks.get(&task_id).and_then(|task| task.assignee);
+        self.flow_state_cache.mark_task_dirty(task_id, assignee);
     }
 
     fn reset_ids(&mut self) {
@@ -711,19 +679,37 @@ impl FlowState {
         self.next_filter_id = self.filters.keys().max().map_or(1, |max_id| max_id + 1);
     }
 
-    fn get_label_id(&self, label_name: &LabelName) -> Option<LabelId> {
+    pub fn get_team_id(&self, team_name: &str) -> Option<TeamId> {
+        self.teams.iter()
+            .find(|(_, team)| team.name == team_name)
+            .map(|(id, _)| *id)
+    }
+
+    pub fn get_resource_id(&self, resource_name: &str) -> Option<ResourceId> {
+        self.resources.iter()
+            .find(|(_, resource)| resource.name == resource_name)
+            .map(|(id, _)| *id)
+    }
+
+    pub fn get_label_id(&self, label_name: &str) -> Option<LabelId> {
         self.labels.iter()
-            .find(|(_, label)| label.name == *label_name)
+            .find(|(_, label)| label.name == label_name)
+            .map(|(id, _)| *id)
+    }
+
+    pub fn get_filter_id(&self, filter_name: &str) -> Option<FilterId> {
+        self.filters.iter()
+            .find(|(_, filter)| filter.name == filter_name)
             .map(|(id, _)| *id)
     }
 
-    fn next_team_id(&mut self) -> TeamId {
+    pub fn next_team_id(&mut self) -> TeamId {
         let id = self.next_team_id;
         self.next_team_id += 1;
         id
     }
     
-    fn next_resource_id(&mut self) -> ResourceId {
+    pub fn next_resource_id(&mut self) -> ResourceId {
         let id = self.next_resource_id;
         self.next_resource_id += 1;
         id
@@ -735,13 +721,13 @@ impl FlowState {
         id
     }
 
-    fn next_label_id(&mut self) -> LabelId {
+    pub fn next_label_id(&mut self) -> LabelId {
         let id = self.next_label_id;
         self.next_label_id += 1;
         id
     }
     
-    fn next_filter_id(&mut self) -> FilterId {
+    pub fn next_filter_id(&mut self) -> FilterId {
         let id = self.next_filter_id;
         self.next_filter_id += 1;
         id
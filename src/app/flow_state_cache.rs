@@ -1,7 +1,15 @@
 use crate::app::*;
 use chrono::{NaiveDate, Utc, Duration, Datelike};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
 
+/// Source of cache revisions, shared by all caches so that two caches built apart never have
+/// the same one.
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(0);
+
+/// Derived schedule of a `FlowState`. Commands mark the resources and tasks they touch as
+/// dirty, and `update` re-allocates only the queues of those resources; a change of the
+/// scheduling date falls back to a full `rebuild`.
 #[derive(Debug, Clone)]
 pub struct FlowStateCache {
     pub start_date: NaiveDate,
@@ -11,6 +19,22 @@ pub struct FlowStateCache {
     pub task_alloc_rendering: HashMap<TaskId, HashMap<NaiveDate, Fraction>>,
     pub resource_absence_rendering: HashMap<ResourceId, HashMap<NaiveDate, Fraction>>,
     pub worklogs_on_others_tasks: HashMap<ResourceId, HashMap<NaiveDate, Fraction>>,
+    /// Sum of the worklogs of each resource per day, over all tasks.
+    pub worklog_totals: HashMap<ResourceId, HashMap<NaiveDate, Fraction>>,
+
+    built_for: Option<NaiveDate>,
+    dirty_resources: BTreeSet<ResourceId>,
+    dirty_tasks: BTreeSet<TaskId>,
+    /// Tasks marked dirty since the project was last saved; kept over rebuilds.
+    unsaved_tasks: BTreeSet<TaskId>,
+    resource_queues: HashMap<ResourceId, Vec<TaskId>>,
+    /// Tasks each resource has worklogs on. May still hold tasks whose worklogs of the
+    /// resource were removed until the resource is updated.
+    resource_worklog_tasks: HashMap<ResourceId, BTreeSet<TaskId>>,
+    resource_alloc_end: HashMap<ResourceId, NaiveDate>,
+    unassigned_alloc_end: HashMap<TaskId, NaiveDate>,
+    resource_date_range: HashMap<ResourceId, (NaiveDate, NaiveDate)>,
+    revision: u64,
 }
 
 impl FlowStateCache {
@@ -23,183 +47,314 @@ impl FlowStateCache {
             task_alloc_rendering: HashMap::new(),
             resource_absence_rendering: HashMap::new(),
             worklogs_on_others_tasks: HashMap::new(),
+            worklog_totals: HashMap::new(),
+
+            built_for: None,
+            dirty_resources: BTreeSet::new(),
+            dirty_tasks: BTreeSet::new(),
+            unsaved_tasks: BTreeSet::new(),
+            resource_queues: HashMap::new(),
+            resource_worklog_tasks: HashMap::new(),
+            resource_alloc_end: HashMap::new(),
+            unassigned_alloc_end: HashMap::new(),
+            resource_date_range: HashMap::new(),
+            revision: NEXT_REVISION.fetch_add(1, Ordering::Relaxed),
         }
     }
 
     pub fn from(flow_state: &FlowState, date: NaiveDate) -> Self {
-        let resource_absence_rendering: HashMap<ResourceId, HashMap<NaiveDate, Fraction>> = flow_state.resources.iter()
-            .map(|(resource_id, resource)| {
-                let absence_map = {
-                    resource.absences.iter().fold(HashMap::new(), |mut acc, absence| {
-                        let mut current_date = absence.start_date;
-                        let mut remaining_days = absence.duration.days;
-                        
-                        while remaining_days > 0 {
-                            if current_date.weekday() != chrono::Weekday::Sat && current_date.weekday() != chrono::Weekday::Sun {
-                                acc.entry(current_date).or_insert(100);
-                                remaining_days -= 1;
-                            }
-                            current_date = current_date + Duration::days(1);
-                        }
-                        
-                        if absence.duration.fraction > 0 {
-                            while current_date.weekday() == chrono::Weekday::Sat || current_date.weekday() == chrono::Weekday::Sun {
-                                current_date = current_date + Duration::days(1);
-                            }
-                            acc.entry(current_date).or_insert(absence.duration.fraction);
-                        }
-                        acc
-                    })
-                };
-                (*resource_id, absence_map)
-            })
-            .collect();
-        let date_to_milestones = flow_state.milestones.iter()
-            .fold(BTreeMap::new(), |mut acc, milestone| {
-                acc.entry(milestone.date)
-                    .or_insert_with(Vec::new)
-                    .push(milestone.clone());
-                acc
-            });
-        let unassigned_tasks : Vec<TaskId> = flow_state.tasks.iter()
-            .filter(|(_, task)| task.assignee.is_none())
-            .map(|(id, _)| *id)
-            .collect();
-        let total_worklogs: HashMap<TaskId, TaskDuration> = flow_state.worklogs.iter()
-            .map(|(task_id, resource_map)| {
-                let total = resource_map.values()
-                    .flat_map(|date_map| date_map.values())
-                    .fold(TaskDuration { days: 0, fraction: 0 }, |acc, worklog| {
-                        acc + TaskDuration { days: 0, fraction: worklog.fraction }
-                    });
-                (*task_id, total)
-            })
-            .collect();
-        let remaining_durations: HashMap<TaskId, TaskDuration> = flow_state.tasks.iter()
-            .map(|(task_id, task)| {
-                let total_worklog = total_worklogs.get(task_id)
-                    .cloned()
-                    .unwrap_or(TaskDuration { days: 0, fraction: 0 });
-                (*task_id, TaskDuration::zero().max(task.duration.clone() - total_worklog))
-            })
-            .collect();
+        let mut cache = FlowStateCache::new();
+        cache.rebuild(flow_state, date);
+        cache
+    }
+
+    pub fn mark_resource_dirty(&mut self, resource_id: ResourceId) {
+        self.dirty_resources.insert(resource_id);
+    }
+
+    /// `assignee` is the assignee of the task before the change, whose queue loses the task
+    /// if it gets reassigned or deleted. The assignee after the change is looked up on `update`.
+    pub fn mark_task_dirty(&mut self, task_id: TaskId, assignee: Option<ResourceId>) {
+        self.dirty_tasks.insert(task_id);
+        self.unsaved_tasks.insert(task_id);
+        if let Some(resource_id) = assignee {
+            self.dirty_resources.insert(resource_id);
+        }
+    }
+
+    /// Changes on every rebuild and update, so that views derived from the flow state, such as
+    /// the rows of the gantt charts, can tell when to derive them again.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty_resources.is_empty() || !self.dirty_tasks.is_empty()
+    }
+
+    /// The tasks marked dirty since the last call, for storages writing only changed tasks.
+    pub fn take_unsaved_tasks(&mut self) -> BTreeSet<TaskId> {
+        std::mem::take(&mut self.unsaved_tasks)
+    }
 
-        let mut most_farther_alloc_date = date;
-        let mut task_alloc_rendering: HashMap<TaskId, HashMap<NaiveDate, Fraction>> = HashMap::new();
+    /// Recomputes everything from scratch.
+    pub fn rebuild(&mut self, flow_state: &FlowState, date: NaiveDate) {
+        let unsaved_tasks = std::mem::take(&mut self.unsaved_tasks);
+        *self = FlowStateCache::new();
+        self.unsaved_tasks = unsaved_tasks;
+        for (task_id, resource_map) in &flow_state.worklogs {
+            let assignee = flow_state.tasks.get(task_id).map(|task| task.assignee);
+            for (resource_id, date_map) in resource_map {
+                self.resource_worklog_tasks.entry(*resource_id).or_default().insert(*task_id);
+                self.index_worklogs(*resource_id, assignee, date_map);
+            }
+        }
         for (resource_id, resource) in &flow_state.resources {
-            let mut cursor = AllocCursor::new(date);
-            for task_id in &resource.assigned_tasks {
-                if let Some(_task) = flow_state.tasks.get(task_id) {
-                    let mut remaining_alloc = remaining_durations.get(task_id)
-                        .cloned()
-                        .unwrap_or(TaskDuration { days: 0, fraction: 0 });
-                    while remaining_alloc > (TaskDuration { days: 0, fraction: 0 }) {
-                        let absence_for_current_day = resource_absence_rendering.get(resource_id)
-                            .and_then(|absence_map| absence_map.get(&cursor.date))
-                            .copied()
-                            .unwrap_or(0);
-                        let total_worklog_for_current_day = flow_state.worklogs.iter()
-                            .filter_map(|(_, resource_map)| resource_map.get(resource_id))
-                            .filter_map(|date_map| date_map.get(&cursor.date))
-                            .map(|w| w.fraction)
-                            .sum::<Fraction>();
-                        let remaining_alloc_for_current_day = TaskDuration { days: 1, fraction: 0 } 
-                            - cursor.alloced_amount
-                            - TaskDuration { days: 0, fraction: total_worklog_for_current_day }
-                            - TaskDuration { days: 0, fraction: absence_for_current_day };
-                        let work_to_allocate = remaining_alloc.min(remaining_alloc_for_current_day);
-                        if work_to_allocate > (TaskDuration { days: 0, fraction: 0 }) {
-                            task_alloc_rendering.entry(*task_id).or_default()
-                                .insert(cursor.date, work_to_allocate.into());
-                        }
-                        remaining_alloc -= work_to_allocate;
-                        if remaining_alloc == (TaskDuration { days: 0, fraction: 0 }) {
-                            cursor += work_to_allocate;
-                        } else {
-                            cursor.advance_to_next_working_day();
-                            most_farther_alloc_date = most_farther_alloc_date.max(cursor.date);
-                        }
-                    }
+            self.index_absences(*resource_id, resource);
+            self.allocate_resource(flow_state, *resource_id, resource, date);
+        }
+        self.unassigned_tasks = Self::collect_unassigned_tasks(flow_state);
+        for task_id in self.unassigned_tasks.clone() {
+            self.allocate_unassigned_task(flow_state, task_id, date);
+        }
+        self.update_milestones_and_range(flow_state, date);
+        self.built_for = Some(date);
+    }
+
+    /// Re-allocates only the queues affected by the commands executed since the last update.
+    pub fn update(&mut self, flow_state: &FlowState, date: NaiveDate) {
+        if self.built_for != Some(date) {
+            self.rebuild(flow_state, date);
+            return;
+        }
+        self.revision = NEXT_REVISION.fetch_add(1, Ordering::Relaxed);
+        let mut resources = std::mem::take(&mut self.dirty_resources);
+        let tasks = std::mem::take(&mut self.dirty_tasks);
+        for task_id in &tasks {
+            if let Some(assignee) = flow_state.tasks.get(task_id).and_then(|task| task.assignee) {
+                resources.insert(assignee);
+            }
+            // The assignee decides whether a worklog counts as work on someone else's task.
+            if let Some(resource_map) = flow_state.worklogs.get(task_id) {
+                for resource_id in resource_map.keys() {
+                    resources.insert(*resource_id);
+                    self.resource_worklog_tasks.entry(*resource_id).or_default().insert(*task_id);
                 }
             }
+            self.task_alloc_rendering.remove(task_id);
+            self.unassigned_alloc_end.remove(task_id);
+        }
+
+        // Clear every dirty queue before allocating any, as a reassigned task moves between them.
+        for resource_id in &resources {
+            self.clear_resource(*resource_id);
+        }
+        for resource_id in &resources {
+            if let Some(resource) = flow_state.resources.get(resource_id) {
+                let mut worklog_tasks = self.resource_worklog_tasks.remove(resource_id).unwrap_or_default();
+                worklog_tasks.retain(|task_id| {
+                    let date_map = flow_state.worklogs.get(task_id).and_then(|resource_map| resource_map.get(resource_id));
+                    if let Some(date_map) = date_map {
+                        let assignee = flow_state.tasks.get(task_id).map(|task| task.assignee);
+                        self.index_worklogs(*resource_id, assignee, date_map);
+                    }
+                    date_map.is_some()
+                });
+                self.resource_worklog_tasks.insert(*resource_id, worklog_tasks);
+                self.index_absences(*resource_id, resource);
+                self.allocate_resource(flow_state, *resource_id, resource, date);
+            }
+        }
+
+        self.unassigned_tasks = Self::collect_unassigned_tasks(flow_state);
+        for task_id in &tasks {
+            if flow_state.tasks.get(task_id).is_some_and(|task| task.assignee.is_none()) {
+                self.allocate_unassigned_task(flow_state, *task_id, date);
+            }
+        }
+        self.update_milestones_and_range(flow_state, date);
+    }
+
+    fn clear_resource(&mut self, resource_id: ResourceId) {
+        for task_id in self.resource_queues.remove(&resource_id).unwrap_or_default() {
+            self.task_alloc_rendering.remove(&task_id);
+        }
+        self.resource_absence_rendering.remove(&resource_id);
+        self.worklogs_on_others_tasks.remove(&resource_id);
+        self.worklog_totals.remove(&resource_id);
+        self.resource_alloc_end.remove(&resource_id);
+        self.resource_date_range.remove(&resource_id);
+    }
+
+    /// `assignee` is `None` if the task does not exist, and `Some(None)` if it is unassigned.
+    fn index_worklogs(&mut self, resource_id: ResourceId, assignee: Option<Option<ResourceId>>, date_map: &HashMap<NaiveDate, Worklog>) {
+        let is_others_task = assignee.is_some_and(|assignee| assignee != Some(resource_id));
+        for (date, worklog) in date_map {
+            let total = self.worklog_totals.entry(resource_id).or_default().entry(*date).or_insert(0);
+            *total = total.saturating_add(worklog.fraction);
+            if is_others_task {
+                let others = self.worklogs_on_others_tasks.entry(resource_id).or_default().entry(*date).or_insert(0);
+                *others = others.saturating_add(worklog.fraction);
+            }
+            self.extend_date_range(resource_id, *date);
         }
-        let today = date;
-        for task_id in &unassigned_tasks {
-            let mut remaining_alloc = remaining_durations.get(task_id)
-                .cloned()
-                .unwrap_or(TaskDuration { days: 0, fraction: 0 });
-            let mut date = today;
-            while remaining_alloc > (TaskDuration { days: 0, fraction: 0 }) {
-                while date.weekday() == chrono::Weekday::Sat || date.weekday() == chrono::Weekday::Sun {
-                    date += Duration::days(1);
+    }
+
+    fn index_absences(&mut self, resource_id: ResourceId, resource: &Resource) {
+        let absence_map = resource.absences.iter().fold(HashMap::new(), |mut acc, absence| {
+            let mut current_date = absence.start_date;
+            let mut remaining_days = absence.duration.days;
+
+            while remaining_days > 0 {
+                if current_date.weekday() != chrono::Weekday::Sat && current_date.weekday() != chrono::Weekday::Sun {
+                    acc.entry(current_date).or_insert(100);
+                    remaining_days -= 1;
                 }
-                let work_to_allocate = remaining_alloc.min(TaskDuration { days: 1, fraction: 0 });
-                task_alloc_rendering.entry(*task_id).or_default().insert(date, work_to_allocate.into());
-                remaining_alloc -= work_to_allocate;
-                date += Duration::days(1);
+                current_date = current_date + Duration::days(1);
             }
-            most_farther_alloc_date = most_farther_alloc_date.max(date);
+
+            if absence.duration.fraction > 0 {
+                while current_date.weekday() == chrono::Weekday::Sat || current_date.weekday() == chrono::Weekday::Sun {
+                    current_date = current_date + Duration::days(1);
+                }
+                acc.entry(current_date).or_insert(absence.duration.fraction);
+            }
+            acc
+        });
+        for absence in &resource.absences {
+            self.extend_date_range(resource_id, absence.start_date);
         }
-        let mut worklogs_on_others_tasks: HashMap<ResourceId, HashMap<NaiveDate, Fraction>> = HashMap::new();
-        for (task_id, task) in &flow_state.tasks {
-            if let Some(resource_map) = flow_state.worklogs.get(task_id) {
-                for (resource_id, date_map) in resource_map {
-                    for (date, worklog) in date_map {
-                        if task.assignee.is_none() || *resource_id != task.assignee.unwrap() {
-                            *worklogs_on_others_tasks
-                                .entry(*resource_id)
-                                .or_insert_with(HashMap::new)
-                                .entry(*date)
-                                .or_insert(0) += worklog.fraction;
-                        }
+        self.resource_absence_rendering.insert(resource_id, absence_map);
+    }
+
+    fn extend_date_range(&mut self, resource_id: ResourceId, date: NaiveDate) {
+        self.resource_date_range.entry(resource_id)
+            .and_modify(|(first, last)| {
+                *first = (*first).min(date);
+                *last = (*last).max(date);
+            })
+            .or_insert((date, date));
+    }
+
+    /// Work still to schedule for the task, by the estimate basis of the plan.
+    pub fn remaining_duration(flow_state: &FlowState, task: &Task, task_id: TaskId) -> TaskDuration {
+        TaskDuration::zero().max(task.scheduled_duration(flow_state.estimate_basis) - Self::worked_duration(flow_state, task_id))
+    }
+
+    pub fn worked_duration(flow_state: &FlowState, task_id: TaskId) -> TaskDuration {
+        flow_state.worklogs.get(&task_id)
+            .map(|resource_map| resource_map.values()
+                .flat_map(|date_map| date_map.values())
+                .fold(TaskDuration::zero(), |acc, worklog| acc + TaskDuration { days: 0, fraction: worklog.fraction }))
+            .unwrap_or(TaskDuration::zero())
+    }
+
+    /// Allocates the assigned tasks of a resource in priority order, around its absences and
+    /// the worklogs it already has on each day.
+    fn allocate_resource(&mut self, flow_state: &FlowState, resource_id: ResourceId, resource: &Resource, date: NaiveDate) {
+        let mut farthest_alloc_date = date;
+        let mut cursor = AllocCursor::new(date);
+        let absences = self.resource_absence_rendering.get(&resource_id);
+        let worklog_totals = self.worklog_totals.get(&resource_id);
+        for task_id in &resource.assigned_tasks {
+            if let Some(task) = flow_state.tasks.get(task_id) {
+                let mut remaining_alloc = Self::remaining_duration(flow_state, task, *task_id);
+                while remaining_alloc > TaskDuration::zero() {
+                    let absence_for_current_day = absences
+                        .and_then(|absence_map| absence_map.get(&cursor.date))
+                        .copied()
+                        .unwrap_or(0);
+                    let total_worklog_for_current_day = worklog_totals
+                        .and_then(|date_map| date_map.get(&cursor.date))
+                        .copied()
+                        .unwrap_or(0);
+                    let remaining_alloc_for_current_day = TaskDuration { days: 1, fraction: 0 }
+                        - cursor.alloced_amount
+                        - TaskDuration { days: 0, fraction: total_worklog_for_current_day }
+                        - TaskDuration { days: 0, fraction: absence_for_current_day };
+                    let work_to_allocate = remaining_alloc.min(remaining_alloc_for_current_day);
+                    if work_to_allocate > TaskDuration::zero() {
+                        self.task_alloc_rendering.entry(*task_id).or_default()
+                            .insert(cursor.date, work_to_allocate.into());
+                    }
+                    remaining_alloc -= work_to_allocate;
+                    if remaining_alloc == TaskDuration::zero() {
+                        cursor += work_to_allocate;
+                    } else {
+                        cursor.advance_to_next_working_day();
+                        farthest_alloc_date = farthest_alloc_date.max(cursor.date);
                     }
                 }
             }
         }
+        self.resource_queues.insert(resource_id, resource.assigned_tasks.clone());
+        self.resource_alloc_end.insert(resource_id, farthest_alloc_date);
+    }
+
+    /// Unassigned tasks are drawn as if each had a resource of its own, starting today.
+    fn allocate_unassigned_task(&mut self, flow_state: &FlowState, task_id: TaskId, today: NaiveDate) {
+        let Some(task) = flow_state.tasks.get(&task_id) else {
+            return;
+        };
+        let mut remaining_alloc = Self::remaining_duration(flow_state, task, task_id);
+        let mut date = today;
+        while remaining_alloc > TaskDuration::zero() {
+            while date.weekday() == chrono::Weekday::Sat || date.weekday() == chrono::Weekday::Sun {
+                date += Duration::days(1);
+            }
+            let work_to_allocate = remaining_alloc.min(TaskDuration { days: 1, fraction: 0 });
+            self.task_alloc_rendering.entry(task_id).or_default().insert(date, work_to_allocate.into());
+            remaining_alloc -= work_to_allocate;
+            date += Duration::days(1);
+        }
+        self.unassigned_alloc_end.insert(task_id, date);
+    }
+
+    fn collect_unassigned_tasks(flow_state: &FlowState) -> Vec<TaskId> {
+        flow_state.tasks.iter()
+            .filter(|(_, task)| task.assignee.is_none())
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    fn update_milestones_and_range(&mut self, flow_state: &FlowState, date: NaiveDate) {
+        self.date_to_milestones = flow_state.milestones.iter()
+            .fold(BTreeMap::new(), |mut acc, milestone| {
+                acc.entry(milestone.date)
+                    .or_insert_with(Vec::new)
+                    .push(milestone.clone());
+                acc
+            });
+
         let mut start_date = flow_state.milestones.iter()
             .map(|m| m.date)
             .min()
             .unwrap_or(Utc::now().date_naive());
-        start_date = start_date.min(flow_state.worklogs.iter()
-            .flat_map(|(_, resource_map)| resource_map.values())
-            .flat_map(|date_map| date_map.keys())
-            .cloned()
+        start_date = start_date.min(self.resource_date_range.values()
+            .map(|(first, _)| *first)
             .min()
             .unwrap_or(start_date));
-        start_date = start_date.min(flow_state.resources.iter()
-            .flat_map(|(_, resource)| resource.absences.iter())
-            .map(|absence| absence.start_date)
-            .min()
-            .unwrap_or(NaiveDate::MAX));
         start_date = start_date.checked_sub_signed(Duration::days(30))
             .unwrap_or(NaiveDate::MIN);
+
         let mut end_date = flow_state.milestones.iter()
             .map(|m| m.date)
             .max()
             .unwrap_or(Utc::now().date_naive());
-        end_date = end_date.max(flow_state.worklogs.iter()
-            .flat_map(|(_, resource_map)| resource_map.values())
-            .flat_map(|date_map| date_map.keys())
-            .cloned()
+        end_date = end_date.max(self.resource_date_range.values()
+            .map(|(_, last)| *last)
             .max()
             .unwrap_or(end_date));
-        end_date = end_date.max(flow_state.resources.iter()
-            .flat_map(|(_, resource)| resource.absences.iter())
-            .map(|absence| absence.start_date)
+        end_date = end_date.max(self.resource_alloc_end.values()
+            .chain(self.unassigned_alloc_end.values())
+            .copied()
             .max()
-            .unwrap_or(NaiveDate::MIN));
-        end_date = end_date.max(most_farther_alloc_date);
+            .unwrap_or(date));
         end_date = end_date.checked_add_signed(Duration::days(30))
             .unwrap_or(NaiveDate::MAX);
-        FlowStateCache {
-            start_date,
-            end_date,
-            date_to_milestones,
-            unassigned_tasks,
-            task_alloc_rendering,
-            resource_absence_rendering,
-            worklogs_on_others_tasks,
-        }
+
+        self.start_date = start_date;
+        self.end_date = end_date;
     }
 
     pub fn day(&self, index: usize) -> NaiveDate {
@@ -209,4 +364,34 @@ impl FlowStateCache {
     pub fn num_days(&self) -> usize {
         self.end_date.signed_duration_since(self.start_date).num_days() as usize
     }
-}
\ No newline at end of file
+
+    /// Last day the task is scheduled on, if it is scheduled at all.
+    pub fn task_end_date(&self, task_id: TaskId) -> Option<NaiveDate> {
+        self.task_alloc_rendering.get(&task_id)?.keys().max().copied()
+    }
+
+    /// The working days after its end date a task would take if it turned out as pessimistic,
+    /// counted as full days. Empty for tasks without a three-point estimate or already
+    /// scheduled by their pessimistic duration.
+    pub fn task_spread_days(&self, flow_state: &FlowState, task_id: TaskId) -> Vec<NaiveDate> {
+        let (Some(task), Some(end_date)) = (flow_state.tasks.get(&task_id), self.task_end_date(task_id)) else {
+            return Vec::new();
+        };
+        let Some(estimate) = task.estimate else {
+            return Vec::new();
+        };
+        let extra = estimate.pessimistic - task.scheduled_duration(flow_state.estimate_basis);
+        let num_days = extra.days as usize + usize::from(extra.fraction > 0);
+        end_date.iter_days()
+            .skip(1)
+            .filter(|day| day.weekday() != chrono::Weekday::Sat && day.weekday() != chrono::Weekday::Sun)
+            .take(num_days)
+            .collect()
+    }
+}
+
+impl Default for FlowStateCache {
+    fn default() -> Self {
+        FlowStateCache::new()
+    }
+}
@@ -0,0 +1,277 @@
+use crate::app::*;
+use chrono::NaiveDate;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ForecastOptions {
+    pub iterations: usize,
+    /// The same seed gives the same forecast for the same plan.
+    pub seed: u64,
+    /// How much smaller and bigger than its estimate the remaining work of a task without a
+    /// three-point estimate may turn out, as factors of the estimate.
+    pub optimistic_factor: f64,
+    pub pessimistic_factor: f64,
+}
+
+impl Default for ForecastOptions {
+    fn default() -> Self {
+        ForecastOptions { iterations: 2000, seed: 0, optimistic_factor: 0.75, pessimistic_factor: 1.5 }
+    }
+}
+
+/// Completion dates seen over all simulations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateDistribution {
+    pub p50: NaiveDate,
+    pub p80: NaiveDate,
+    pub p95: NaiveDate,
+    /// Number of simulations completing on each date, in date order.
+    pub histogram: Vec<(NaiveDate, usize)>,
+}
+
+impl DateDistribution {
+    /// `None` for no dates.
+    pub fn from_dates(mut dates: Vec<NaiveDate>) -> Option<Self> {
+        if dates.is_empty() {
+            return None;
+        }
+        dates.sort();
+        let percentile = |percent: usize| dates[(dates.len() * percent).div_ceil(100).max(1) - 1];
+        let mut histogram: Vec<(NaiveDate, usize)> = Vec::new();
+        for date in &dates {
+            match histogram.last_mut() {
+                Some((last_date, count)) if last_date == date => *count += 1,
+                _ => histogram.push((*date, 1)),
+            }
+        }
+        Some(DateDistribution { p50: percentile(50), p80: percentile(80), p95: percentile(95), histogram })
+    }
+
+    /// Share of the simulations completing on or before `date`.
+    pub fn probability_by(&self, date: NaiveDate) -> f64 {
+        let total: usize = self.histogram.iter().map(|(_, count)| count).sum();
+        let done: usize = self.histogram.iter()
+            .take_while(|(completion_date, _)| *completion_date <= date)
+            .map(|(_, count)| count)
+            .sum();
+        done as f64 / total as f64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskForecast {
+    pub task_id: TaskId,
+    pub ticket: String,
+    pub title: String,
+    pub planned_end_date: NaiveDate,
+    pub distribution: DateDistribution,
+}
+
+/// A milestone is taken to cover the tasks the plan gets done by its date, and is reached
+/// when the last of them is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MilestoneForecast {
+    pub title: String,
+    pub date: NaiveDate,
+    pub num_tasks: usize,
+    /// Share of the simulations reaching the milestone on or before its date.
+    pub probability: f64,
+    /// `None` if the plan gets no unfinished task done by the milestone.
+    pub distribution: Option<DateDistribution>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Forecast {
+    pub date: NaiveDate,
+    pub iterations: usize,
+    /// Actual work over estimated work of the finished tasks of each resource.
+    pub ratios: BTreeMap<ResourceId, f64>,
+    pub milestones: Vec<MilestoneForecast>,
+    pub tasks: Vec<TaskForecast>,
+}
+
+/// How much more (or less) work than first estimated each resource has put into the tasks it
+/// finished, from the estimate `commands` created each task with and the worklogs on it. A
+/// task several resources worked on counts for each in proportion to their share of the work.
+pub fn estimate_ratios(commands: &[Command], flow_state: &FlowState) -> BTreeMap<ResourceId, f64> {
+    let mut first_estimates: HashMap<TaskId, TaskDuration> = HashMap::new();
+    for details in flatten_commands(commands) {
+        if let CommandDetails::CreateTask { id, duration, .. } = details {
+            first_estimates.entry(*id).or_insert(*duration);
+        }
+    }
+    let mut totals: BTreeMap<ResourceId, (f64, f64)> = BTreeMap::new();
+    for (task_id, resource_map) in &flow_state.worklogs {
+        let (Some(task), Some(estimate)) = (flow_state.tasks.get(task_id), first_estimates.get(task_id)) else {
+            continue;
+        };
+        if FlowStateCache::worked_duration(flow_state, *task_id) < task.duration {
+            continue;
+        }
+        let worked = |date_map: &HashMap<NaiveDate, Worklog>| date_map.values().map(|worklog| worklog.fraction as f64).sum::<f64>();
+        let actual: f64 = resource_map.values().map(worked).sum();
+        if actual == 0.0 {
+            continue;
+        }
+        for (resource_id, date_map) in resource_map {
+            let share = worked(date_map);
+            let (actual_total, estimate_total) = totals.entry(*resource_id).or_default();
+            *actual_total += share;
+            *estimate_total += fractions(*estimate) as f64 * share / actual;
+        }
+    }
+    totals.into_iter()
+        .filter(|(_, (_, estimate_total))| *estimate_total > 0.0)
+        .map(|(resource_id, (actual_total, estimate_total))| (resource_id, actual_total / estimate_total))
+        .collect()
+}
+
+/// An unfinished task as the simulations see it, in fractions of a day.
+struct SimulatedTask {
+    task_id: TaskId,
+    worked: u64,
+    /// The most likely remaining work.
+    remaining: u64,
+    /// Bounds of the remaining work as factors of `remaining`.
+    optimistic_factor: f64,
+    pessimistic_factor: f64,
+    ratio: f64,
+}
+
+/// Simulates the plan `options.iterations` times. In each simulation the remaining work of
+/// every unfinished task is drawn from a triangular distribution over its three-point
+/// estimate, or the range of `options` around its duration, scaled by the historical ratio
+/// of its assignee, and the schedule is updated on a copy of the plan. Returns `None` if
+/// cancelled.
+pub fn run_forecast(
+    flow_state: &FlowState,
+    commands: &[Command],
+    options: &ForecastOptions,
+    date: NaiveDate,
+    control: &InspectionControl,
+) -> Option<Forecast> {
+    let ratios = estimate_ratios(commands, flow_state);
+    let unfinished: Vec<SimulatedTask> = flow_state.tasks.iter()
+        .filter_map(|(task_id, task)| {
+            let worked = fractions(FlowStateCache::worked_duration(flow_state, *task_id));
+            let remaining = fractions(task.duration).saturating_sub(worked);
+            if remaining == 0 {
+                return None;
+            }
+            let (optimistic_factor, pessimistic_factor) = match task.estimate {
+                Some(estimate) => {
+                    let factor = |bound: TaskDuration| fractions(bound).saturating_sub(worked) as f64 / remaining as f64;
+                    (factor(estimate.optimistic), factor(estimate.pessimistic))
+                }
+                None => (options.optimistic_factor, options.pessimistic_factor),
+            };
+            Some(SimulatedTask {
+                task_id: *task_id,
+                worked,
+                remaining,
+                optimistic_factor,
+                pessimistic_factor,
+                ratio: task.assignee.and_then(|resource_id| ratios.get(&resource_id)).copied().unwrap_or(1.0),
+            })
+        })
+        .collect();
+
+    // A milestone covers the unfinished tasks the plan gets done by its date.
+    let covered: Vec<Vec<TaskId>> = flow_state.milestones.iter()
+        .map(|milestone| unfinished.iter()
+            .map(|simulated| simulated.task_id)
+            .filter(|task_id| flow_state.cache().task_end_date(*task_id).is_some_and(|end_date| end_date <= milestone.date))
+            .collect())
+        .collect();
+
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let mut state = flow_state.clone();
+    // The simulations draw durations themselves, so the basis of the plan must not replace them.
+    for (task_id, task) in state.tasks.iter_mut() {
+        task.estimate = None;
+        state.flow_state_cache.mark_task_dirty(*task_id, task.assignee);
+    }
+    let mut end_dates: HashMap<TaskId, Vec<NaiveDate>> = HashMap::new();
+    let mut reached: Vec<Vec<NaiveDate>> = vec![Vec::new(); covered.len()];
+    for iteration in 0..options.iterations {
+        if control.is_cancelled() {
+            return None;
+        }
+        control.set_progress(iteration as f32 / options.iterations as f32);
+        for simulated in &unfinished {
+            let factor = triangular(&mut rng, simulated.optimistic_factor, 1.0, simulated.pessimistic_factor);
+            let remaining = ((simulated.remaining as f64 * factor * simulated.ratio).round() as u64).max(1);
+            if let Some(task) = state.tasks.get_mut(&simulated.task_id) {
+                task.duration = duration_of(simulated.worked + remaining);
+                state.flow_state_cache.mark_task_dirty(simulated.task_id, task.assignee);
+            }
+        }
+        state.update_cache(date);
+        for simulated in &unfinished {
+            if let Some(end_date) = state.cache().task_end_date(simulated.task_id) {
+                end_dates.entry(simulated.task_id).or_default().push(end_date);
+            }
+        }
+        for (task_ids, reached) in covered.iter().zip(&mut reached) {
+            if let Some(last_end_date) = task_ids.iter().filter_map(|task_id| state.cache().task_end_date(*task_id)).max() {
+                reached.push(last_end_date);
+            }
+        }
+    }
+
+    let mut tasks: Vec<TaskForecast> = unfinished.iter()
+        .filter_map(|simulated| {
+            let task_id = &simulated.task_id;
+            let task = &flow_state.tasks[task_id];
+            Some(TaskForecast {
+                task_id: *task_id,
+                ticket: task.ticket.clone(),
+                title: task.title.clone(),
+                planned_end_date: flow_state.cache().task_end_date(*task_id)?,
+                distribution: DateDistribution::from_dates(end_dates.remove(task_id)?)?,
+            })
+        })
+        .collect();
+    tasks.sort_by_key(|forecast| (forecast.distribution.p95, forecast.task_id));
+
+    let mut milestones: Vec<MilestoneForecast> = flow_state.milestones.iter()
+        .zip(covered.iter().zip(reached))
+        .map(|(milestone, (task_ids, reached))| {
+            let distribution = DateDistribution::from_dates(reached);
+            MilestoneForecast {
+                title: milestone.title.clone(),
+                date: milestone.date,
+                num_tasks: task_ids.len(),
+                probability: distribution.as_ref().map_or(1.0, |distribution| distribution.probability_by(milestone.date)),
+                distribution,
+            }
+        })
+        .collect();
+    milestones.sort_by_key(|forecast| forecast.date);
+
+    Some(Forecast { date, iterations: options.iterations, ratios, milestones, tasks })
+}
+
+/// Draws from the triangular distribution between `low` and `high` peaking at `mode`.
+fn triangular(rng: &mut StdRng, low: f64, mode: f64, high: f64) -> f64 {
+    if high <= low {
+        return mode;
+    }
+    let u: f64 = rng.gen();
+    let split = (mode - low) / (high - low);
+    if u < split {
+        low + (u * (high - low) * (mode - low)).sqrt()
+    } else {
+        high - ((1.0 - u) * (high - low) * (high - mode)).sqrt()
+    }
+}
+
+fn fractions(duration: TaskDuration) -> u64 {
+    duration.days * 100 + duration.fraction as u64
+}
+
+fn duration_of(fractions: u64) -> TaskDuration {
+    TaskDuration { days: fractions / 100, fraction: (fractions % 100) as Fraction }
+}
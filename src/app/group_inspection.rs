@@ -0,0 +1,139 @@
+use crate::app::*;
+use chrono::NaiveDate;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+/// A set of tasks inspected together, resolved against each replayed day on its own so tasks
+/// join and leave the group as labels are added and removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskGroup {
+    Label(LabelId),
+    /// Tasks carrying all labels of the filter, like the label filter of the Gantt chart.
+    Filter(FilterId),
+}
+
+impl TaskGroup {
+    pub fn contains(&self, flow_state: &FlowState, task: &Task) -> bool {
+        match self {
+            TaskGroup::Label(label_id) => task.label_ids.contains(label_id),
+            TaskGroup::Filter(filter_id) => flow_state.filters.get(filter_id)
+                .is_some_and(|filter| filter.labels.is_subset(&task.label_ids)),
+        }
+    }
+}
+
+/// What one task of the group adds to a cell, in percent of a person-day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroupContribution {
+    pub task_id: TaskId,
+    /// Logged by all resources together.
+    pub logged: u32,
+    pub remaining: Fraction,
+}
+
+/// How the work of a group of tasks was logged and scheduled on each day of the project's
+/// history. The history is keyed by the snapshot date first and the scheduled day second.
+#[derive(Debug, Clone)]
+pub struct GroupInspection {
+    pub group: TaskGroup,
+    pub contributions_history: HashMap<NaiveDate, HashMap<NaiveDate, Vec<GroupContribution>>>,
+    /// Largest logged plus remaining amount of any cell, for scaling the bars.
+    pub peak_person_days: f32,
+    pub flow_state: FlowState,
+    pub start_date: NaiveDate,
+}
+
+impl GroupInspection {
+    pub fn new(group: TaskGroup) -> Self {
+        GroupInspection {
+            group,
+            contributions_history: HashMap::new(),
+            peak_person_days: 0.0,
+            flow_state: FlowState::new(),
+            start_date: NaiveDate::MAX,
+        }
+    }
+
+    /// Replays `commands` day by day and records what the tasks of `group` contributed on each
+    /// day. Returns `None` if cancelled through `control`.
+    pub fn build(
+        group: TaskGroup,
+        commands: &[Command],
+        checkpoints: &[Checkpoint],
+        date: NaiveDate,
+        snapshots: &Mutex<InspectionSnapshots>,
+        control: &InspectionControl,
+    ) -> FlowResult<Option<Self>> {
+        let mut group_inspector = GroupInspection::new(group);
+        group_inspector.start_date = match group {
+            TaskGroup::Label(label_id) => creation_date(commands, |details| {
+                matches!(details, CommandDetails::CreateLabel { id, .. } if *id == label_id)
+            }).ok_or_else(|| FlowError::not_found(EntityKind::Label, label_id))?,
+            TaskGroup::Filter(filter_id) => creation_date(commands, |details| {
+                matches!(details, CommandDetails::CreateModifyFilter { id, .. } if *id == filter_id)
+            }).ok_or_else(|| FlowError::not_found(EntityKind::Filter, filter_id))?,
+        };
+
+        let flow_state = replay_days(commands, checkpoints, date, snapshots, control, |flow_state, day, _| {
+            group_inspector.record_day(flow_state, day);
+        })?;
+        Ok(flow_state.map(|flow_state| {
+            group_inspector.flow_state = flow_state;
+            group_inspector
+        }))
+    }
+
+    /// The tasks behind the cell of `day` as known on `snapshot_date`.
+    pub fn contributions(&self, snapshot_date: NaiveDate, day: NaiveDate) -> &[GroupContribution] {
+        self.contributions_history.get(&snapshot_date)
+            .and_then(|days| days.get(&day))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn logged_person_days(&self, snapshot_date: NaiveDate, day: NaiveDate) -> f32 {
+        self.contributions(snapshot_date, day).iter().map(|c| c.logged as f32).sum::<f32>() / 100.0
+    }
+
+    pub fn remaining_person_days(&self, snapshot_date: NaiveDate, day: NaiveDate) -> f32 {
+        self.contributions(snapshot_date, day).iter().map(|c| c.remaining as f32).sum::<f32>() / 100.0
+    }
+
+    /// Logged and remaining person-days of the whole group as known on `snapshot_date`.
+    pub fn totals(&self, snapshot_date: NaiveDate) -> (f32, f32) {
+        self.contributions_history.get(&snapshot_date).into_iter()
+            .flat_map(|days| days.values().flatten())
+            .fold((0.0, 0.0), |(logged, remaining), c| {
+                (logged + c.logged as f32 / 100.0, remaining + c.remaining as f32 / 100.0)
+            })
+    }
+
+    fn record_day(&mut self, flow_state: &FlowState, date: NaiveDate) {
+        let mut contributions: HashMap<NaiveDate, BTreeMap<TaskId, GroupContribution>> = HashMap::new();
+        for (task_id, task) in &flow_state.tasks {
+            if !self.group.contains(flow_state, task) {
+                continue;
+            }
+            let new_contribution = GroupContribution { task_id: *task_id, logged: 0, remaining: 0 };
+            for worklogs in flow_state.worklogs.get(task_id).into_iter().flat_map(|by_resource| by_resource.values()) {
+                for (day, worklog) in worklogs {
+                    contributions.entry(*day).or_default()
+                        .entry(*task_id).or_insert(new_contribution)
+                        .logged += worklog.fraction as u32;
+                }
+            }
+            for (day, fraction) in flow_state.cache().task_alloc_rendering.get(task_id).into_iter().flatten() {
+                contributions.entry(*day).or_default()
+                    .entry(*task_id).or_insert(new_contribution)
+                    .remaining = *fraction;
+            }
+        }
+        for by_task in contributions.values() {
+            let cell: u32 = by_task.values().map(|c| c.logged + c.remaining as u32).sum();
+            self.peak_person_days = self.peak_person_days.max(cell as f32 / 100.0);
+        }
+        self.contributions_history.insert(date, contributions.into_iter()
+            .map(|(day, by_task)| (day, by_task.into_values().collect()))
+            .collect());
+    }
+}
@@ -0,0 +1,185 @@
+use crate::app::*;
+use chrono::NaiveDate;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::{Arc, Mutex};
+
+/// Progress reporting and cancellation shared between an inspection and whoever waits for it.
+#[derive(Debug, Default)]
+pub struct InspectionControl {
+    progress_permille: AtomicU32,
+    cancelled: AtomicBool,
+}
+
+impl InspectionControl {
+    pub fn progress(&self) -> f32 {
+        self.progress_permille.load(Ordering::Relaxed) as f32 / 1000.0
+    }
+
+    pub fn set_progress(&self, progress: f32) {
+        self.progress_permille.store((progress.clamp(0.0, 1.0) * 1000.0) as u32, Ordering::Relaxed);
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// What an inspection follows through the project's history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InspectionSubject {
+    Task(TaskId),
+    Resource(ResourceId),
+    Group(TaskGroup),
+    SlipReport { from: Option<NaiveDate>, to: Option<NaiveDate> },
+}
+
+#[derive(Debug, Clone)]
+pub enum Inspection {
+    Task(TaskInspection),
+    Resource(ResourceInspection),
+    Group(GroupInspection),
+    SlipReport(SlipReport),
+}
+
+impl Inspection {
+    pub fn subject(&self) -> InspectionSubject {
+        match self {
+            Inspection::Task(inspection) => InspectionSubject::Task(inspection.task_id),
+            Inspection::Resource(inspection) => InspectionSubject::Resource(inspection.resource_id),
+            Inspection::Group(inspection) => InspectionSubject::Group(inspection.group),
+            Inspection::SlipReport(report) => InspectionSubject::SlipReport { from: report.from, to: report.to },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum InspectionJobStatus {
+    Running,
+    Finished(Box<Inspection>),
+    Cancelled,
+    Failed(FlowError),
+}
+
+/// An `Inspection` being built on a worker thread.
+pub struct InspectionJob {
+    pub subject: InspectionSubject,
+    control: Arc<InspectionControl>,
+    receiver: Receiver<FlowResult<Option<Inspection>>>,
+}
+
+impl InspectionJob {
+    pub fn spawn(
+        subject: InspectionSubject,
+        commands: Vec<Command>,
+        checkpoints: Vec<Checkpoint>,
+        date: NaiveDate,
+        snapshots: Arc<Mutex<InspectionSnapshots>>,
+    ) -> Self {
+        let control = Arc::new(InspectionControl::default());
+        let (sender, receiver) = mpsc::channel();
+        let worker_control = control.clone();
+        std::thread::spawn(move || {
+            let result = match subject {
+                InspectionSubject::Task(task_id) =>
+                    TaskInspection::build(task_id, &commands, &checkpoints, date, &snapshots, &worker_control)
+                        .map(|inspection| inspection.map(Inspection::Task)),
+                InspectionSubject::Resource(resource_id) =>
+                    ResourceInspection::build(resource_id, &commands, &checkpoints, date, &snapshots, &worker_control)
+                        .map(|inspection| inspection.map(Inspection::Resource)),
+                InspectionSubject::Group(group) =>
+                    GroupInspection::build(group, &commands, &checkpoints, date, &snapshots, &worker_control)
+                        .map(|inspection| inspection.map(Inspection::Group)),
+                InspectionSubject::SlipReport { from, to } =>
+                    SlipReport::build(&commands, &checkpoints, date, from, to, &snapshots, &worker_control)
+                        .map(|report| report.map(Inspection::SlipReport)),
+            };
+            // The receiver is gone if the job was dropped, in which case nobody wants the result.
+            let _ = sender.send(result);
+        });
+        InspectionJob { subject, control, receiver }
+    }
+
+    pub fn progress(&self) -> f32 {
+        self.control.progress()
+    }
+
+    pub fn cancel(&self) {
+        self.control.cancel();
+    }
+
+    /// Non-blocking; returns `Running` until the worker is done.
+    pub fn poll(&self) -> InspectionJobStatus {
+        match self.receiver.try_recv() {
+            Ok(Ok(Some(inspection))) => InspectionJobStatus::Finished(Box::new(inspection)),
+            Ok(Ok(None)) => InspectionJobStatus::Cancelled,
+            Ok(Err(e)) => InspectionJobStatus::Failed(e),
+            Err(TryRecvError::Empty) => InspectionJobStatus::Running,
+            Err(TryRecvError::Disconnected) => {
+                let (kind, key): (EntityKind, EntityKey) = match self.subject {
+                    InspectionSubject::Task(task_id) => (EntityKind::Task, task_id.into()),
+                    InspectionSubject::Resource(resource_id) => (EntityKind::Resource, resource_id.into()),
+                    InspectionSubject::Group(TaskGroup::Label(label_id)) => (EntityKind::Label, label_id.into()),
+                    InspectionSubject::Group(TaskGroup::Filter(filter_id)) => (EntityKind::Filter, filter_id.into()),
+                    InspectionSubject::SlipReport { .. } => (EntityKind::Project, "slip report".into()),
+                };
+                InspectionJobStatus::Failed(FlowError::invariant(kind, key, "inspection worker stopped unexpectedly"))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BackgroundJobStatus<T> {
+    Running,
+    Finished(T),
+    Cancelled,
+    Failed(FlowError),
+}
+
+/// Any other computation on a worker thread, reporting progress and checking for cancellation
+/// through an `InspectionControl` like an inspection does.
+pub struct BackgroundJob<T> {
+    /// Names the job in the error reported if its worker stops unexpectedly.
+    name: String,
+    control: Arc<InspectionControl>,
+    receiver: Receiver<FlowResult<Option<T>>>,
+}
+
+impl<T: Send + 'static> BackgroundJob<T> {
+    /// `work` returns `None` once it sees that it was cancelled.
+    pub fn spawn(name: &str, work: impl FnOnce(&InspectionControl) -> FlowResult<Option<T>> + Send + 'static) -> Self {
+        let control = Arc::new(InspectionControl::default());
+        let (sender, receiver) = mpsc::channel();
+        let worker_control = control.clone();
+        std::thread::spawn(move || {
+            // The receiver is gone if the job was dropped, in which case nobody wants the result.
+            let _ = sender.send(work(&worker_control));
+        });
+        BackgroundJob { name: name.to_string(), control, receiver }
+    }
+
+    pub fn progress(&self) -> f32 {
+        self.control.progress()
+    }
+
+    pub fn cancel(&self) {
+        self.control.cancel();
+    }
+
+    /// Non-blocking; returns `Running` until the worker is done.
+    pub fn poll(&self) -> BackgroundJobStatus<T> {
+        match self.receiver.try_recv() {
+            Ok(Ok(Some(result))) => BackgroundJobStatus::Finished(result),
+            Ok(Ok(None)) => BackgroundJobStatus::Cancelled,
+            Ok(Err(e)) => BackgroundJobStatus::Failed(e),
+            Err(TryRecvError::Empty) => BackgroundJobStatus::Running,
+            Err(TryRecvError::Disconnected) => BackgroundJobStatus::Failed(
+                FlowError::invariant(EntityKind::Project, self.name.as_str(), "worker stopped unexpectedly")),
+        }
+    }
+}
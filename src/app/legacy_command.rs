@@ -0,0 +1,288 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use chrono::NaiveDate;
+use crate::app::*;
+
+/// Command encoding of project file format version 2 and older, where teams, resources,
+/// labels and filters were referenced by name. Only used to migrate existing command logs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegacyCommand {
+    pub timestamp: DateTime<Utc>,
+    pub details: LegacyCommandDetails,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LegacyCommandDetails {
+    Void,
+    CreateTeam{
+        name: TeamName,
+    },
+    RenameTeam{
+        old_name: TeamName,
+        new_name: TeamName,
+    },
+    DeleteTeam{
+        name: TeamName,
+    },
+    CreateResource{
+        name: ResourceName,
+        team_name: TeamName,
+    },
+    RenameResource{
+        old_name: ResourceName,
+        new_name: ResourceName,
+    },
+    SwitchTeam{
+        resource_name: ResourceName,
+        new_team_name: TeamName,
+    },
+    DeleteResource{
+        name: ResourceName,
+    },
+    CreateTask{
+        id: TaskId,
+        ticket: String,
+        title: String,
+        duration: TaskDuration,
+    },
+    UpdateTask{
+        id: TaskId,
+        ticket: String,
+        title: String,
+        duration: TaskDuration,
+    },
+    DeleteTask{
+        id: TaskId,
+    },
+    PrioritizeTask{
+        task_id: TaskId,
+        to_top: bool,
+    },
+    DeprioritizeTask{
+        task_id: TaskId,
+        to_bottom: bool,
+    },
+    ChangeTaskPriority{
+        task_id: TaskId,
+        delta: i32,
+    },
+    AssignTask{
+        task_id: TaskId,
+        resource_name: ResourceName,
+    },
+    UnassignTask{
+        task_id: TaskId,
+    },
+    AddWatcher{
+        task_id: TaskId,
+        resource_name: ResourceName,
+    },
+    RemoveWatcher{
+        task_id: TaskId,
+        resource_name: ResourceName,
+    },
+    CreateLabel{
+        name: String,
+    },
+    RenameLabel{
+        old_name: String,
+        new_name: String,
+    },
+    DeleteLabel{
+        name: String,
+    },
+    AddLabelToTask{
+        task_id: TaskId,
+        label_name: LabelName,
+    },
+    RemoveLabelFromTask{
+        task_id: TaskId,
+        label_name: LabelName,
+    },
+    CreateModifyFilter{
+        name: FilterName,
+        labels: Vec<LabelName>,
+        is_favorite: bool,
+    },
+    RenameFilter{
+        old_name: FilterName,
+        new_name: FilterName,
+    },
+    DeleteFilter{
+        name: FilterName,
+    },
+    SetWorklog{
+        task_id: TaskId,
+        date: NaiveDate,
+        resource_name: ResourceName,
+        fraction: Fraction,
+    },
+    SetAbsence{
+        resource_name: ResourceName,
+        start_date: NaiveDate,
+        days: TaskDuration,
+    },
+    AddMilestone{
+        title: String,
+        date: NaiveDate,
+    },
+    RemoveMilestone{
+        title: String,
+    },
+    CompoundCommand{
+        commands: Vec<LegacyCommand>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegacyCommandRecord {
+    pub undo_command: LegacyCommand,
+    pub redo_command: LegacyCommand,
+}
+
+/// Replays a legacy command log, resolving every name against the state it was recorded in.
+/// Undo commands are regenerated from the replay rather than translated, since the name a
+/// legacy undo command refers to may no longer exist in the state it is applied to.
+pub fn migrate_legacy_command_stack(records: Vec<LegacyCommandRecord>) -> FlowResult<Vec<CommandRecord>> {
+    let mut flow_state = FlowState::new();
+    records.into_iter().enumerate()
+        .map(|(index, record)| {
+            let (redo_command, undo_command) = record.redo_command.migrate(&mut flow_state)
+                .map_err(|e| FlowError::parse(format!("Failed to migrate command {}: {}", index + 1, e)))?;
+            Ok(CommandRecord { undo_command, redo_command, author: None })
+        })
+        .collect()
+}
+
+fn resolve<T>(id: Option<T>, kind: EntityKind, name: &str) -> FlowResult<T> {
+    id.ok_or_else(|| FlowError::not_found(kind, name))
+}
+
+impl LegacyCommand {
+    /// Converts and executes the command on `flow_state`, returning it with its inverse.
+    fn migrate(self, flow_state: &mut FlowState) -> FlowResult<(Command, Command)> {
+        let timestamp = self.timestamp;
+        if let LegacyCommandDetails::CompoundCommand { commands } = self.details {
+            let mut redo_commands = Vec::new();
+            let mut undo_commands = Vec::new();
+            for command in commands {
+                let (redo_command, undo_command) = command.migrate(flow_state)?;
+                redo_commands.push(redo_command);
+                undo_commands.push(undo_command);
+            }
+            undo_commands.reverse();
+            return Ok((
+                Command { timestamp, details: CommandDetails::CompoundCommand { commands: redo_commands } },
+                Command { timestamp, details: CommandDetails::CompoundCommand { commands: undo_commands } },
+            ));
+        }
+        let redo_command = Command { timestamp, details: self.details.resolve(flow_state)? };
+        let undo_command = flow_state.execute_command_and_generate_inverse(redo_command.clone())?;
+        Ok((redo_command, undo_command))
+    }
+}
+
+impl LegacyCommandDetails {
+    fn resolve(self, flow_state: &mut FlowState) -> FlowResult<CommandDetails> {
+        let details = match self {
+            LegacyCommandDetails::Void => CommandDetails::Void,
+            LegacyCommandDetails::CreateTeam { name } => CommandDetails::CreateTeam {
+                id: flow_state.next_team_id(),
+                name,
+            },
+            LegacyCommandDetails::RenameTeam { old_name, new_name } => CommandDetails::RenameTeam {
+                id: resolve(flow_state.get_team_id(&old_name), EntityKind::Team, &old_name)?,
+                new_name,
+            },
+            LegacyCommandDetails::DeleteTeam { name } => CommandDetails::DeleteTeam {
+                id: resolve(flow_state.get_team_id(&name), EntityKind::Team, &name)?,
+            },
+            LegacyCommandDetails::CreateResource { name, team_name } => CommandDetails::CreateResource {
+                team_id: resolve(flow_state.get_team_id(&team_name), EntityKind::Team, &team_name)?,
+                id: flow_state.next_resource_id(),
+                name,
+            },
+            LegacyCommandDetails::RenameResource { old_name, new_name } => CommandDetails::RenameResource {
+                id: resolve(flow_state.get_resource_id(&old_name), EntityKind::Resource, &old_name)?,
+                new_name,
+            },
+            LegacyCommandDetails::SwitchTeam { resource_name, new_team_name } => CommandDetails::SwitchTeam {
+                resource_id: resolve(flow_state.get_resource_id(&resource_name), EntityKind::Resource, &resource_name)?,
+                new_team_id: resolve(flow_state.get_team_id(&new_team_name), EntityKind::Team, &new_team_name)?,
+            },
+            LegacyCommandDetails::DeleteResource { name } => CommandDetails::DeleteResource {
+                id: resolve(flow_state.get_resource_id(&name), EntityKind::Resource, &name)?,
+            },
+            LegacyCommandDetails::CreateTask { id, ticket, title, duration } => CommandDetails::CreateTask { id, ticket, title, duration, estimate: None },
+            LegacyCommandDetails::UpdateTask { id, ticket, title, duration } => CommandDetails::UpdateTask { id, ticket, title, duration, estimate: None },
+            LegacyCommandDetails::DeleteTask { id } => CommandDetails::DeleteTask { id },
+            LegacyCommandDetails::PrioritizeTask { task_id, to_top } => CommandDetails::PrioritizeTask { task_id, to_top },
+            LegacyCommandDetails::DeprioritizeTask { task_id, to_bottom } => CommandDetails::DeprioritizeTask { task_id, to_bottom },
+            LegacyCommandDetails::ChangeTaskPriority { task_id, delta } => CommandDetails::ChangeTaskPriority { task_id, delta },
+            LegacyCommandDetails::AssignTask { task_id, resource_name } => CommandDetails::AssignTask {
+                task_id,
+                resource_id: resolve(flow_state.get_resource_id(&resource_name), EntityKind::Resource, &resource_name)?,
+            },
+            LegacyCommandDetails::UnassignTask { task_id } => CommandDetails::UnassignTask { task_id },
+            LegacyCommandDetails::AddWatcher { task_id, resource_name } => CommandDetails::AddWatcher {
+                task_id,
+                resource_id: resolve(flow_state.get_resource_id(&resource_name), EntityKind::Resource, &resource_name)?,
+            },
+            LegacyCommandDetails::RemoveWatcher { task_id, resource_name } => CommandDetails::RemoveWatcher {
+                task_id,
+                resource_id: resolve(flow_state.get_resource_id(&resource_name), EntityKind::Resource, &resource_name)?,
+            },
+            LegacyCommandDetails::CreateLabel { name } => CommandDetails::CreateLabel {
+                id: flow_state.next_label_id(),
+                name,
+            },
+            LegacyCommandDetails::RenameLabel { old_name, new_name } => CommandDetails::RenameLabel {
+                id: resolve(flow_state.get_label_id(&old_name), EntityKind::Label, &old_name)?,
+                new_name,
+            },
+            LegacyCommandDetails::DeleteLabel { name } => CommandDetails::DeleteLabel {
+                id: resolve(flow_state.get_label_id(&name), EntityKind::Label, &name)?,
+            },
+            LegacyCommandDetails::AddLabelToTask { task_id, label_name } => CommandDetails::AddLabelToTask {
+                task_id,
+                label_id: resolve(flow_state.get_label_id(&label_name), EntityKind::Label, &label_name)?,
+            },
+            LegacyCommandDetails::RemoveLabelFromTask { task_id, label_name } => CommandDetails::RemoveLabelFromTask {
+                task_id,
+                label_id: resolve(flow_state.get_label_id(&label_name), EntityKind::Label, &label_name)?,
+            },
+            LegacyCommandDetails::CreateModifyFilter { name, labels, is_favorite } => {
+                let labels = labels.iter()
+                    .map(|label_name| resolve(flow_state.get_label_id(label_name), EntityKind::Label, label_name))
+                    .collect::<Result<Vec<_>, _>>()?;
+                // Legacy filters were always created as non-favorites, whatever the command said.
+                match flow_state.get_filter_id(&name) {
+                    Some(id) => CommandDetails::CreateModifyFilter { id, name, labels, is_favorite },
+                    None => CommandDetails::CreateModifyFilter { id: flow_state.next_filter_id(), name, labels, is_favorite: false },
+                }
+            }
+            LegacyCommandDetails::RenameFilter { old_name, new_name } => CommandDetails::RenameFilter {
+                id: resolve(flow_state.get_filter_id(&old_name), EntityKind::Filter, &old_name)?,
+                new_name,
+            },
+            LegacyCommandDetails::DeleteFilter { name } => CommandDetails::DeleteFilter {
+                id: resolve(flow_state.get_filter_id(&name), EntityKind::Filter, &name)?,
+            },
+            LegacyCommandDetails::SetWorklog { task_id, date, resource_name, fraction } => CommandDetails::SetWorklog {
+                task_id,
+                date,
+                resource_id: resolve(flow_state.get_resource_id(&resource_name), EntityKind::Resource, &resource_name)?,
+                fraction,
+            },
+            LegacyCommandDetails::SetAbsence { resource_name, start_date, days } => CommandDetails::SetAbsence {
+                resource_id: resolve(flow_state.get_resource_id(&resource_name), EntityKind::Resource, &resource_name)?,
+                start_date,
+                days,
+            },
+            LegacyCommandDetails::AddMilestone { title, date } => CommandDetails::AddMilestone { title, date },
+            LegacyCommandDetails::RemoveMilestone { title } => CommandDetails::RemoveMilestone { title },
+            LegacyCommandDetails::CompoundCommand { .. } => unreachable!("compound commands are migrated element by element"),
+        };
+        Ok(details)
+    }
+}
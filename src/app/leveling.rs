@@ -0,0 +1,224 @@
+use crate::app::*;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use std::collections::BTreeSet;
+
+/// What the leveling engine minimizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelingObjective {
+    /// The date the last task ends, then the sum of all end dates.
+    FinishDate,
+    /// The days tasks end after the next milestone, then the finish date.
+    MilestoneLateness,
+}
+
+impl LevelingObjective {
+    pub const ALL: [LevelingObjective; 2] = [LevelingObjective::FinishDate, LevelingObjective::MilestoneLateness];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LevelingObjective::FinishDate => "Finish date",
+            LevelingObjective::MilestoneLateness => "Milestone lateness",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelingOptions {
+    /// Only resources of this team are considered, or all resources for `None`.
+    pub team_id: Option<TeamId>,
+    /// Only resources having the skills a task is labelled with are considered.
+    pub match_skills: bool,
+    pub objective: LevelingObjective,
+}
+
+/// How good a schedule is, compared lexicographically; lower is better.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LevelingScore {
+    pub lateness_days: i64,
+    pub finish_date: Option<NaiveDate>,
+    pub total_end_days: i64,
+}
+
+impl LevelingScore {
+    fn of(flow_state: &FlowState, objective: LevelingObjective, deadline: Option<NaiveDate>) -> Self {
+        let end_dates: Vec<NaiveDate> = flow_state.tasks.keys()
+            .filter_map(|task_id| flow_state.cache().task_end_date(*task_id))
+            .collect();
+        let lateness_days = match (objective, deadline) {
+            (LevelingObjective::MilestoneLateness, Some(deadline)) => end_dates.iter()
+                .map(|end_date| (*end_date - deadline).num_days().max(0))
+                .sum(),
+            _ => 0,
+        };
+        LevelingScore {
+            lateness_days,
+            finish_date: end_dates.iter().max().copied(),
+            total_end_days: end_dates.iter().map(|end_date| end_date.num_days_from_ce() as i64).sum(),
+        }
+    }
+}
+
+/// An unassigned task given an owner, and where it goes in the owner's queue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProposedAssignment {
+    pub task_id: TaskId,
+    pub ticket: String,
+    pub title: String,
+    pub resource_id: ResourceId,
+    pub resource_name: String,
+    /// Index in the queue of the resource, 0 being its top priority.
+    pub position: usize,
+    pub end_date: Option<NaiveDate>,
+}
+
+/// The assignments the leveling engine proposes, and the commands that make them.
+#[derive(Debug, Clone)]
+pub struct LevelingProposal {
+    pub assignments: Vec<ProposedAssignment>,
+    /// Unassigned tasks no resource qualifies for.
+    pub unplaced: Vec<TaskId>,
+    pub score_before: LevelingScore,
+    pub score_after: LevelingScore,
+    commands: Vec<CommandDetails>,
+}
+
+impl LevelingProposal {
+    /// All assignments as a single compound command, or `None` if nothing was proposed.
+    pub fn to_command(&self, timestamp: DateTime<Utc>) -> Option<Command> {
+        if self.commands.is_empty() {
+            return None;
+        }
+        let commands = self.commands.iter()
+            .map(|details| Command { timestamp, details: details.clone() })
+            .collect();
+        Some(Command { timestamp, details: CommandDetails::CompoundCommand { commands } })
+    }
+}
+
+/// Labels of the tasks a resource is assigned to or has logged work on, which it is taken to
+/// have the skills for.
+pub fn resource_skills(flow_state: &FlowState, resource_id: ResourceId) -> BTreeSet<LabelId> {
+    flow_state.tasks.iter()
+        .filter(|(task_id, task)| task.assignee == Some(resource_id)
+            || flow_state.worklogs.get(task_id).is_some_and(|resource_map| resource_map.contains_key(&resource_id)))
+        .flat_map(|(_, task)| task.label_ids.iter().copied())
+        .collect()
+}
+
+/// Proposes an assignee and queue position for every unassigned task, greedily: the longest
+/// tasks are placed first, each where it scores best given the tasks placed before it. Ties
+/// go to the spot that delays the fewest queued tasks, then to the lowest resource id.
+/// Every position of every queue is tried, by assigning the task on top and moving it down one
+/// step at a time. Returns `None` if cancelled.
+pub fn propose_leveling(
+    flow_state: &FlowState,
+    options: &LevelingOptions,
+    date: NaiveDate,
+    control: &InspectionControl,
+) -> FlowResult<Option<LevelingProposal>> {
+    let deadline = flow_state.milestones.iter()
+        .map(|milestone| milestone.date)
+        .filter(|milestone_date| *milestone_date >= date)
+        .min();
+    let mut state = flow_state.clone();
+    let score_before = LevelingScore::of(&state, options.objective, deadline);
+
+    let candidates: Vec<ResourceId> = state.resources.iter()
+        .filter(|(_, resource)| options.team_id.is_none_or(|team_id| resource.team_id == team_id))
+        .map(|(resource_id, _)| *resource_id)
+        .collect();
+    let skills: Vec<(ResourceId, BTreeSet<LabelId>)> = candidates.iter()
+        .map(|resource_id| (*resource_id, resource_skills(&state, *resource_id)))
+        .collect();
+
+    let mut unassigned: Vec<TaskId> = state.tasks.iter()
+        .filter(|(_, task)| task.assignee.is_none())
+        .map(|(task_id, _)| *task_id)
+        .collect();
+    unassigned.sort_by_key(|task_id| std::cmp::Reverse(state.tasks[task_id].duration));
+
+    let timestamp = Utc::now();
+    let mut assignments = Vec::new();
+    let mut unplaced = Vec::new();
+    let mut commands = Vec::new();
+    let num_unassigned = unassigned.len();
+    for (index, task_id) in unassigned.into_iter().enumerate() {
+        if control.is_cancelled() {
+            return Ok(None);
+        }
+        control.set_progress(index as f32 / num_unassigned as f32);
+        let labels = state.tasks[&task_id].label_ids.clone();
+        let mut best: Option<(LevelingScore, usize, ResourceId)> = None;
+        for (resource_id, resource_skills) in &skills {
+            if options.match_skills && !labels.is_subset(resource_skills) {
+                continue;
+            }
+            let queue_length = state.resources[resource_id].assigned_tasks.len();
+            let mut undo_commands = execute_all(&mut state, &placement_commands(task_id, *resource_id, 0), timestamp, date)?;
+            for position in 0..=queue_length {
+                if position > 0 {
+                    let move_down = CommandDetails::ChangeTaskPriority { task_id, delta: 1 };
+                    let undo_move = execute_all(&mut state, &[move_down], timestamp, date)?;
+                    undo_commands.splice(0..0, undo_move);
+                }
+                let score = LevelingScore::of(&state, options.objective, deadline);
+                let key = (score, queue_length - position, *resource_id);
+                if best.as_ref().is_none_or(|best| key < *best) {
+                    best = Some(key);
+                }
+            }
+            execute_all(&mut state, &undo_commands, timestamp, date)?;
+        }
+        let Some((_, pushed, resource_id)) = best else {
+            unplaced.push(task_id);
+            continue;
+        };
+        let position = state.resources[&resource_id].assigned_tasks.len() - pushed;
+        let placement = placement_commands(task_id, resource_id, position);
+        execute_all(&mut state, &placement, timestamp, date)?;
+        commands.extend(placement);
+        let task = &state.tasks[&task_id];
+        assignments.push(ProposedAssignment {
+            task_id,
+            ticket: task.ticket.clone(),
+            title: task.title.clone(),
+            resource_id,
+            resource_name: state.resources[&resource_id].name.clone(),
+            position,
+            end_date: None,
+        });
+    }
+    for assignment in &mut assignments {
+        assignment.end_date = state.cache().task_end_date(assignment.task_id);
+    }
+
+    Ok(Some(LevelingProposal {
+        assignments,
+        unplaced,
+        score_before,
+        score_after: LevelingScore::of(&state, options.objective, deadline),
+        commands,
+    }))
+}
+
+/// Assigning puts a task on top of the queue, from where it is moved down to `position`.
+pub fn placement_commands(task_id: TaskId, resource_id: ResourceId, position: usize) -> Vec<CommandDetails> {
+    let mut commands = vec![CommandDetails::AssignTask { task_id, resource_id }];
+    if position > 0 {
+        commands.push(CommandDetails::ChangeTaskPriority { task_id, delta: position as i32 });
+    }
+    commands
+}
+
+/// Executes `commands` in order, updating the cache once after all of them, and returns the
+/// commands that undo them, in the order to run them.
+fn execute_all(flow_state: &mut FlowState, commands: &[CommandDetails], timestamp: DateTime<Utc>, date: NaiveDate) -> FlowResult<Vec<CommandDetails>> {
+    let mut undo_commands = Vec::new();
+    for details in commands {
+        let undo_command = flow_state.execute_command_and_generate_inverse(Command { timestamp, details: details.clone() })?;
+        undo_commands.push(undo_command.details);
+    }
+    flow_state.update_cache(date);
+    undo_commands.reverse();
+    Ok(undo_commands)
+}
@@ -4,9 +4,15 @@ pub use duration::*;
 pub mod ids;
 pub use ids::*;
 
+pub mod error;
+pub use error::*;
+
 pub mod command;
 pub use command::*;
 
+pub mod legacy_command;
+pub use legacy_command::*;
+
 pub mod model;
 pub use model::*;
 
@@ -22,5 +28,62 @@ pub use flow_state::*;
 pub mod flow_state_cache;
 pub use flow_state_cache::*;
 
+pub mod checkpoint;
+pub use checkpoint::*;
+
+pub mod project_file;
+pub use project_file::*;
+
+pub mod storage;
+pub use storage::*;
+
+pub mod sqlite_storage;
+pub use sqlite_storage::*;
+
 pub mod task_inspection;
-pub use task_inspection::*;
\ No newline at end of file
+pub use task_inspection::*;
+
+pub mod inspection_worker;
+pub use inspection_worker::*;
+
+pub mod resource_inspection;
+pub use resource_inspection::*;
+
+pub mod group_inspection;
+pub use group_inspection::*;
+
+pub mod slip_report;
+pub use slip_report::*;
+
+pub mod scenario;
+pub use scenario::*;
+
+pub mod undo_tree;
+pub use undo_tree::*;
+
+pub mod csv_export;
+pub use csv_export::*;
+
+pub mod audit_log;
+pub use audit_log::*;
+
+pub mod permissions;
+pub use permissions::*;
+
+pub mod observer;
+pub use observer::*;
+
+pub mod scripting;
+pub use scripting::*;
+
+pub mod query;
+pub use query::*;
+
+pub mod leveling;
+pub use leveling::*;
+
+pub mod priority_optimizer;
+pub use priority_optimizer::*;
+
+pub mod forecast;
+pub use forecast::*;
\ No newline at end of file
@@ -96,7 +96,10 @@ pub struct Task {
     create_timestamp: DateTime<Utc>,
     pub ticket: String,
     pub title: String,
+    /// The most likely duration, and the only one of tasks without a three-point estimate.
     pub duration: TaskDuration,
+    #[serde(default)]
+    pub estimate: Option<ThreePointEstimate>,
     pub label_ids: BTreeSet<LabelId>,
     pub assignee: Option<ResourceId>,
     pub watchers: BTreeSet<ResourceId>,
@@ -110,11 +113,17 @@ impl Task {
             ticket,
             title,
             duration,
+            estimate: None,
             label_ids: BTreeSet::new(),
             assignee: None,
             watchers: BTreeSet::new(),
         }
     }
+
+    /// The duration the scheduler allocates for the task.
+    pub fn scheduled_duration(&self, basis: EstimateBasis) -> TaskDuration {
+        basis.duration(self.duration, self.estimate.as_ref())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
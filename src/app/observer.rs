@@ -0,0 +1,97 @@
+use crate::app::*;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandEventKind {
+    /// The command was invoked for the first time.
+    Executed,
+    /// The inverse of the command was executed.
+    Undone,
+    /// The command was executed again after being undone.
+    Redone,
+    /// The state was rebuilt by a jump through the history or a switch of undo branches
+    /// rather than by executing commands. `command` and `inverse` are void; `before` and
+    /// `after` tell what changed.
+    HistoryReset,
+}
+
+/// The parts of the cache integrations typically compare before and after a command.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheSummary {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub num_unassigned_tasks: usize,
+    /// Last day each scheduled task is allocated on.
+    pub task_end_dates: BTreeMap<TaskId, NaiveDate>,
+}
+
+impl CacheSummary {
+    pub fn of(flow_state: &FlowState) -> Self {
+        let cache = flow_state.cache();
+        CacheSummary {
+            start_date: cache.start_date,
+            end_date: cache.end_date,
+            num_unassigned_tasks: cache.unassigned_tasks.len(),
+            task_end_dates: flow_state.tasks.keys()
+                .filter_map(|task_id| Some((*task_id, cache.task_end_date(*task_id)?)))
+                .collect(),
+        }
+    }
+}
+
+/// A command that went through the plan. `command` is always the command as invoked and
+/// `inverse` the command that takes it back, whichever of the two was just executed.
+#[derive(Debug, Clone)]
+pub struct CommandEvent<'a> {
+    pub kind: CommandEventKind,
+    pub command: &'a Command,
+    pub inverse: &'a Command,
+    pub author: Option<&'a str>,
+    pub before: &'a CacheSummary,
+    pub after: &'a CacheSummary,
+}
+
+/// Receives the commands executed, undone and redone on the plan of a project, and the resets
+/// of its state by jumps through the history or switches between undo branches. Commands of
+/// scenarios are not reported until they are merged.
+pub trait CommandObserver {
+    fn on_command(&mut self, event: &CommandEvent);
+}
+
+/// Observers are `Send` so that a project can move to a worker thread with them.
+pub type SharedCommandObserver = Arc<Mutex<dyn CommandObserver + Send>>;
+
+/// The observers registered on a project. They are shared rather than owned, so whoever
+/// registers one can keep a handle to it.
+#[derive(Clone, Default)]
+pub struct CommandObservers {
+    observers: Vec<SharedCommandObserver>,
+}
+
+impl CommandObservers {
+    pub fn register(&mut self, observer: SharedCommandObserver) {
+        self.observers.push(observer);
+    }
+
+    pub fn unregister(&mut self, observer: &SharedCommandObserver) {
+        self.observers.retain(|registered| !Arc::ptr_eq(registered, observer));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.observers.is_empty()
+    }
+
+    pub fn notify(&self, event: &CommandEvent) {
+        for observer in &self.observers {
+            observer.lock().unwrap().on_command(event);
+        }
+    }
+}
+
+impl std::fmt::Debug for CommandObservers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CommandObservers({})", self.observers.len())
+    }
+}
@@ -0,0 +1,109 @@
+use crate::app::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Role {
+    /// Logs work and absences for their own resource only.
+    Developer,
+    /// Edits the plan: teams, resources, tasks, labels, filters and milestones.
+    Manager,
+    /// Edits the plan and the members of the project.
+    Admin,
+}
+
+impl Role {
+    pub const ALL: [Role; 3] = [Role::Developer, Role::Manager, Role::Admin];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Role::Developer => "Developer",
+            Role::Manager => "Manager",
+            Role::Admin => "Admin",
+        }
+    }
+}
+
+/// A user of the project, identified by the author name their commands are recorded under.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Member {
+    pub role: Role,
+    /// The resource a developer logs work and absences for.
+    pub resource_id: Option<ResourceId>,
+}
+
+/// Who may invoke which commands. A project without members lets everyone do everything;
+/// once there are members, users who are not one of them cannot change anything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Permissions {
+    pub members: BTreeMap<String, Member>,
+}
+
+impl Permissions {
+    pub fn is_enforced(&self) -> bool {
+        !self.members.is_empty()
+    }
+
+    pub fn member(&self, user: Option<&str>) -> Option<&Member> {
+        self.members.get(user?)
+    }
+
+    pub fn can_edit_plan(&self, user: Option<&str>) -> bool {
+        !self.is_enforced() || self.member(user).is_some_and(|member| member.role >= Role::Manager)
+    }
+
+    pub fn can_manage_members(&self, user: Option<&str>) -> bool {
+        !self.is_enforced() || self.member(user).is_some_and(|member| member.role == Role::Admin)
+    }
+
+    /// Whether `user` may log work and absences of `resource_id`.
+    pub fn can_log_for(&self, user: Option<&str>, resource_id: ResourceId) -> bool {
+        self.can_edit_plan(user) || self.member(user).is_some_and(|member| member.resource_id == Some(resource_id))
+    }
+
+    pub fn allows(&self, user: Option<&str>, details: &CommandDetails) -> bool {
+        match details {
+            CommandDetails::Void => true,
+            CommandDetails::SetWorklog { resource_id, .. }
+            | CommandDetails::SetAbsence { resource_id, .. } => self.can_log_for(user, *resource_id),
+            CommandDetails::CompoundCommand { commands } => {
+                commands.iter().all(|command| self.allows(user, &command.details))
+            }
+            _ => self.can_edit_plan(user),
+        }
+    }
+
+    /// Fails if `user` may not invoke `details`, naming the command as it reads in `flow_state`.
+    pub fn check(&self, user: Option<&str>, details: &CommandDetails, flow_state: &FlowState) -> FlowResult<()> {
+        if self.allows(user, details) {
+            return Ok(());
+        }
+        let reason = match self.member(user) {
+            Some(member) => format!("is a {} and may not: {}", member.role.name(), details.describe(flow_state)),
+            None => "is not a member of the project".to_string(),
+        };
+        Err(FlowError::permission_denied(user.unwrap_or("anonymous user"), reason))
+    }
+
+    /// Adds or updates the member called `name`, or removes it for `None`, on behalf of `user`.
+    pub fn set_member(&mut self, user: Option<&str>, name: &str, member: Option<Member>) -> FlowResult<()> {
+        if !self.can_manage_members(user) {
+            return Err(FlowError::permission_denied(user.unwrap_or("anonymous user"), "may not change the members of the project"));
+        }
+        if name.is_empty() {
+            return Err(FlowError::invariant(EntityKind::Member, name, "needs a name"));
+        }
+        let mut members = self.members.clone();
+        match member {
+            Some(member) => { members.insert(name.to_string(), member); }
+            None => {
+                members.remove(name).ok_or_else(|| FlowError::not_found(EntityKind::Member, name))?;
+            }
+        }
+        if !members.is_empty() && !members.values().any(|member| member.role == Role::Admin) {
+            return Err(FlowError::invariant(EntityKind::Member, name, "cannot be changed, the project would have no admin left"));
+        }
+        self.members = members;
+        Ok(())
+    }
+}
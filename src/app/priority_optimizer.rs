@@ -0,0 +1,159 @@
+use crate::app::*;
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// The milestone to meet and which tasks have to be done by then.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriorityGoal {
+    pub milestone: String,
+    /// Tasks with this label are the must-haves.
+    pub label_id: LabelId,
+    /// Whether must-haves that stay late may move to another resource of the same team.
+    pub allow_reassignment: bool,
+}
+
+/// The reordering and reassignments that bring the must-haves forward, and their effect.
+#[derive(Debug, Clone)]
+pub struct PriorityPlan {
+    pub milestone_date: NaiveDate,
+    pub must_haves: Vec<TaskId>,
+    /// Must-haves ending after the milestone before and after the plan.
+    pub late_before: Vec<TaskId>,
+    pub late_after: Vec<TaskId>,
+    /// End dates that change, with the current plan as "plan" and the optimized one as "scenario".
+    pub diff: ScenarioDiff,
+    commands: Vec<CommandDetails>,
+}
+
+impl PriorityPlan {
+    /// All changes as a single compound command, or `None` if nothing needs to change.
+    pub fn to_command(&self, timestamp: DateTime<Utc>) -> Option<Command> {
+        if self.commands.is_empty() {
+            return None;
+        }
+        let commands = self.commands.iter()
+            .map(|details| Command { timestamp, details: details.clone() })
+            .collect();
+        Some(Command { timestamp, details: CommandDetails::CompoundCommand { commands } })
+    }
+}
+
+/// Moves the must-haves of `goal` to the top of the queue of their assignee, keeping the order
+/// among them and among the other tasks. If some still end after the milestone and
+/// reassignment is allowed, each of those moves to the resource of its team where it ends
+/// earliest, behind that resource's must-haves, as long as that leaves fewer must-haves late.
+/// Returns `None` if cancelled.
+pub fn optimize_priorities(
+    flow_state: &FlowState,
+    goal: &PriorityGoal,
+    date: NaiveDate,
+    control: &InspectionControl,
+) -> FlowResult<Option<PriorityPlan>> {
+    let milestone_date = flow_state.milestones.iter()
+        .find(|milestone| milestone.title == goal.milestone)
+        .map(|milestone| milestone.date)
+        .ok_or_else(|| FlowError::not_found(EntityKind::Milestone, &goal.milestone))?;
+    if !flow_state.labels.contains_key(&goal.label_id) {
+        return Err(FlowError::not_found(EntityKind::Label, goal.label_id));
+    }
+    let is_must_have = |state: &FlowState, task_id: &TaskId| state.tasks.get(task_id)
+        .is_some_and(|task| task.label_ids.contains(&goal.label_id));
+    let must_haves: Vec<TaskId> = flow_state.tasks.keys()
+        .filter(|task_id| is_must_have(flow_state, task_id))
+        .copied()
+        .collect();
+    let late = |state: &FlowState| -> Vec<TaskId> {
+        must_haves.iter()
+            .filter(|task_id| state.cache().task_end_date(**task_id).is_some_and(|end_date| end_date > milestone_date))
+            .copied()
+            .collect()
+    };
+
+    let timestamp = Utc::now();
+    let mut state = flow_state.clone();
+    let mut commands = Vec::new();
+    let resource_ids: Vec<ResourceId> = state.resources.keys().copied().collect();
+    // Reordering takes the first half of the progress, reassigning the second.
+    for (index, resource_id) in resource_ids.iter().enumerate() {
+        if control.is_cancelled() {
+            return Ok(None);
+        }
+        control.set_progress(index as f32 / resource_ids.len() as f32 / 2.0);
+        let queue = state.resources[resource_id].assigned_tasks.clone();
+        let (mut target, others): (Vec<TaskId>, Vec<TaskId>) = queue.iter().partition(|task_id| is_must_have(&state, task_id));
+        target.extend(others);
+        for details in reorder_commands(&queue, &target) {
+            execute(&mut state, &details, timestamp, date)?;
+            commands.push(details);
+        }
+    }
+
+    if goal.allow_reassignment {
+        let late_tasks = late(&state);
+        for (index, task_id) in late_tasks.iter().copied().enumerate() {
+            if control.is_cancelled() {
+                return Ok(None);
+            }
+            control.set_progress(0.5 + index as f32 / late_tasks.len() as f32 / 2.0);
+            let Some(assignee) = state.tasks[&task_id].assignee else {
+                continue;
+            };
+            let team_id = state.resources[&assignee].team_id;
+            let num_late = late(&state).len();
+            let mut best: Option<(usize, Option<NaiveDate>, ResourceId, usize)> = None;
+            for (resource_id, resource) in &state.resources {
+                if *resource_id == assignee || resource.team_id != team_id {
+                    continue;
+                }
+                let position = resource.assigned_tasks.iter()
+                    .filter(|queued| is_must_have(&state, queued))
+                    .count();
+                let mut candidate = state.clone();
+                for details in placement_commands(task_id, *resource_id, position) {
+                    execute(&mut candidate, &details, timestamp, date)?;
+                }
+                let key = (late(&candidate).len(), candidate.cache().task_end_date(task_id), *resource_id, position);
+                if key.0 < num_late && best.is_none_or(|best| key < best) {
+                    best = Some(key);
+                }
+            }
+            if let Some((_, _, resource_id, position)) = best {
+                for details in placement_commands(task_id, resource_id, position) {
+                    execute(&mut state, &details, timestamp, date)?;
+                    commands.push(details);
+                }
+            }
+        }
+    }
+
+    Ok(Some(PriorityPlan {
+        milestone_date,
+        late_before: late(flow_state),
+        late_after: late(&state),
+        diff: ScenarioDiff::between(flow_state, &state),
+        must_haves,
+        commands,
+    }))
+}
+
+/// The `ChangeTaskPriority` commands that turn `queue` into `target`, filling `target` from
+/// the top by moving each task up from where it is.
+fn reorder_commands(queue: &[TaskId], target: &[TaskId]) -> Vec<CommandDetails> {
+    let mut queue = queue.to_vec();
+    let mut commands = Vec::new();
+    for (index, task_id) in target.iter().enumerate() {
+        let Some(position) = queue.iter().position(|queued| queued == task_id) else {
+            continue;
+        };
+        if position != index {
+            queue.remove(position);
+            queue.insert(index, *task_id);
+            commands.push(CommandDetails::ChangeTaskPriority { task_id: *task_id, delta: index as i32 - position as i32 });
+        }
+    }
+    commands
+}
+
+fn execute(flow_state: &mut FlowState, details: &CommandDetails, timestamp: DateTime<Utc>, date: NaiveDate) -> FlowResult<()> {
+    flow_state.execute_command_generate_inverse_and_rebuild_cache(Command { timestamp, details: details.clone() }, date)?;
+    Ok(())
+}
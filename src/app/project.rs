@@ -1,8 +1,6 @@
 use crate::app::*;
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use std::fs::File;
-use std::io::Read;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Project {
@@ -10,102 +8,610 @@ pub struct Project {
     filename: Option<String>,
     pub command_stack: Vec<CommandRecord>,
     num_commands_applied: usize,
+    checkpoints: Vec<Checkpoint>,
+    branches: Vec<UndoBranch>,
+    permissions: Permissions,
+    history_events: Vec<HistoryEvent>,
     #[serde(skip)]
     flow_state: FlowState,
+    #[serde(skip)]
+    read_only: bool,
+    #[serde(skip)]
+    load_warning: Option<String>,
+    /// Why no further checkpoints are created: a command of the history failed to replay.
+    #[serde(skip)]
+    checkpoint_error: Option<FlowError>,
+    /// The date being looked at and the state at its end, while travelling back in time.
+    #[serde(skip)]
+    as_of: Option<(NaiveDate, FlowState)>,
+    #[serde(skip)]
+    scenarios: Vec<Scenario>,
+    /// Name of the scenario commands go to instead of the plan.
+    #[serde(skip)]
+    active_scenario: Option<String>,
+    /// Recorded as the author of every command invoked from now on.
+    #[serde(skip)]
+    author: Option<String>,
+    #[serde(skip)]
+    observers: CommandObservers,
+    /// What the next save has to write besides the current state of the history.
+    #[serde(skip)]
+    unsaved: UnsavedChanges,
 }
 
 impl Project {
-    pub fn new(yaml_filename: &str) -> Self {
+    pub fn new(filename: &str) -> Self {
         Self {
-            filename: Some(yaml_filename.to_string()),
+            filename: Some(filename.to_string()),
             command_stack: Vec::new(),
             num_commands_applied: 0,
+            checkpoints: Vec::new(),
+            branches: Vec::new(),
+            permissions: Permissions::default(),
+            history_events: Vec::new(),
             flow_state: FlowState::new(),
+            read_only: false,
+            load_warning: None,
+            checkpoint_error: None,
+            as_of: None,
+            scenarios: Vec::new(),
+            active_scenario: None,
+            author: None,
+            observers: CommandObservers::default(),
+            unsaved: UnsavedChanges::everything(),
         }
     }
 
-    pub fn load_from_yaml(yaml_filename: &str, date: NaiveDate) -> Result<Self, String> {
-        let mut file = File::open(yaml_filename).map_err(|e| format!("Failed to open YAML file: {}", e))?;
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).map_err(|e| format!("Failed to read YAML file: {}", e))?;
+    /// Loads the project from `filename`, using the storage backend selected by its extension.
+    pub fn load(filename: &str, date: NaiveDate) -> FlowResult<Self> {
+        let loaded = storage_for(filename).load()?;
+        let ProjectFile { num_commands_applied, command_stack, checkpoints, branches, permissions, history_events, .. } = loaded.file;
 
-        let (num_commands_applied, command_stack, ): (usize, Vec<CommandRecord>) =
-            serde_yaml::from_str(&contents).map_err(|e| format!("Failed to deserialize YAML: {}", e))?;
-
-        let flow_state = FlowState::from_commands(&command_stack.iter().take(num_commands_applied)
-            .map(|record| record.redo_command.clone()).collect::<Vec<_>>(), date)
-            .expect("Failed to fast forward flow state");
+        let applied_commands = command_stack.iter().take(num_commands_applied)
+            .map(|record| record.redo_command.clone()).collect::<Vec<_>>();
+        let checkpoints = Checkpoint::retain_valid(checkpoints.into_owned(), &applied_commands);
+        let mut flow_state = FlowState::from_checkpoints(&applied_commands, &checkpoints, date)?;
+        flow_state.flow_state_cache.take_unsaved_tasks();
+        let unsaved = UnsavedChanges::none(command_stack.len());
         Ok(Self {
-            filename: Some(yaml_filename.to_string()),
-            command_stack,
+            filename: Some(filename.to_string()),
+            command_stack: command_stack.into_owned(),
             num_commands_applied,
+            checkpoints,
+            branches: branches.into_owned(),
+            permissions: permissions.into_owned(),
+            history_events: history_events.into_owned(),
             flow_state,
+            read_only: loaded.read_only,
+            load_warning: loaded.warning,
+            checkpoint_error: None,
+            as_of: None,
+            scenarios: Vec::new(),
+            active_scenario: None,
+            author: None,
+            observers: CommandObservers::default(),
+            unsaved,
         })
     }
 
-    pub fn save_to_yaml(&mut self) -> Result<(), String> {
-        let data = (self.num_commands_applied, &self.command_stack);
-        let yaml_string = serde_yaml::to_string(&data).map_err(|e| format!("Failed to serialize to YAML: {}", e))?;
-        std::fs::write(self.filename.as_ref().unwrap(), yaml_string).map_err(|e| format!("Failed to write to file: {}", e))?;
+    pub fn save(&mut self) -> FlowResult<()> {
+        self.check_writable()?;
+        self.update_checkpoints();
+        self.unsaved.add_tasks(self.flow_state.flow_state_cache.take_unsaved_tasks());
+        let data = ProjectFile::new(
+            self.num_commands_applied,
+            &self.command_stack,
+            &self.checkpoints,
+            &self.branches,
+            &self.permissions,
+            &self.history_events);
+        storage_for(self.filename.as_ref().unwrap()).save(&data, &self.flow_state, &self.unsaved)?;
+        self.unsaved = UnsavedChanges::none(self.command_stack.len());
         Ok(())
     }
 
-    pub fn invoke_command(&mut self, command: Command, date: NaiveDate) -> Result<(), String> {
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn set_author(&mut self, author: Option<String>) {
+        self.author = author;
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    /// Reports every command executed, undone or redone on the plan, and every reset of its
+    /// history, to `observer` from now on.
+    pub fn register_observer(&mut self, observer: SharedCommandObserver) {
+        self.observers.register(observer);
+    }
+
+    pub fn unregister_observer(&mut self, observer: &SharedCommandObserver) {
+        self.observers.unregister(observer);
+    }
+
+    /// The summary of the plan before a command, if anyone is observing.
+    fn summarize_for_observers(&self) -> Option<CacheSummary> {
+        (!self.observers.is_empty()).then(|| CacheSummary::of(&self.flow_state))
+    }
+
+    fn notify_observers(&self, kind: CommandEventKind, command: &Command, inverse: &Command, before: Option<CacheSummary>) {
+        let Some(before) = before else {
+            return;
+        };
+        let after = CacheSummary::of(&self.flow_state);
+        self.observers.notify(&CommandEvent {
+            kind,
+            command,
+            inverse,
+            author: self.author.as_deref(),
+            before: &before,
+            after: &after,
+        });
+    }
+
+    fn notify_history_reset(&self, timestamp: DateTime<Utc>, before: Option<CacheSummary>) {
+        let void = Command { timestamp, details: CommandDetails::Void };
+        self.notify_observers(CommandEventKind::HistoryReset, &void, &void, before);
+    }
+
+    pub fn permissions(&self) -> &Permissions {
+        &self.permissions
+    }
+
+    /// Adds, updates or, for `None`, removes a member on behalf of the author.
+    pub fn set_member(&mut self, name: &str, member: Option<Member>) -> FlowResult<()> {
+        self.check_writable()?;
+        self.permissions.set_member(self.author.as_deref(), name, member)?;
+        self.save()
+    }
+
+    /// Fails unless the author may invoke `details`.
+    fn check_permission(&self, details: &CommandDetails) -> FlowResult<()> {
+        self.permissions.check(self.author.as_deref(), details, self.flow_state())
+    }
+
+    /// Fails unless the author may rewrite the history of the plan.
+    fn check_plan_editing(&self) -> FlowResult<()> {
+        if self.permissions.can_edit_plan(self.author.as_deref()) {
+            return Ok(());
+        }
+        Err(FlowError::permission_denied(self.author.as_deref().unwrap_or("anonymous user"), "may not change the history of the plan"))
+    }
+
+    pub fn load_warning(&self) -> Option<&str> {
+        self.load_warning.as_deref()
+    }
+
+    pub fn checkpoint_error(&self) -> Option<&FlowError> {
+        self.checkpoint_error.as_ref()
+    }
+
+    fn key(&self) -> EntityKey {
+        self.filename.clone().unwrap_or_default().into()
+    }
+
+    fn check_writable(&self) -> FlowResult<()> {
+        if self.read_only {
+            return Err(FlowError::invariant(EntityKind::Project, self.key(), "is read-only"));
+        }
+        if let Some((date, _)) = &self.as_of {
+            return Err(FlowError::invariant(EntityKind::Project, self.key(), format!("is shown as of {date} and cannot be changed")));
+        }
+        Ok(())
+    }
+
+    /// Shows the project as it was at the end of `date` by replaying only the commands with
+    /// timestamps up to then. The project cannot be changed until `return_to_now`.
+    pub fn travel_to(&mut self, date: NaiveDate) -> FlowResult<()> {
+        let commands = self.commands_up_to(Some(date));
+        let flow_state = FlowState::from_checkpoints(&commands, &self.checkpoints, date)?;
+        self.as_of = Some((date, flow_state));
+        Ok(())
+    }
+
+    pub fn return_to_now(&mut self) {
+        self.as_of = None;
+    }
+
+    pub fn as_of(&self) -> Option<NaiveDate> {
+        self.as_of.as_ref().map(|(date, _)| *date)
+    }
+
+    /// The commands behind `flow_state`, so only those up to the time travel date if any, or
+    /// followed by those of the active scenario.
+    pub fn applied_commands(&self) -> Vec<Command> {
+        let mut commands = self.commands_up_to(self.as_of());
+        if self.as_of.is_none() {
+            if let Some(scenario) = self.active_scenario() {
+                commands.extend(scenario.commands());
+            }
+        }
+        commands
+    }
+
+    fn commands_up_to(&self, date: Option<NaiveDate>) -> Vec<Command> {
+        self.command_stack.iter().take(self.num_commands_applied)
+            .map(|record| record.redo_command.clone())
+            .filter(|command| date.is_none_or(|date| command.timestamp.date_naive() <= date))
+            .collect()
+    }
+
+    /// Forks the plan into a new scenario and makes it the active one.
+    pub fn fork_scenario(&mut self, name: &str) -> FlowResult<()> {
+        self.check_writable()?;
+        if name.is_empty() {
+            return Err(FlowError::invariant(EntityKind::Scenario, name, "needs a name"));
+        }
+        if self.scenarios.iter().any(|scenario| scenario.name == name) {
+            return Err(FlowError::conflict(EntityKind::Scenario, name, "already exists"));
+        }
+        self.scenarios.push(Scenario::fork(name, &self.flow_state));
+        self.active_scenario = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Makes the scenario called `name` the active one, or the plan if `name` is `None`.
+    pub fn switch_scenario(&mut self, name: Option<&str>) -> FlowResult<()> {
+        if let Some(name) = name {
+            self.scenario_index(name)?;
+        }
+        self.active_scenario = name.map(str::to_string);
+        Ok(())
+    }
+
+    pub fn scenarios(&self) -> &[Scenario] {
+        &self.scenarios
+    }
+
+    pub fn active_scenario(&self) -> Option<&Scenario> {
+        let name = self.active_scenario.as_ref()?;
+        self.scenarios.iter().find(|scenario| &scenario.name == name)
+    }
+
+    /// The state of the plan, whichever scenario is active.
+    pub fn plan_flow_state(&self) -> &FlowState {
+        &self.flow_state
+    }
+
+    pub fn discard_scenario(&mut self, name: &str) -> FlowResult<()> {
+        let index = self.scenario_index(name)?;
+        self.scenarios.remove(index);
+        if self.active_scenario.as_deref() == Some(name) {
+            self.active_scenario = None;
+        }
+        Ok(())
+    }
+
+    /// Replays the commands of the scenario onto the plan as a single compound command, so
+    /// that one undo takes the whole scenario back, and removes the scenario. The scenario is
+    /// kept if its commands no longer apply to the plan.
+    pub fn merge_scenario(&mut self, name: &str, timestamp: DateTime<Utc>, date: NaiveDate) -> FlowResult<()> {
+        self.check_writable()?;
+        let index = self.scenario_index(name)?;
+        let commands = self.scenarios[index].commands();
+        // The commands go to the plan, and the active scenario stays so until they succeed.
+        let active_scenario = self.active_scenario.take();
+        if !commands.is_empty() {
+            if let Err(error) = self.invoke_command(Command { timestamp, details: CommandDetails::CompoundCommand { commands } }, date) {
+                self.active_scenario = active_scenario;
+                return Err(error);
+            }
+        }
+        self.scenarios.remove(index);
+        self.active_scenario = active_scenario.filter(|active| active != name);
+        Ok(())
+    }
+
+    fn scenario_index(&self, name: &str) -> FlowResult<usize> {
+        self.scenarios.iter().position(|scenario| scenario.name == name)
+            .ok_or_else(|| FlowError::not_found(EntityKind::Scenario, name))
+    }
+
+    fn active_scenario_mut(&mut self) -> Option<&mut Scenario> {
+        let name = self.active_scenario.as_ref()?;
+        self.scenarios.iter_mut().find(|scenario| &scenario.name == name)
+    }
+
+    pub fn invoke_command(&mut self, command: Command, date: NaiveDate) -> FlowResult<()> {
+        self.check_writable()?;
+        self.check_permission(&command.details)?;
+        if let Some(scenario) = self.active_scenario_mut() {
+            return scenario.invoke_command(command, date);
+        }
         println!("Invoking command: {:?}", command);
+        let before = self.summarize_for_observers();
         let undo_command = self.flow_state.execute_command_generate_inverse_and_rebuild_cache(command.clone(), date)?;
+        self.notify_observers(CommandEventKind::Executed, &command, &undo_command, before);
         self.append_to_command_history(CommandRecord {
             undo_command,
             redo_command: command,
+            author: self.author.clone(),
         });
-        self.save_to_yaml()?;
+        self.save()?;
         Ok(())
     }
 
-    pub fn undo(&mut self, date: NaiveDate) -> Result<(), String> {
+    /// Undoes the last applied command, recorded as a history event at `timestamp`.
+    pub fn undo(&mut self, timestamp: DateTime<Utc>, date: NaiveDate) -> FlowResult<()> {
+        self.check_writable()?;
+        if let Some(scenario) = self.active_scenario_mut() {
+            return scenario.undo(date);
+        }
         if self.num_commands_applied == 0 {
-            return Err("No commands to undo".to_string());
+            return Err(FlowError::invariant(EntityKind::Project, self.key(), "has no commands to undo"));
         }
-        let command_record = &self.command_stack[self.num_commands_applied - 1];
+        let command_record = self.command_stack[self.num_commands_applied - 1].clone();
+        self.check_permission(&command_record.undo_command.details)?;
         println!("Command for undo: {:?}", command_record.undo_command);
+        let before = self.summarize_for_observers();
         self.flow_state.execute_command_generate_inverse_and_rebuild_cache(command_record.undo_command.clone(), date)?;
+        self.notify_observers(CommandEventKind::Undone, &command_record.redo_command, &command_record.undo_command, before);
         self.num_commands_applied -= 1;
-        self.save_to_yaml()?;
+        self.record_history_event(timestamp, HistoryEventKind::Undo { command: command_record.redo_command });
+        self.save()?;
         Ok(())
     }
 
-    pub fn redo(&mut self, date: NaiveDate) -> Result<(), String> {
+    /// Redoes the first undone command, recorded as a history event at `timestamp`.
+    pub fn redo(&mut self, timestamp: DateTime<Utc>, date: NaiveDate) -> FlowResult<()> {
+        self.check_writable()?;
+        if let Some(scenario) = self.active_scenario_mut() {
+            return scenario.redo(date);
+        }
         if self.num_commands_applied >= self.command_stack.len() {
-            return Err("No commands to redo".to_string());
+            return Err(FlowError::invariant(EntityKind::Project, self.key(), "has no commands to redo"));
         }
-        let command_record = &self.command_stack[self.num_commands_applied];
+        let command_record = self.command_stack[self.num_commands_applied].clone();
+        self.check_permission(&command_record.redo_command.details)?;
         println!("Command for redo: {:?}", command_record.redo_command);
+        let before = self.summarize_for_observers();
         self.flow_state.execute_command_generate_inverse_and_rebuild_cache(command_record.redo_command.clone(), date)?;
+        self.notify_observers(CommandEventKind::Redone, &command_record.redo_command, &command_record.undo_command, before);
         self.num_commands_applied += 1;
-        self.save_to_yaml()?;
+        self.record_history_event(timestamp, HistoryEventKind::Redo { command: command_record.redo_command });
+        self.save()?;
+        Ok(())
+    }
+
+    pub fn num_commands_applied(&self) -> usize {
+        self.num_commands_applied
+    }
+
+    pub fn history_events(&self) -> &[HistoryEvent] {
+        &self.history_events
+    }
+
+    fn record_history_event(&mut self, timestamp: DateTime<Utc>, kind: HistoryEventKind) {
+        self.history_events.push(HistoryEvent { timestamp, author: self.author.clone(), kind });
+    }
+
+    /// Rebuilds the state from the nearest checkpoint so that exactly the first
+    /// `num_commands_applied` commands of the command stack are applied. No undo or redo
+    /// commands are executed, so observers are told about a history reset instead. The jump is
+    /// recorded as a history event at `timestamp`.
+    pub fn jump_to(&mut self, num_commands_applied: usize, timestamp: DateTime<Utc>, date: NaiveDate) -> FlowResult<()> {
+        self.check_writable()?;
+        self.check_plan_active()?;
+        self.check_plan_editing()?;
+        if num_commands_applied > self.command_stack.len() {
+            return Err(FlowError::invariant(EntityKind::Project, self.key(),
+                format!("has only {} commands to jump to", self.command_stack.len())));
+        }
+        let commands: Vec<Command> = self.command_stack.iter().take(num_commands_applied)
+            .map(|record| record.redo_command.clone())
+            .collect();
+        let before = self.summarize_for_observers();
+        self.flow_state = FlowState::from_checkpoints(&commands, &self.checkpoints, date)?;
+        self.notify_history_reset(timestamp, before);
+        self.record_history_event(timestamp, HistoryEventKind::Jump { from: self.num_commands_applied, to: num_commands_applied });
+        self.num_commands_applied = num_commands_applied;
+        self.unsaved.tasks = None;
+        self.save()?;
         Ok(())
     }
 
+    /// Reverts the applied command at `index` alone by invoking its undo command as a new
+    /// command. Fails with a conflict if a later command changed the same entities or the undo
+    /// command no longer applies, without touching the project.
+    pub fn revert_command(&mut self, index: usize, timestamp: DateTime<Utc>, date: NaiveDate) -> FlowResult<()> {
+        self.check_writable()?;
+        self.check_plan_active()?;
+        if index >= self.num_commands_applied {
+            return Err(FlowError::not_found(EntityKind::Command, index as u64));
+        }
+        let record = &self.command_stack[index];
+        let subjects = record.redo_command.details.subjects();
+        for later in &self.command_stack[index + 1..self.num_commands_applied] {
+            if let Some((kind, key)) = later.redo_command.details.subjects().into_iter().find(|subject| subjects.contains(subject)) {
+                return Err(FlowError::conflict(EntityKind::Command, index as u64,
+                    format!("was followed by another change of {kind} {key}: {}", later.redo_command.details.describe(&self.flow_state))));
+            }
+        }
+        let command = Command { timestamp, details: record.undo_command.details.clone() };
+        self.flow_state.clone().execute_command_and_generate_inverse(command.clone())
+            .map_err(|e| FlowError::conflict(EntityKind::Command, index as u64, format!("no longer applies: {e}")))?;
+        self.invoke_command(command, date)
+    }
+
+    fn check_plan_active(&self) -> FlowResult<()> {
+        match &self.active_scenario {
+            Some(name) => Err(FlowError::invariant(EntityKind::Scenario, name, "is active, switch back to the plan to change its history")),
+            None => Ok(()),
+        }
+    }
+
+    /// Adds a checkpoint for every `CHECKPOINT_INTERVAL` applied commands that has none yet,
+    /// replaying only the commands since the previous checkpoint, and thins out the older
+    /// ones. A command that fails to replay stops the checkpoints before it, as a replay from
+    /// scratch would fail there too, and is recorded as the checkpoint error.
+    fn update_checkpoints(&mut self) {
+        self.checkpoint_error = None;
+        'checkpoints: loop {
+            let (mut flow_state, start_index, commands_hash) = match self.checkpoints.last() {
+                Some(checkpoint) => (checkpoint.flow_state.clone(), checkpoint.command_index, checkpoint.commands_hash),
+                None => (FlowState::new(), 0, Checkpoint::hash_commands(&[])),
+            };
+            let end_index = start_index + CHECKPOINT_INTERVAL;
+            if end_index > self.num_commands_applied {
+                break;
+            }
+            let commands: Vec<Command> = self.command_stack[start_index..end_index].iter()
+                .map(|record| record.redo_command.clone())
+                .collect();
+            for (index, command) in (start_index..).zip(&commands) {
+                if let Err(error) = flow_state.execute_command_and_generate_inverse(command.clone()) {
+                    self.checkpoint_error = Some(FlowError::invariant(EntityKind::Command, index as u64, format!("fails to replay: {error}")));
+                    break 'checkpoints;
+                }
+            }
+            let commands_hash = Checkpoint::extend_hash(commands_hash, &commands);
+            self.checkpoints.push(Checkpoint::new(end_index, commands_hash, flow_state));
+        }
+        Checkpoint::thin_out(&mut self.checkpoints);
+    }
+
+    /// Appends to the applied commands. The commands undone before are kept as a branch
+    /// rather than discarded.
     fn append_to_command_history(&mut self, command_record: CommandRecord) {
+        self.unsaved.add_commands_from(self.num_commands_applied);
         if self.num_commands_applied < self.command_stack.len() {
-            self.command_stack.truncate(self.num_commands_applied);
+            self.unsaved.branches = true;
+            split_off_branch(&mut self.command_stack, &mut self.branches, self.num_commands_applied);
+            let num_commands_applied = self.num_commands_applied;
+            self.checkpoints.retain(|checkpoint| checkpoint.command_index <= num_commands_applied);
         }
         self.command_stack.push(command_record);
         self.num_commands_applied = self.command_stack.len();
     }
 
+    pub fn branches(&self) -> &[UndoBranch] {
+        &self.branches
+    }
+
+    /// Makes the branch at `index` the active command stack with all of its commands applied.
+    /// The commands it replaces become a branch in turn. Observers are told about a history
+    /// reset, and the switch is recorded as a history event at `timestamp`.
+    pub fn switch_branch(&mut self, index: usize, timestamp: DateTime<Utc>, date: NaiveDate) -> FlowResult<()> {
+        self.check_writable()?;
+        self.check_plan_active()?;
+        self.check_plan_editing()?;
+        if index >= self.branches.len() {
+            return Err(FlowError::invariant(EntityKind::Project, self.key(), format!("has no undo branch {index}")));
+        }
+        let mut command_stack = self.command_stack.clone();
+        let mut branches = self.branches.clone();
+        let branch = branches.remove(index);
+        split_off_branch(&mut command_stack, &mut branches, branch.fork_index);
+        let num_branch_commands = branch.records.len();
+        command_stack.extend(branch.records);
+
+        let commands: Vec<Command> = command_stack.iter().map(|record| record.redo_command.clone()).collect();
+        let checkpoints: Vec<Checkpoint> = self.checkpoints.iter()
+            .filter(|checkpoint| checkpoint.command_index <= branch.fork_index)
+            .cloned()
+            .collect();
+        let before = self.summarize_for_observers();
+        self.flow_state = FlowState::from_checkpoints(&commands, &checkpoints, date)?;
+        self.notify_history_reset(timestamp, before);
+        self.num_commands_applied = command_stack.len();
+        self.command_stack = command_stack;
+        self.branches = branches;
+        self.checkpoints = checkpoints;
+        self.record_history_event(timestamp, HistoryEventKind::SwitchBranch { fork_index: branch.fork_index, num_commands: num_branch_commands });
+        self.unsaved.add_commands_from(branch.fork_index);
+        self.unsaved.branches = true;
+        self.unsaved.tasks = None;
+        self.save()?;
+        Ok(())
+    }
+
+    /// The current state, the state as of the time travel date, or that of the active scenario.
     pub fn flow_state(&self) -> &FlowState {
-        &self.flow_state
+        if let Some((_, flow_state)) = &self.as_of {
+            return flow_state;
+        }
+        match self.active_scenario() {
+            Some(scenario) => scenario.flow_state(),
+            None => &self.flow_state,
+        }
+    }
+
+    pub fn filename(&self) -> Option<&str> {
+        self.filename.as_deref()
+    }
+
+    pub fn checkpoints(&self) -> &[Checkpoint] {
+        &self.checkpoints
     }
 
     pub fn flow_state_mut(&mut self) -> &mut FlowState {
-        &mut self.flow_state
+        match (&mut self.as_of, &self.active_scenario) {
+            (Some((_, flow_state)), _) => flow_state,
+            (None, Some(name)) => match self.scenarios.iter_mut().find(|scenario| &scenario.name == name) {
+                Some(scenario) => scenario.flow_state_mut(),
+                None => &mut self.flow_state,
+            },
+            (None, None) => &mut self.flow_state,
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::app::*;
-    use chrono::{NaiveDate, Utc};
+    use chrono::{DateTime, NaiveDate, Utc};
+
+    /// A project seeded with commands for a test, whose file is removed when it is dropped,
+    /// even if an assertion fails first.
+    struct TestProject {
+        project: Project,
+        filename: &'static str,
+        timestamp: DateTime<Utc>,
+        date: NaiveDate,
+    }
+
+    impl TestProject {
+        fn seed(filename: &'static str, date: NaiveDate, commands: impl IntoIterator<Item = CommandDetails>) -> Self {
+            let _ = std::fs::remove_file(filename);
+            let mut project = TestProject { project: Project::new(filename), filename, timestamp: Utc::now(), date };
+            for details in commands {
+                project.invoke(details);
+            }
+            project
+        }
+
+        /// Invokes `details` at the timestamp and date of the test.
+        fn invoke(&mut self, details: CommandDetails) {
+            self.project.invoke_command(Command { timestamp: self.timestamp, details }, self.date).unwrap();
+        }
+    }
+
+    impl std::ops::Deref for TestProject {
+        type Target = Project;
+
+        fn deref(&self) -> &Project {
+            &self.project
+        }
+    }
+
+    impl std::ops::DerefMut for TestProject {
+        fn deref_mut(&mut self) -> &mut Project {
+            &mut self.project
+        }
+    }
+
+    impl Drop for TestProject {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(self.filename);
+        }
+    }
+
+    fn create_task(id: TaskId, title: &str, days: u64) -> CommandDetails {
+        CommandDetails::CreateTask { id, ticket: format!("T-{id}"), title: title.to_string(), duration: TaskDuration { days, fraction: 0 }, estimate: None }
+    }
 
     #[test]
     fn test_create_team() {
@@ -113,7 +619,7 @@ mod tests {
         let timestamp = Utc::now();
         let team_name = "Development".to_string();
 
-        let result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { name: team_name } }, timestamp.date_naive());
+        let result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { id: 1, name: team_name } }, timestamp.date_naive());
 
         assert!(result.is_ok());
         assert!(app.flow_state.teams.values().any(|team| team.name == "Development"));
@@ -125,11 +631,11 @@ mod tests {
         let timestamp = Utc::now();
         let team_name = "Development".to_string();
 
-        let result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { name: team_name } }, timestamp.date_naive());
+        let result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { id: 1, name: team_name } }, timestamp.date_naive());
         assert!(result.is_ok());
         assert!(app.flow_state.teams.values().any(|team| team.name == "Development"));
 
-        let undo_result = app.undo(timestamp.date_naive());
+        let undo_result = app.undo(timestamp, timestamp.date_naive());
         assert!(undo_result.is_ok());
         assert!(!app.flow_state.teams.values().any(|team| team.name == "Development"));
     }
@@ -140,15 +646,15 @@ mod tests {
         let timestamp = Utc::now();
         let team_name = "Development".to_string();
 
-        let result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { name: team_name } }, timestamp.date_naive());
+        let result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { id: 1, name: team_name } }, timestamp.date_naive());
         assert!(result.is_ok());
         assert!(app.flow_state.teams.values().any(|team| team.name == "Development"));
 
-        let undo_result = app.undo(timestamp.date_naive());
+        let undo_result = app.undo(timestamp, timestamp.date_naive());
         assert!(undo_result.is_ok());
         assert!(!app.flow_state.teams.values().any(|team| team.name == "Development"));
 
-        let redo_result = app.redo(timestamp.date_naive());
+        let redo_result = app.redo(timestamp, timestamp.date_naive());
         assert!(redo_result.is_ok());
         assert!(app.flow_state.teams.values().any(|team| team.name == "Development"));
     }
@@ -160,19 +666,44 @@ mod tests {
         let team_name = "Development".to_string();
         let new_team_name = "Engineering".to_string();
 
-        let create_result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { name: team_name.clone() } }, timestamp.date_naive());
+        let create_result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { id: 1, name: team_name.clone() } }, timestamp.date_naive());
         assert!(create_result.is_ok());
         assert!(app.flow_state.teams.values().any(|team| team.name == team_name));
 
-        let rename_result = app.invoke_command(Command { timestamp, details: CommandDetails::RenameTeam { old_name: team_name.clone(), new_name: new_team_name.clone() } }, timestamp.date_naive());
+        let rename_result = app.invoke_command(Command { timestamp, details: CommandDetails::RenameTeam { id: 1, new_name: new_team_name.clone() } }, timestamp.date_naive());
         assert!(rename_result.is_ok());
         assert!(app.flow_state.teams.values().any(|team| team.name == new_team_name));
 
-        let delete_result = app.invoke_command(Command { timestamp, details: CommandDetails::DeleteTeam { name: new_team_name.clone() } }, timestamp.date_naive());
+        let delete_result = app.invoke_command(Command { timestamp, details: CommandDetails::DeleteTeam { id: 1 } }, timestamp.date_naive());
         assert!(delete_result.is_ok());
         assert!(!app.flow_state.teams.values().any(|team| team.name == new_team_name));
     }
 
+    #[test]
+    fn test_command_errors_carry_entity_context() {
+        let mut app = Project::new("test_project.yaml");
+        let timestamp = Utc::now();
+        let date = timestamp.date_naive();
+
+        app.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { id: 1, name: "Development".to_string() } }, date).unwrap();
+        let duplicate = app.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { id: 2, name: "Development".to_string() } }, date);
+        assert!(matches!(duplicate, Err(FlowError::Conflict { kind: EntityKind::Team, key: EntityKey::Name(ref name), .. }) if name == "Development"));
+
+        let missing = app.invoke_command(Command { timestamp, details: CommandDetails::RenameTeam { id: 7, new_name: "QA".to_string() } }, date);
+        assert_eq!(missing, Err(FlowError::NotFound { kind: EntityKind::Team, key: EntityKey::Id(7) }));
+
+        app.invoke_command(Command { timestamp, details: CommandDetails::CreateResource { id: 1, name: "Alice".to_string(), team_id: 1 } }, date).unwrap();
+        app.invoke_command(Command { timestamp, details: CommandDetails::CreateTask { id: 1, ticket: "T-1".to_string(), title: "Task".to_string(), duration: TaskDuration { days: 1, fraction: 0 }, estimate: None } }, date).unwrap();
+        let duplicate_task = app.invoke_command(Command { timestamp, details: CommandDetails::CreateTask { id: 1, ticket: "T-2".to_string(), title: "Other".to_string(), duration: TaskDuration { days: 1, fraction: 0 }, estimate: None } }, date);
+        assert_eq!(duplicate_task, Err(FlowError::conflict(EntityKind::Task, 1, "already exists")));
+        assert_eq!(app.flow_state.tasks[&1].title, "Task");
+        assert_eq!(app.flow_state_mut().next_task_id(), 2);
+        app.invoke_command(Command { timestamp, details: CommandDetails::AssignTask { task_id: 1, resource_id: 1 } }, date).unwrap();
+        let delete_assigned = app.invoke_command(Command { timestamp, details: CommandDetails::DeleteTask { id: 1 } }, date);
+        assert!(matches!(delete_assigned, Err(FlowError::InvariantViolation { kind: EntityKind::Task, key: EntityKey::Id(1), .. })));
+        assert!(app.flow_state.tasks.contains_key(&1));
+    }
+
     #[test]
     fn test_create_rename_switch_team_delete_resource() {
         let mut app = Project::new("test_project.yaml");
@@ -181,26 +712,26 @@ mod tests {
         let resource_name = "Alice".to_string();
         let new_team_name = "Engineering".to_string();
 
-        let create_team_result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { name: team_name.clone() } }, timestamp.date_naive());
+        let create_team_result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { id: 1, name: team_name.clone() } }, timestamp.date_naive());
         assert!(create_team_result.is_ok());
         assert!(app.flow_state.teams.values().any(|team| team.name == team_name));
 
-        let create_resource_result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateResource { name: resource_name.clone(), team_name: team_name.clone() } }, timestamp.date_naive());
+        let create_resource_result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateResource { id: 1, name: resource_name.clone(), team_id: 1 } }, timestamp.date_naive());
         assert!(create_resource_result.is_ok());
         assert!(app.flow_state.resources.values().any(|res| res.name == resource_name));
 
-        let rename_team_result = app.invoke_command(Command { timestamp, details: CommandDetails::RenameTeam { old_name: team_name.clone(), new_name: new_team_name.clone() } }, timestamp.date_naive());
+        let rename_team_result = app.invoke_command(Command { timestamp, details: CommandDetails::RenameTeam { id: 1, new_name: new_team_name.clone() } }, timestamp.date_naive());
         assert!(rename_team_result.is_ok());
         assert!(app.flow_state.teams.values().any(|team| team.name == new_team_name));
 
-        let switch_team_result = app.invoke_command(Command { timestamp, details: CommandDetails::SwitchTeam { resource_name: resource_name.clone(), new_team_name: new_team_name.clone() } }, timestamp.date_naive());
+        let switch_team_result = app.invoke_command(Command { timestamp, details: CommandDetails::SwitchTeam { resource_id: 1, new_team_id: 1 } }, timestamp.date_naive());
         assert!(switch_team_result.is_ok());
         
         if let Some(resource) = app.flow_state.resources.get(&1) {
             assert_eq!(resource.team_id, 1); // Assuming the new team's ID is 1
         }
 
-        let delete_resource_result = app.invoke_command(Command { timestamp, details: CommandDetails::DeleteResource { name: resource_name.clone() } }, timestamp.date_naive());
+        let delete_resource_result = app.invoke_command(Command { timestamp, details: CommandDetails::DeleteResource { id: 1 } }, timestamp.date_naive());
         assert!(delete_resource_result.is_ok());
         assert!(!app.flow_state.resources.values().any(|res| res.name == resource_name));
     }
@@ -221,15 +752,16 @@ mod tests {
                 ticket,
                 title: title.clone(),
                 duration,
+                estimate: None,
             }}, timestamp.date_naive());
         assert!(create_task_result.is_ok());
         assert!(app.flow_state.tasks.values().any(|task| task.title == title));
 
-        let undo_result = app.undo(timestamp.date_naive());
+        let undo_result = app.undo(timestamp, timestamp.date_naive());
         assert!(undo_result.is_ok());
         assert!(!app.flow_state.tasks.values().any(|task| task.title == title));
 
-        let redo_result = app.redo(timestamp.date_naive());
+        let redo_result = app.redo(timestamp, timestamp.date_naive());
         assert!(redo_result.is_ok());
         assert!(app.flow_state.tasks.values().any(|task| task.title == title));
     }
@@ -242,20 +774,20 @@ mod tests {
         let resource_name = "Alice".to_string();
 
         // Create a team
-        let create_team_result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { name: team_name.clone() } }, timestamp.date_naive());
+        let create_team_result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { id: 1, name: team_name.clone() } }, timestamp.date_naive());
         assert!(create_team_result.is_ok());
         assert!(app.flow_state.teams.values().any(|team| team.name == team_name));
 
         // Create a resource in the team
-        let create_resource_result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateResource { name: resource_name.clone(), team_name: team_name.clone() } }, timestamp.date_naive());
+        let create_resource_result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateResource { id: 1, name: resource_name.clone(), team_id: 1 } }, timestamp.date_naive());
         assert!(create_resource_result.is_ok());
         assert!(app.flow_state.resources.values().any(|res| res.name == resource_name));
 
         // Save to YAML
-        app.save_to_yaml().unwrap();
+        app.save().unwrap();
 
         // Load from YAML
-        if let Ok(loaded_app) = Project::load_from_yaml("database.yaml", NaiveDate::from_ymd_opt(2025, 8, 22).unwrap()) {
+        if let Ok(loaded_app) = Project::load("database.yaml", NaiveDate::from_ymd_opt(2025, 8, 22).unwrap()) {
             // Verify loaded state
             assert!(loaded_app.flow_state.teams.values().any(|team| team.name == team_name));
             assert!(loaded_app.flow_state.resources.values().any(|res| res.name == resource_name));
@@ -263,6 +795,74 @@ mod tests {
 
     }
 
+    #[test]
+    fn test_checkpoint_created_and_used_on_load() {
+        let filename = "test_checkpoint_project.yaml";
+        let mut app = Project::new(filename);
+        let timestamp = Utc::now();
+        for i in 0..CHECKPOINT_INTERVAL + 5 {
+            let result = app.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { id: i as TeamId + 1, name: format!("Team {}", i) } }, timestamp.date_naive());
+            assert!(result.is_ok());
+        }
+        assert_eq!(app.checkpoints().len(), 1);
+        assert_eq!(app.checkpoints()[0].command_index, CHECKPOINT_INTERVAL);
+
+        let loaded_app = Project::load(filename, timestamp.date_naive()).unwrap();
+        assert_eq!(loaded_app.checkpoints().len(), 1);
+        assert_eq!(loaded_app.flow_state.teams.len(), CHECKPOINT_INTERVAL + 5);
+        let _ = std::fs::remove_file(filename);
+    }
+
+    #[test]
+    fn test_checkpoint_ignored_when_history_differs() {
+        let timestamp = Utc::now();
+        let commands: Vec<Command> = (0..3)
+            .map(|i| Command { timestamp, details: CommandDetails::CreateTeam { id: i as TeamId + 1, name: format!("Team {}", i) } })
+            .collect();
+        let mut flow_state = FlowState::new();
+        flow_state.execute_command_and_generate_inverse(commands[0].clone()).unwrap();
+        flow_state.execute_command_and_generate_inverse(commands[1].clone()).unwrap();
+        let checkpoint = Checkpoint::new(2, Checkpoint::hash_commands(&commands[..2]), flow_state);
+        assert!(checkpoint.matches(&commands));
+
+        let mut other_commands = commands.clone();
+        other_commands[1].details = CommandDetails::CreateTeam { id: 2, name: "Other".to_string() };
+        assert!(!checkpoint.matches(&other_commands));
+        let flow_state = FlowState::from_checkpoints(&other_commands, &[checkpoint], timestamp.date_naive()).unwrap();
+        assert!(flow_state.teams.values().any(|team| team.name == "Other"));
+        assert!(!flow_state.teams.values().any(|team| team.name == "Team 1"));
+    }
+
+    #[test]
+    fn test_checkpoints_thinned_out_to_recent_and_power_of_two() {
+        let mut checkpoints: Vec<Checkpoint> = (1..=20)
+            .map(|interval| Checkpoint::new(interval * CHECKPOINT_INTERVAL, 0, FlowState::new()))
+            .collect();
+        Checkpoint::thin_out(&mut checkpoints);
+        let intervals: Vec<usize> = checkpoints.iter().map(|checkpoint| checkpoint.command_index / CHECKPOINT_INTERVAL).collect();
+        assert_eq!(intervals, [1, 2, 4, 8, 16, 17, 18, 19, 20]);
+    }
+
+    #[test]
+    fn test_checkpoints_stop_at_command_failing_to_replay() {
+        let filename = "test_checkpoint_failure_project.yaml";
+        let mut app = Project::new(filename);
+        let timestamp = Utc::now();
+        for i in 0..CHECKPOINT_INTERVAL - 1 {
+            app.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { id: i as TeamId + 1, name: format!("Team {}", i) } }, timestamp.date_naive()).unwrap();
+        }
+        let broken = Command { timestamp, details: CommandDetails::RenameTeam { id: 999, new_name: "Missing".to_string() } };
+        app.command_stack.push(CommandRecord { undo_command: broken.clone(), redo_command: broken, author: None });
+        app.num_commands_applied = app.command_stack.len();
+        app.save().unwrap();
+
+        assert!(app.checkpoints().is_empty());
+        let index = CHECKPOINT_INTERVAL as u64 - 1;
+        assert!(matches!(app.checkpoint_error(), Some(FlowError::InvariantViolation { kind: EntityKind::Command, key: EntityKey::Id(i), .. }) if *i == index));
+        assert!(Project::load(filename, timestamp.date_naive()).is_err());
+        let _ = std::fs::remove_file(filename);
+    }
+
     #[test]
     fn test_absence_intersections() {
         let a1 = Absence {
@@ -284,4 +884,954 @@ mod tests {
         cursor += TaskDuration { days: 0, fraction: 50 };
         assert_eq!(cursor.alloced_amount, TaskDuration { days: 0, fraction: 50 });
     }
+
+    #[test]
+    fn test_incremental_cache_matches_full_rebuild() {
+        let timestamp = Utc::now();
+        let date = NaiveDate::from_ymd_opt(2025, 8, 20).unwrap();
+        let mut flow_state = FlowState::new();
+        flow_state.rebuild_cache(date);
+        let task = |id: TaskId, days: u64| CommandDetails::CreateTask {
+            id, ticket: format!("T-{}", id), title: "Task".to_string(), duration: TaskDuration { days, fraction: 0 }, estimate: None,
+        };
+        let steps = vec![
+            CommandDetails::CreateTeam { id: 1, name: "Development".to_string() },
+            CommandDetails::CreateResource { id: 1, name: "Alice".to_string(), team_id: 1 },
+            CommandDetails::CreateResource { id: 2, name: "Bob".to_string(), team_id: 1 },
+            task(1, 3), task(2, 2), task(3, 4), task(4, 1),
+            CommandDetails::AssignTask { task_id: 1, resource_id: 1 },
+            CommandDetails::AssignTask { task_id: 2, resource_id: 1 },
+            CommandDetails::AssignTask { task_id: 3, resource_id: 2 },
+            CommandDetails::SetWorklog { task_id: 3, date, resource_id: 1, fraction: 50 },
+            CommandDetails::SetAbsence { resource_id: 2, start_date: date, days: TaskDuration { days: 2, fraction: 0 } },
+            CommandDetails::AssignTask { task_id: 3, resource_id: 1 },
+            CommandDetails::PrioritizeTask { task_id: 3, to_top: true },
+            CommandDetails::UpdateTask { id: 2, ticket: "T-2".to_string(), title: "Task".to_string(), duration: TaskDuration { days: 5, fraction: 0 }, estimate: None },
+            CommandDetails::UnassignTask { task_id: 1 },
+            CommandDetails::AddMilestone { title: "Release".to_string(), date: date + chrono::Duration::days(60) },
+            CommandDetails::DeleteTask { id: 4 },
+            CommandDetails::SetWorklog { task_id: 3, date, resource_id: 1, fraction: 0 },
+        ];
+        for details in steps {
+            flow_state.execute_command_generate_inverse_and_rebuild_cache(Command { timestamp, details: details.clone() }, date).unwrap();
+            let incremental = flow_state.cache();
+            let full = FlowStateCache::from(&flow_state, date);
+            assert_eq!(incremental.task_alloc_rendering, full.task_alloc_rendering, "after {:?}", details);
+            assert_eq!(incremental.resource_absence_rendering, full.resource_absence_rendering, "after {:?}", details);
+            assert_eq!(incremental.worklogs_on_others_tasks, full.worklogs_on_others_tasks, "after {:?}", details);
+            assert_eq!(incremental.worklog_totals, full.worklog_totals, "after {:?}", details);
+            assert_eq!(incremental.unassigned_tasks, full.unassigned_tasks, "after {:?}", details);
+            assert_eq!((incremental.start_date, incremental.end_date), (full.start_date, full.end_date), "after {:?}", details);
+            assert!(!incremental.is_dirty());
+        }
+    }
+
+    #[test]
+    fn test_task_inspection_reuses_snapshots_and_can_be_cancelled() {
+        let timestamp = Utc::now();
+        let date = timestamp.date_naive();
+        let commands: Vec<Command> = vec![
+            CommandDetails::CreateTeam { id: 1, name: "Development".to_string() },
+            CommandDetails::CreateResource { id: 1, name: "Alice".to_string(), team_id: 1 },
+            CommandDetails::CreateTask { id: 1, ticket: "T-1".to_string(), title: "First".to_string(), duration: TaskDuration { days: 2, fraction: 0 }, estimate: None },
+            CommandDetails::CreateTask { id: 2, ticket: "T-2".to_string(), title: "Second".to_string(), duration: TaskDuration { days: 1, fraction: 0 }, estimate: None },
+            CommandDetails::AssignTask { task_id: 1, resource_id: 1 },
+        ].into_iter().map(|details| Command { timestamp, details }).collect();
+        let snapshots = std::sync::Mutex::new(InspectionSnapshots::default());
+
+        let first = TaskInspection::build(1, &commands, &[], date, &snapshots, &InspectionControl::default()).unwrap().unwrap();
+        let num_snapshots = snapshots.lock().unwrap().len();
+        assert!(num_snapshots > 0);
+        let second = TaskInspection::build(2, &commands, &[], date, &snapshots, &InspectionControl::default()).unwrap().unwrap();
+        assert_eq!(snapshots.lock().unwrap().len(), num_snapshots);
+        assert_eq!(first.assignee_history.get(&date), Some(&Some(1)));
+        assert_eq!(second.assignee_history.get(&date), Some(&None));
+        assert_eq!(TaskInspection::from(1, commands.clone(), &[], date).unwrap().allocations_history, first.allocations_history);
+
+        let control = InspectionControl::default();
+        control.cancel();
+        assert!(TaskInspection::build(1, &commands, &[], date, &snapshots, &control).unwrap().is_none());
+        assert!(TaskInspection::build(3, &commands, &[], date, &snapshots, &InspectionControl::default()).is_err());
+    }
+
+    #[test]
+    fn test_inspection_snapshots_stay_sparse_on_long_projects() {
+        let timestamp = Utc::now();
+        let date = timestamp.date_naive();
+        let commands: Vec<Command> = vec![
+            CommandDetails::CreateTeam { id: 1, name: "Development".to_string() },
+            CommandDetails::CreateResource { id: 1, name: "Alice".to_string(), team_id: 1 },
+            CommandDetails::CreateTask { id: 1, ticket: "T-1".to_string(), title: "Long".to_string(), duration: TaskDuration { days: 400, fraction: 0 }, estimate: None },
+            CommandDetails::AssignTask { task_id: 1, resource_id: 1 },
+        ].into_iter().map(|details| Command { timestamp, details }).collect();
+        let mut flow_state = FlowState::new();
+        flow_state.execute_command_and_generate_inverse(commands[0].clone()).unwrap();
+        let checkpoints = [Checkpoint::new(1, Checkpoint::hash_commands(&commands[..1]), flow_state)];
+        assert_eq!(Checkpoint::hash_commands_from(&checkpoints, &commands), Checkpoint::hash_commands(&commands));
+
+        let snapshots = std::sync::Mutex::new(InspectionSnapshots::default());
+        let first = TaskInspection::build(1, &commands, &checkpoints, date, &snapshots, &InspectionControl::default()).unwrap().unwrap();
+        assert!(first.allocations_history.len() as i64 > INSPECTION_SNAPSHOT_INTERVAL_DAYS * MAX_INSPECTION_SNAPSHOTS as i64);
+        let num_snapshots = snapshots.lock().unwrap().len();
+        assert!(num_snapshots > 1 && num_snapshots <= MAX_INSPECTION_SNAPSHOTS);
+        let second = TaskInspection::build(1, &commands, &checkpoints, date, &snapshots, &InspectionControl::default()).unwrap().unwrap();
+        assert_eq!(second.allocations_history, first.allocations_history);
+        assert_eq!(snapshots.lock().unwrap().len(), num_snapshots);
+    }
+
+    #[test]
+    fn test_task_inspection_annotates_changes() {
+        let timestamp = Utc::now();
+        let date = timestamp.date_naive();
+        let commands: Vec<Command> = vec![
+            CommandDetails::CreateTeam { id: 1, name: "Development".to_string() },
+            CommandDetails::CreateResource { id: 1, name: "Alice".to_string(), team_id: 1 },
+            CommandDetails::CreateTask { id: 1, ticket: "T-1".to_string(), title: "First".to_string(), duration: TaskDuration { days: 2, fraction: 0 }, estimate: None },
+            CommandDetails::CreateTask { id: 2, ticket: "T-2".to_string(), title: "Second".to_string(), duration: TaskDuration { days: 1, fraction: 0 }, estimate: None },
+            CommandDetails::AssignTask { task_id: 1, resource_id: 1 },
+            CommandDetails::CompoundCommand { commands: vec![
+                Command { timestamp, details: CommandDetails::UpdateTask { id: 1, ticket: "T-1".to_string(), title: "First".to_string(), duration: TaskDuration { days: 3, fraction: 50 }, estimate: None } },
+            ]},
+            CommandDetails::AssignTask { task_id: 2, resource_id: 1 },
+            CommandDetails::SetAbsence { resource_id: 1, start_date: date, days: TaskDuration { days: 1, fraction: 0 } },
+            CommandDetails::SetWorklog { task_id: 1, date, resource_id: 1, fraction: 10 },
+        ].into_iter().map(|details| Command { timestamp, details }).collect();
+
+        let inspection = TaskInspection::from(1, commands, &[], date).unwrap();
+        assert_eq!(inspection.estimate_history.get(&date), Some(&TaskDuration { days: 3, fraction: 50 }));
+        assert_eq!(inspection.changes_history.get(&date).unwrap(), &vec![
+            "Created with an estimate of 2.00 days".to_string(),
+            "Assigned to Alice".to_string(),
+            "Estimate changed from 2.00 days to 3.50 days".to_string(),
+            "T-2 assigned to Alice ahead of this task".to_string(),
+            format!("Alice absent for 1.00 days from {}", date),
+        ]);
+    }
+
+    #[test]
+    fn test_slip_report_attributes_moved_end_dates() {
+        let now = Utc::now();
+        let days_ago = |days: i64| now - chrono::Duration::days(days);
+        let commands = vec![
+            Command { timestamp: days_ago(20), details: CommandDetails::CreateTeam { id: 1, name: "Development".to_string() } },
+            Command { timestamp: days_ago(20), details: CommandDetails::CreateResource { id: 1, name: "Alice".to_string(), team_id: 1 } },
+            Command { timestamp: days_ago(20), details: CommandDetails::CreateTask { id: 1, ticket: "T-1".to_string(), title: "First, with a comma".to_string(), duration: TaskDuration { days: 2, fraction: 0 }, estimate: None } },
+            Command { timestamp: days_ago(20), details: CommandDetails::AssignTask { task_id: 1, resource_id: 1 } },
+            Command { timestamp: days_ago(10), details: CommandDetails::UpdateTask { id: 1, ticket: "T-1".to_string(), title: "First, with a comma".to_string(), duration: TaskDuration { days: 6, fraction: 0 }, estimate: None } },
+            Command { timestamp: days_ago(5), details: CommandDetails::CreateTask { id: 2, ticket: "T-2".to_string(), title: "Urgent".to_string(), duration: TaskDuration { days: 5, fraction: 0 }, estimate: None } },
+            Command { timestamp: days_ago(5), details: CommandDetails::AssignTask { task_id: 2, resource_id: 1 } },
+        ];
+        let snapshots = std::sync::Mutex::new(InspectionSnapshots::default());
+
+        let report = SlipReport::build(&commands, &[], now.date_naive(), None, None, &snapshots, &InspectionControl::default()).unwrap().unwrap();
+        let slip = report.slips.iter().find(|slip| slip.task_id == 1).unwrap();
+        assert!(slip.slip_days() > 0);
+        assert!(slip.causes.estimate_growth >= 4.0);
+        assert!(slip.causes.pushed >= 5.0);
+        assert!(slip.causes.missed_work > 0.0);
+        let attributed = slip.causes.estimate_growth + slip.causes.missed_work + slip.causes.absences + slip.causes.pushed;
+        assert_eq!(attributed, slip.slip_days() as f32);
+
+        let mut report = report;
+        report.sort_by(SlipColumn::Ticket, true);
+        assert_eq!(report.slips.iter().map(|slip| slip.task_id).collect::<Vec<_>>(), vec![1, 2]);
+        let csv = report.to_csv();
+        assert!(csv.starts_with("Ticket,Title,"));
+        assert!(csv.contains("T-1,\"First, with a comma\","));
+
+        let recent = SlipReport::build(&commands, &[], now.date_naive(), Some(days_ago(3).date_naive()), None, &snapshots, &InspectionControl::default()).unwrap().unwrap();
+        assert!(recent.slips.iter().all(|slip| slip.causes.estimate_growth == 0.0 && slip.causes.pushed == 0.0));
+    }
+
+    #[test]
+    fn test_resource_inspection_follows_the_whole_queue() {
+        let timestamp = Utc::now();
+        let date = timestamp.date_naive();
+        let commands: Vec<Command> = vec![
+            CommandDetails::CreateTeam { id: 1, name: "Development".to_string() },
+            CommandDetails::CreateResource { id: 1, name: "Alice".to_string(), team_id: 1 },
+            CommandDetails::CreateTask { id: 1, ticket: "T-1".to_string(), title: "First".to_string(), duration: TaskDuration { days: 1, fraction: 0 }, estimate: None },
+            CommandDetails::CreateTask { id: 2, ticket: "T-2".to_string(), title: "Second".to_string(), duration: TaskDuration { days: 1, fraction: 0 }, estimate: None },
+            CommandDetails::AssignTask { task_id: 2, resource_id: 1 },
+            CommandDetails::AssignTask { task_id: 1, resource_id: 1 },
+            CommandDetails::SetWorklog { task_id: 2, date, resource_id: 1, fraction: 50 },
+        ].into_iter().map(|details| Command { timestamp, details }).collect();
+        let snapshots = std::sync::Mutex::new(InspectionSnapshots::default());
+
+        let inspection = ResourceInspection::build(1, &commands, &[], date, &snapshots, &InspectionControl::default()).unwrap().unwrap();
+        // Assigning puts the task on top of the queue.
+        assert_eq!(inspection.tasks, vec![1, 2]);
+        assert_eq!(inspection.task_label(1), "T1");
+        assert_eq!(inspection.task_label(2), "T2");
+        assert_eq!(inspection.worklogs_history[&date].get(&date), Some(&50));
+        let scheduled_tasks: std::collections::BTreeSet<TaskId> = inspection.allocations_history[&date].values()
+            .flatten()
+            .map(|(task_id, _)| *task_id)
+            .collect();
+        assert_eq!(scheduled_tasks, [1, 2].into_iter().collect());
+        assert!(ResourceInspection::build(2, &commands, &[], date, &snapshots, &InspectionControl::default()).is_err());
+    }
+
+    #[test]
+    fn test_group_inspection_sums_matching_tasks() {
+        let timestamp = Utc::now();
+        let date = timestamp.date_naive();
+        let task = |id: TaskId| CommandDetails::CreateTask {
+            id, ticket: format!("T-{}", id), title: "Task".to_string(), duration: TaskDuration { days: 2, fraction: 0 }, estimate: None,
+        };
+        let commands: Vec<Command> = vec![
+            CommandDetails::CreateTeam { id: 1, name: "Development".to_string() },
+            CommandDetails::CreateResource { id: 1, name: "Alice".to_string(), team_id: 1 },
+            CommandDetails::CreateResource { id: 2, name: "Bob".to_string(), team_id: 1 },
+            CommandDetails::CreateLabel { id: 1, name: "Epic".to_string() },
+            task(1), task(2), task(3),
+            CommandDetails::AddLabelToTask { task_id: 1, label_id: 1 },
+            CommandDetails::AddLabelToTask { task_id: 2, label_id: 1 },
+            CommandDetails::AssignTask { task_id: 1, resource_id: 1 },
+            CommandDetails::AssignTask { task_id: 2, resource_id: 2 },
+            CommandDetails::AssignTask { task_id: 3, resource_id: 2 },
+            CommandDetails::SetWorklog { task_id: 1, date, resource_id: 1, fraction: 50 },
+            CommandDetails::SetWorklog { task_id: 2, date, resource_id: 2, fraction: 30 },
+            CommandDetails::SetWorklog { task_id: 3, date, resource_id: 2, fraction: 20 },
+            CommandDetails::CreateModifyFilter { id: 1, name: "Epic work".to_string(), labels: vec![1], is_favorite: false },
+        ].into_iter().map(|details| Command { timestamp, details }).collect();
+        let snapshots = std::sync::Mutex::new(InspectionSnapshots::default());
+
+        for group in [TaskGroup::Label(1), TaskGroup::Filter(1)] {
+            let inspection = GroupInspection::build(group, &commands, &[], date, &snapshots, &InspectionControl::default()).unwrap().unwrap();
+            assert_eq!(inspection.logged_person_days(date, date), 0.8);
+            let contributors: Vec<TaskId> = inspection.contributions(date, date).iter().map(|c| c.task_id).collect();
+            assert_eq!(contributors, vec![1, 2]);
+            let (_, remaining) = inspection.totals(date);
+            assert!(remaining > 0.0);
+        }
+        assert!(GroupInspection::build(TaskGroup::Label(2), &commands, &[], date, &snapshots, &InspectionControl::default()).is_err());
+    }
+
+    #[test]
+    fn test_load_legacy_tuple_project_file() {
+        let filename = "test_legacy_project.yaml";
+        let timestamp = Utc::now();
+        let command_stack = vec![LegacyCommandRecord {
+            undo_command: LegacyCommand { timestamp, details: LegacyCommandDetails::DeleteTeam { name: "Development".to_string() } },
+            redo_command: LegacyCommand { timestamp, details: LegacyCommandDetails::CreateTeam { name: "Development".to_string() } },
+        }];
+        std::fs::write(filename, serde_yaml::to_string(&(1usize, &command_stack)).unwrap()).unwrap();
+
+        let project = Project::load(filename, timestamp.date_naive());
+        std::fs::remove_file(filename).unwrap();
+
+        let project = project.unwrap();
+        assert!(!project.is_read_only());
+        assert!(project.flow_state.teams.values().any(|team| team.name == "Development"));
+    }
+
+    #[test]
+    fn test_newer_project_file_opens_read_only() {
+        let filename = "test_newer_project.yaml";
+        let timestamp = Utc::now();
+        let mut project = Project::new(filename);
+        project.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { id: 1, name: "Development".to_string() } }, timestamp.date_naive()).unwrap();
+        let contents = std::fs::read_to_string(filename).unwrap()
+            .replace(&format!("format_version: {}", PROJECT_FILE_FORMAT_VERSION), &format!("format_version: {}", PROJECT_FILE_FORMAT_VERSION + 1));
+        std::fs::write(filename, contents).unwrap();
+
+        let project = Project::load(filename, timestamp.date_naive());
+        std::fs::remove_file(filename).unwrap();
+
+        let mut project = project.unwrap();
+        assert!(project.is_read_only());
+        assert!(project.load_warning().is_some());
+        assert!(project.flow_state.teams.values().any(|team| team.name == "Development"));
+        assert!(project.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { id: 2, name: "QA".to_string() } }, timestamp.date_naive()).is_err());
+    }
+
+    #[test]
+    fn test_time_travel_shows_past_state_read_only() {
+        let filename = "test_time_travel_project.yaml";
+        let timestamp = Utc::now();
+        let yesterday = timestamp - chrono::Duration::days(1);
+        let mut project = Project::new(filename);
+        project.invoke_command(Command { timestamp: yesterday, details: CommandDetails::CreateTeam { id: 1, name: "Development".to_string() } }, timestamp.date_naive()).unwrap();
+        project.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { id: 2, name: "QA".to_string() } }, timestamp.date_naive()).unwrap();
+
+        project.travel_to(yesterday.date_naive()).unwrap();
+        assert_eq!(project.as_of(), Some(yesterday.date_naive()));
+        assert!(project.flow_state().teams.contains_key(&1));
+        assert!(!project.flow_state().teams.contains_key(&2));
+        assert_eq!(project.applied_commands().len(), 1);
+        let result = project.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { id: 3, name: "Ops".to_string() } }, timestamp.date_naive());
+
+        project.return_to_now();
+        std::fs::remove_file(filename).unwrap();
+
+        assert!(result.is_err());
+        assert!(project.flow_state().teams.contains_key(&2));
+        assert!(!project.flow_state().teams.contains_key(&3));
+    }
+
+    #[test]
+    fn test_scenario_diff_and_merge() {
+        let date = Utc::now().date_naive();
+        let one_day = TaskDuration { days: 1, fraction: 0 };
+        let mut project = TestProject::seed("test_scenario_project.yaml", date, [
+            CommandDetails::CreateTeam { id: 1, name: "Development".to_string() },
+            CommandDetails::CreateResource { id: 1, name: "Alice".to_string(), team_id: 1 },
+            create_task(1, "First", 1),
+            CommandDetails::AssignTask { task_id: 1, resource_id: 1 },
+            CommandDetails::AddMilestone { title: "Release".to_string(), date: date + chrono::Duration::days(60) },
+        ]);
+        let timestamp = project.timestamp;
+        let plan_end_date = project.flow_state().cache().task_end_date(1).unwrap();
+
+        project.fork_scenario("Longer").unwrap();
+        assert!(project.fork_scenario("Longer").is_err());
+        let longer = TaskDuration { days: 10, fraction: 0 };
+        project.invoke(CommandDetails::UpdateTask { id: 1, ticket: "T-1".to_string(), title: "First".to_string(), duration: longer, estimate: None });
+        assert_eq!(project.plan_flow_state().tasks[&1].duration, one_day);
+        assert_eq!(project.flow_state().tasks[&1].duration, longer);
+
+        let diff = ScenarioDiff::between(project.plan_flow_state(), project.flow_state());
+        assert_eq!(diff.tasks.len(), 1);
+        assert_eq!(diff.tasks[0].plan_end_date, Some(plan_end_date));
+        assert!(diff.tasks[0].shift_days().unwrap() > 0);
+        assert_eq!(diff.milestones.len(), 1);
+        assert_eq!(diff.milestones[0].tasks_done_in_plan, 1);
+
+        let num_commands = project.command_stack.len();
+        project.merge_scenario("Longer", timestamp, date).unwrap();
+        assert!(project.scenarios().is_empty());
+        assert_eq!(project.command_stack.len(), num_commands + 1);
+        assert_eq!(project.flow_state().tasks[&1].duration, longer);
+
+        project.undo(timestamp, date).unwrap();
+        project.fork_scenario("Discarded").unwrap();
+        project.invoke(CommandDetails::UnassignTask { task_id: 1 });
+        project.discard_scenario("Discarded").unwrap();
+
+        // A scenario that no longer applies to the plan stays active after a failed merge.
+        project.fork_scenario("Stale").unwrap();
+        project.invoke(CommandDetails::UnassignTask { task_id: 1 });
+        project.switch_scenario(None).unwrap();
+        project.invoke(CommandDetails::UnassignTask { task_id: 1 });
+        project.switch_scenario(Some("Stale")).unwrap();
+        assert!(project.merge_scenario("Stale", timestamp, date).is_err());
+        assert_eq!(project.active_scenario().map(|scenario| scenario.name.as_str()), Some("Stale"));
+        project.discard_scenario("Stale").unwrap();
+        project.undo(timestamp, date).unwrap();
+
+        assert_eq!(project.flow_state().tasks[&1].duration, one_day);
+        assert_eq!(project.flow_state().tasks[&1].assignee, Some(1));
+    }
+
+    #[test]
+    fn test_history_jump_and_selective_revert() {
+        let date = Utc::now().date_naive();
+        let mut project = TestProject::seed("test_history_project.yaml", date, [
+            CommandDetails::CreateTeam { id: 1, name: "Development".to_string() },
+            CommandDetails::RenameTeam { id: 1, new_name: "Engineering".to_string() },
+            CommandDetails::CreateLabel { id: 1, name: "Backend".to_string() },
+            CommandDetails::RenameLabel { id: 1, new_name: "Server".to_string() },
+        ]);
+        let timestamp = project.timestamp;
+        assert_eq!(project.command_stack[0].redo_command.details.describe(project.flow_state()), "Create team Development");
+
+        let conflict = project.revert_command(0, timestamp, date);
+        assert!(matches!(conflict, Err(FlowError::Conflict { kind: EntityKind::Command, key: EntityKey::Id(0), .. })));
+        assert_eq!(project.command_stack.len(), 4);
+
+        project.revert_command(3, timestamp, date).unwrap();
+        assert_eq!(project.command_stack.len(), 5);
+        assert_eq!(project.flow_state().labels[&1].name, "Backend");
+
+        project.jump_to(1, timestamp, date).unwrap();
+        assert_eq!(project.num_commands_applied(), 1);
+        assert_eq!(project.flow_state().teams[&1].name, "Development");
+        assert!(project.flow_state().labels.is_empty());
+        assert!(project.jump_to(6, timestamp, date).is_err());
+
+        project.jump_to(5, timestamp, date).unwrap();
+        assert!(matches!(project.history_events(), [
+            HistoryEvent { kind: HistoryEventKind::Jump { from: 5, to: 1 }, .. },
+            HistoryEvent { kind: HistoryEventKind::Jump { from: 1, to: 5 }, .. },
+        ]));
+
+        assert_eq!(project.flow_state().teams[&1].name, "Engineering");
+        assert_eq!(project.flow_state().labels[&1].name, "Backend");
+    }
+
+    #[test]
+    fn test_undo_tree_keeps_and_switches_branches() {
+        let date = Utc::now().date_naive();
+        let filename = "test_undo_tree_project.db";
+        let mut project = TestProject::seed(filename, date, []);
+        let timestamp = project.timestamp;
+        project.invoke(CommandDetails::CreateTeam { id: 1, name: "Development".to_string() });
+        project.invoke(CommandDetails::CreateLabel { id: 1, name: "Backend".to_string() });
+        project.invoke(CommandDetails::CreateLabel { id: 2, name: "Frontend".to_string() });
+        project.undo(timestamp, date).unwrap();
+        project.undo(timestamp, date).unwrap();
+        project.invoke(CommandDetails::CreateTeam { id: 2, name: "QA".to_string() });
+        assert_eq!(project.branches().len(), 1);
+        assert_eq!(project.branches()[0].fork_index, 1);
+        assert_eq!(project.branches()[0].records.len(), 2);
+
+        project.switch_branch(0, timestamp, date).unwrap();
+        assert_eq!(project.command_stack.len(), 3);
+        assert_eq!(project.num_commands_applied(), 3);
+        assert_eq!(project.flow_state().labels.len(), 2);
+        assert!(!project.flow_state().teams.contains_key(&2));
+        assert_eq!(project.branches().len(), 1);
+        assert_eq!(project.branches()[0].records.len(), 1);
+
+        // Undoing past the fork rebases the existing branch onto the new fork.
+        for _ in 0..3 {
+            project.undo(timestamp, date).unwrap();
+        }
+        project.invoke(CommandDetails::CreateLabel { id: 3, name: "Ops".to_string() });
+        assert_eq!(project.branches().len(), 2);
+        assert!(project.branches().iter().all(|branch| branch.fork_index == 0));
+        let qa_branch = project.branches().iter()
+            .position(|branch| branch.records.len() == 2 && matches!(branch.records[1].redo_command.details, CommandDetails::CreateTeam { id: 2, .. }))
+            .unwrap();
+
+        let loaded = Project::load(filename, date).unwrap();
+        assert_eq!(loaded.branches().len(), 2);
+        project.switch_branch(qa_branch, timestamp, date).unwrap();
+
+        assert_eq!(project.flow_state().teams.len(), 2);
+        assert!(project.flow_state().labels.is_empty());
+        assert_eq!(project.branches().len(), 2);
+    }
+
+    #[test]
+    fn test_command_authors_persist_and_filter_the_audit_log() {
+        let date = Utc::now().date_naive();
+        let filename = "test_audit_log_project.db";
+        let mut project = TestProject::seed(filename, date, []);
+        let timestamp = project.timestamp;
+        project.set_author(Some("alice".to_string()));
+        project.invoke(CommandDetails::CreateTeam { id: 1, name: "Development".to_string() });
+        project.invoke(create_task(1, "Task, with comma", 1));
+        project.set_author(Some("bob".to_string()));
+        project.invoke(CommandDetails::RenameTeam { id: 1, new_name: "Engineering".to_string() });
+        project.set_author(Some("carol".to_string()));
+        let later = timestamp + chrono::Duration::seconds(1);
+        project.undo(later, date).unwrap();
+        project.undo(later, date).unwrap();
+
+        let loaded = Project::load(filename, date);
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.history_events().len(), 2);
+        assert_eq!(AuditLog::authors(&loaded.command_stack, loaded.history_events()), vec!["alice".to_string(), "bob".to_string(), "carol".to_string()]);
+        let build = |filter: &AuditFilter| AuditLog::build(&loaded.command_stack, loaded.num_commands_applied(), loaded.history_events(), loaded.flow_state(), filter);
+
+        let all = build(&AuditFilter::default());
+        assert_eq!(all.entries.iter().map(|entry| entry.index).collect::<Vec<_>>(), vec![None, None, Some(2), Some(1), Some(0)]);
+        assert_eq!(all.entries[0].description, "Undo: Create task T-1 'Task, with comma' of 1.00 days");
+        assert_eq!(all.entries[0].author.as_deref(), Some("carol"));
+
+        let by_alice = AuditFilter { author: Some("alice".to_string()), ..Default::default() };
+        assert_eq!(build(&by_alice).entries.len(), 2);
+
+        // Who took the task away is found by the task, even though its creation was not theirs.
+        let on_task = AuditFilter { entity_kind: Some(EntityKind::Task), entity_key: Some(EntityKey::Id(1)), ..Default::default() };
+        let on_task = build(&on_task);
+        assert_eq!(on_task.entries.iter().map(|entry| entry.author.as_deref()).collect::<Vec<_>>(), vec![Some("carol"), Some("alice")]);
+
+        let team_key = AuditFilter::resolve_entity_key(loaded.flow_state(), EntityKind::Team, "Development");
+        let on_team = AuditFilter { entity_kind: Some(EntityKind::Team), entity_key: Some(team_key), ..Default::default() };
+        assert_eq!(build(&on_team).entries.len(), 3);
+
+        let tomorrow = AuditFilter { from: later.date_naive().succ_opt(), ..Default::default() };
+        assert!(build(&tomorrow).entries.is_empty());
+
+        let csv = all.to_csv();
+        assert!(csv.starts_with("Index,Timestamp,Author,Command,Entities,Applied\n"));
+        assert!(csv.contains("\"Create task T-1 'Task, with comma' of 1.00 days\""));
+    }
+
+    #[test]
+    fn test_roles_restrict_commands_to_permitted_users() {
+        let date = Utc::now().date_naive();
+        let filename = "test_permissions_project.yaml";
+        let mut project = TestProject::seed(filename, date, []);
+        let timestamp = project.timestamp;
+        project.set_author(Some("alice".to_string()));
+        for details in [
+            CommandDetails::CreateTeam { id: 1, name: "Development".to_string() },
+            CommandDetails::CreateResource { id: 1, name: "Bob".to_string(), team_id: 1 },
+            CommandDetails::CreateResource { id: 2, name: "Carol".to_string(), team_id: 1 },
+            create_task(1, "Task", 2),
+            CommandDetails::AssignTask { task_id: 1, resource_id: 1 },
+        ] {
+            project.invoke(details);
+        }
+        let developer = Member { role: Role::Developer, resource_id: Some(1) };
+        assert!(project.set_member("bob", Some(developer.clone())).is_err());
+        project.set_member("alice", Some(Member { role: Role::Admin, resource_id: None })).unwrap();
+        project.set_member("bob", Some(developer)).unwrap();
+
+        project.set_author(Some("bob".to_string()));
+        let absence = |resource_id| Command { timestamp, details: CommandDetails::SetAbsence { resource_id, start_date: date, days: TaskDuration { days: 1, fraction: 0 } } };
+        project.invoke(CommandDetails::SetWorklog { task_id: 1, date, resource_id: 1, fraction: 50 });
+        project.undo(timestamp, date).unwrap();
+        project.invoke_command(absence(1), date).unwrap();
+        let denied = project.invoke_command(absence(2), date);
+        assert!(matches!(denied, Err(FlowError::PermissionDenied { ref user, .. }) if user == "bob"));
+        assert!(project.invoke_command(Command { timestamp, details: CommandDetails::UnassignTask { task_id: 1 } }, date).is_err());
+        assert!(project.jump_to(0, timestamp, date).is_err());
+        assert!(project.set_member("bob", Some(Member { role: Role::Admin, resource_id: None })).is_err());
+
+        project.set_author(Some("carol".to_string()));
+        assert!(project.invoke_command(absence(2), date).is_err());
+
+        project.set_author(Some("alice".to_string()));
+        assert!(project.set_member("alice", None).is_err());
+        project.invoke_command(absence(2), date).unwrap();
+
+        let loaded = Project::load(filename, date);
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.permissions().members.len(), 2);
+        assert_eq!(loaded.permissions().member(Some("bob")).unwrap().role, Role::Developer);
+    }
+
+    #[test]
+    fn test_observers_receive_executed_undone_and_redone_commands_and_history_resets() {
+        fn assert_send_and_sync<T: Send + Sync>() {}
+        assert_send_and_sync::<Project>();
+
+        struct Recorder {
+            events: Vec<(CommandEventKind, String, usize, usize)>,
+        }
+        impl CommandObserver for Recorder {
+            fn on_command(&mut self, event: &CommandEvent) {
+                let is_reset = event.kind == CommandEventKind::HistoryReset;
+                assert_eq!(matches!(event.inverse.details, CommandDetails::Void), is_reset);
+                self.events.push((
+                    event.kind,
+                    format!("{:?}", event.command.details),
+                    event.before.num_unassigned_tasks,
+                    event.after.num_unassigned_tasks));
+            }
+        }
+
+        let date = Utc::now().date_naive();
+        let mut project = TestProject::seed("test_observer_project.yaml", date, []);
+        let timestamp = project.timestamp;
+        let recorder = std::sync::Arc::new(std::sync::Mutex::new(Recorder { events: Vec::new() }));
+        let observer: SharedCommandObserver = recorder.clone();
+        project.register_observer(observer.clone());
+        for details in [
+            CommandDetails::CreateTeam { id: 1, name: "Development".to_string() },
+            CommandDetails::CreateResource { id: 1, name: "Bob".to_string(), team_id: 1 },
+            create_task(1, "Task", 2),
+            CommandDetails::AssignTask { task_id: 1, resource_id: 1 },
+        ] {
+            project.invoke(details);
+        }
+        project.undo(timestamp, date).unwrap();
+        project.redo(timestamp, date).unwrap();
+        project.jump_to(3, timestamp, date).unwrap();
+        project.fork_scenario("Sandbox").unwrap();
+        project.invoke(CommandDetails::CreateTeam { id: 2, name: "QA".to_string() });
+        project.switch_scenario(None).unwrap();
+        project.unregister_observer(&observer);
+        project.invoke(CommandDetails::CreateTeam { id: 3, name: "Ops".to_string() });
+
+        let events = &recorder.lock().unwrap().events;
+        let kinds: Vec<CommandEventKind> = events.iter().map(|event| event.0).collect();
+        assert_eq!(kinds, [
+            CommandEventKind::Executed,
+            CommandEventKind::Executed,
+            CommandEventKind::Executed,
+            CommandEventKind::Executed,
+            CommandEventKind::Undone,
+            CommandEventKind::Redone,
+            CommandEventKind::HistoryReset,
+        ]);
+        // Undoing and redoing report the assignment itself, not the command that was executed.
+        assert!(events[4].1.starts_with("AssignTask"));
+        assert_eq!(events[5].1, events[4].1);
+        assert_eq!((events[4].2, events[4].3), (0, 1));
+        assert_eq!((events[5].2, events[5].3), (1, 0));
+        assert_eq!((events[6].2, events[6].3), (0, 1));
+    }
+
+    #[test]
+    fn test_script_reads_state_and_emits_one_undoable_command() {
+        let date = Utc::now().date_naive();
+        let mut project = TestProject::seed("test_script_project.yaml", date, [
+            CommandDetails::CreateTeam { id: 1, name: "Development".to_string() },
+            CommandDetails::CreateResource { id: 1, name: "Bob".to_string(), team_id: 1 },
+            CommandDetails::CreateLabel { id: 1, name: "Blocked".to_string() },
+            CommandDetails::CreateLabel { id: 2, name: "Triaged".to_string() },
+            create_task(1, "Blocked task", 2),
+            create_task(2, "Other task", 1),
+            CommandDetails::AssignTask { task_id: 1, resource_id: 1 },
+            CommandDetails::AssignTask { task_id: 2, resource_id: 1 },
+            CommandDetails::AddLabelToTask { task_id: 1, label_id: 1 },
+        ]);
+        let timestamp = project.timestamp;
+
+        let source = r#"
+            for task in state.tasks {
+                if task.labels.contains("Blocked") {
+                    unassign_task(task.id);
+                    emit(#{ AddLabelToTask: #{ task_id: task.id, label_id: 2 } });
+                }
+            }
+            for resource in state.resources {
+                let total = 0;
+                for day in resource.load.keys() {
+                    total += resource.load[day];
+                }
+                print(`${resource.name}: ${total}%`);
+            }
+        "#;
+        let output = run_script("triage", source, project.flow_state(), date).unwrap();
+        assert_eq!(output.printed, ["Bob: 300%"]);
+        assert_eq!(output.commands.len(), 2);
+        project.invoke_command(output.into_command(timestamp).unwrap(), date).unwrap();
+        assert_eq!(project.flow_state().tasks[&1].assignee, None);
+        assert!(project.flow_state().tasks[&1].label_ids.contains(&2));
+
+        project.undo(timestamp, date).unwrap();
+        assert_eq!(project.flow_state().tasks[&1].assignee, Some(1));
+        assert!(!project.flow_state().tasks[&1].label_ids.contains(&2));
+
+        let runaway = run_script("runaway", "loop { }", project.flow_state(), date);
+        assert!(matches!(runaway, Err(FlowError::Script { ref name, .. }) if name == "runaway"));
+        assert!(run_script("sandboxed", r#"eval("1")"#, project.flow_state(), date).is_err());
+        let module = std::env::temp_dir().join("test_script_module.rhai");
+        std::fs::write(&module, "export const ANSWER = 42;").unwrap();
+        let import = format!("import {:?} as m; print(m::ANSWER);", module.display().to_string());
+        let imported = run_script("sandboxed", &import, project.flow_state(), date);
+        let _ = std::fs::remove_file(&module);
+        assert!(imported.is_err());
+        let doubling = r#"let s = "xxxxxxxxxxxxxxxx"; for i in 0..26 { s += s; }"#;
+        assert!(run_script("doubling", doubling, project.flow_state(), date).is_err());
+    }
+
+    #[test]
+    fn test_query_tasks_resources_and_worklogs() {
+        let date = NaiveDate::from_ymd_opt(2026, 11, 2).unwrap();
+        let project = TestProject::seed("test_query_project.yaml", date, [
+            CommandDetails::CreateTeam { id: 1, name: "Backend".to_string() },
+            CommandDetails::CreateTeam { id: 2, name: "Frontend".to_string() },
+            CommandDetails::CreateResource { id: 1, name: "Bob".to_string(), team_id: 1 },
+            CommandDetails::CreateResource { id: 2, name: "Carol".to_string(), team_id: 1 },
+            CommandDetails::CreateResource { id: 3, name: "Dave".to_string(), team_id: 2 },
+            create_task(1, "Long", 5),
+            create_task(2, "Short", 1),
+            create_task(3, "Frontend", 5),
+            CommandDetails::AssignTask { task_id: 1, resource_id: 1 },
+            CommandDetails::AssignTask { task_id: 2, resource_id: 2 },
+            CommandDetails::AssignTask { task_id: 3, resource_id: 3 },
+            CommandDetails::SetWorklog { task_id: 2, date, resource_id: 2, fraction: 50 },
+            CommandDetails::AddMilestone { title: "Code Freeze".to_string(), date: NaiveDate::from_ymd_opt(2026, 11, 4).unwrap() },
+        ]);
+        let flow_state = project.flow_state();
+        let tickets = |text: &str| run_query(text, flow_state, date).unwrap().rows.iter()
+            .map(|row| row[0].clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(tickets(r#"tasks where assignee.team = "Backend" and end > milestone("Code Freeze")"#), ["T-1"]);
+        assert_eq!(tickets("resources where load(2026-11-02..2026-11-06) > 0.9"), ["Bob", "Dave"]);
+        assert_eq!(tickets("resources where not (team = 'Backend') or tasks = 0"), ["Dave"]);
+        assert_eq!(tickets("tasks where remaining < 1 and title contains 'sho'"), ["T-2"]);
+        assert_eq!(tickets("worklogs where resource.name = \"Carol\" and fraction >= 0.5"), ["2026-11-02"]);
+        assert_eq!(tickets("tasks").len(), 3);
+
+        let result = run_query("tasks where ticket = 'T-1'", flow_state, date).unwrap();
+        assert_eq!(result.columns, ["Ticket", "Title", "Assignee", "Start", "End", "Remaining"]);
+        assert_eq!(result.rows, [["T-1", "Long", "Bob", "2026-11-02", "2026-11-06", "5"]]);
+        assert!(result.to_text().starts_with("Ticket  Title  Assignee  Start       End         Remaining\n"));
+
+        assert!(matches!(run_query("tasks where colour = 'red'", flow_state, date), Err(FlowError::Query { .. })));
+        assert!(run_query("milestones", flow_state, date).is_err());
+        assert!(run_query("tasks where (end > today()", flow_state, date).is_err());
+        assert!(run_query("tasks where title", flow_state, date).is_err());
+    }
+
+    #[test]
+    fn test_leveling_proposes_assignments_and_applies_them_as_one_command() {
+        let date = NaiveDate::from_ymd_opt(2026, 11, 2).unwrap();
+        let mut project = TestProject::seed("test_leveling_project.yaml", date, [
+            CommandDetails::CreateTeam { id: 1, name: "Backend".to_string() },
+            CommandDetails::CreateTeam { id: 2, name: "Frontend".to_string() },
+            CommandDetails::CreateResource { id: 1, name: "Bob".to_string(), team_id: 1 },
+            CommandDetails::CreateResource { id: 2, name: "Carol".to_string(), team_id: 1 },
+            CommandDetails::CreateResource { id: 3, name: "Dave".to_string(), team_id: 2 },
+            CommandDetails::CreateLabel { id: 1, name: "Database".to_string() },
+            create_task(1, "Schema", 5),
+            create_task(2, "Migration", 3),
+            create_task(3, "Fix", 1),
+            CommandDetails::AddLabelToTask { task_id: 1, label_id: 1 },
+            CommandDetails::AddLabelToTask { task_id: 2, label_id: 1 },
+            CommandDetails::AssignTask { task_id: 1, resource_id: 1 },
+        ]);
+        let timestamp = project.timestamp;
+        let placed = |proposal: &LevelingProposal| proposal.assignments.iter()
+            .map(|assignment| (assignment.ticket.clone(), assignment.resource_name.clone(), assignment.position))
+            .collect::<Vec<_>>();
+
+        // The idle developer takes both, the short task first so that it is done soonest.
+        let options = LevelingOptions { team_id: Some(1), match_skills: false, objective: LevelingObjective::FinishDate };
+        let proposal = propose_leveling(project.flow_state(), &options, date, &InspectionControl::default()).unwrap().unwrap();
+        assert_eq!(placed(&proposal), [("T-2".to_string(), "Carol".to_string(), 0), ("T-3".to_string(), "Carol".to_string(), 0)]);
+        assert_eq!(proposal.score_after.finish_date, NaiveDate::from_ymd_opt(2026, 11, 6));
+
+        // Only Bob has worked on database tasks, and the shorter one goes first.
+        let options = LevelingOptions { match_skills: true, ..options };
+        let proposal = propose_leveling(project.flow_state(), &options, date, &InspectionControl::default()).unwrap().unwrap();
+        assert_eq!(placed(&proposal), [("T-2".to_string(), "Bob".to_string(), 0), ("T-3".to_string(), "Carol".to_string(), 0)]);
+        assert_eq!(proposal.assignments[0].end_date, NaiveDate::from_ymd_opt(2026, 11, 4));
+
+        let num_commands = project.num_commands_applied();
+        project.invoke_command(proposal.to_command(timestamp).unwrap(), date).unwrap();
+        assert_eq!(project.num_commands_applied(), num_commands + 1);
+        assert_eq!(project.flow_state().resources[&1].assigned_tasks, [2, 1]);
+        assert_eq!(project.flow_state().resources[&2].assigned_tasks, [3]);
+        assert_eq!(project.flow_state().cache().task_end_date(2), proposal.assignments[0].end_date);
+        project.undo(timestamp, date).unwrap();
+        assert!(project.flow_state().tasks[&2].assignee.is_none());
+
+        let options = LevelingOptions { team_id: Some(2), match_skills: true, objective: LevelingObjective::MilestoneLateness };
+        let proposal = propose_leveling(project.flow_state(), &options, date, &InspectionControl::default()).unwrap().unwrap();
+        assert_eq!(proposal.unplaced, [2]);
+
+        // A task longer than the queue goes after it.
+        project.invoke(create_task(4, "Sharding", 10));
+        project.invoke(CommandDetails::AddLabelToTask { task_id: 4, label_id: 1 });
+        let options = LevelingOptions { team_id: Some(1), match_skills: true, objective: LevelingObjective::FinishDate };
+        let control = InspectionControl::default();
+        control.cancel();
+        assert!(propose_leveling(project.flow_state(), &options, date, &control).unwrap().is_none());
+        let proposal = propose_leveling(project.flow_state(), &options, date, &InspectionControl::default()).unwrap().unwrap();
+        assert_eq!(placed(&proposal)[0], ("T-4".to_string(), "Bob".to_string(), 1));
+        project.invoke_command(proposal.to_command(timestamp).unwrap(), date).unwrap();
+        assert_eq!(project.flow_state().resources[&1].assigned_tasks.last(), Some(&4));
+    }
+
+    #[test]
+    fn test_priority_optimizer_gets_must_haves_done_by_the_milestone() {
+        let date = NaiveDate::from_ymd_opt(2026, 11, 2).unwrap();
+        let mut details = vec![
+            CommandDetails::CreateTeam { id: 1, name: "Backend".to_string() },
+            CommandDetails::CreateResource { id: 1, name: "Bob".to_string(), team_id: 1 },
+            CommandDetails::CreateResource { id: 2, name: "Carol".to_string(), team_id: 1 },
+            CommandDetails::CreateLabel { id: 1, name: "Must".to_string() },
+            CommandDetails::AddMilestone { title: "Code Freeze".to_string(), date: NaiveDate::from_ymd_opt(2026, 11, 6).unwrap() },
+        ];
+        for (id, days, resource_id, must_have) in [(1, 5, 1, false), (2, 2, 1, true), (3, 3, 2, true), (4, 3, 2, true)] {
+            details.push(create_task(id, "Task", days));
+            details.push(CommandDetails::AssignTask { task_id: id, resource_id });
+            if must_have {
+                details.push(CommandDetails::AddLabelToTask { task_id: id, label_id: 1 });
+            }
+        }
+        // Assigning puts tasks on top, so move the first ones back to the top of the queues.
+        details.push(CommandDetails::PrioritizeTask { task_id: 1, to_top: true });
+        details.push(CommandDetails::PrioritizeTask { task_id: 3, to_top: true });
+        let mut project = TestProject::seed("test_priority_optimizer_project.yaml", date, details);
+        let timestamp = project.timestamp;
+
+        let goal = PriorityGoal { milestone: "Code Freeze".to_string(), label_id: 1, allow_reassignment: false };
+        let plan = optimize_priorities(project.flow_state(), &goal, date, &InspectionControl::default()).unwrap().unwrap();
+        assert_eq!(plan.must_haves, [2, 3, 4]);
+        assert_eq!(plan.late_before, [2, 4]);
+        assert_eq!(plan.late_after, [4]);
+        let shifts: Vec<(TaskId, Option<i64>)> = plan.diff.tasks.iter().map(|change| (change.task_id, change.shift_days())).collect();
+        assert_eq!(shifts, [(1, Some(4)), (2, Some(-7))]);
+
+        let goal = PriorityGoal { allow_reassignment: true, ..goal };
+        let plan = optimize_priorities(project.flow_state(), &goal, date, &InspectionControl::default()).unwrap().unwrap();
+        assert!(plan.late_after.is_empty());
+        let num_commands = project.num_commands_applied();
+        project.invoke_command(plan.to_command(timestamp).unwrap(), date).unwrap();
+        assert_eq!(project.num_commands_applied(), num_commands + 1);
+        assert_eq!(project.flow_state().resources[&1].assigned_tasks, [2, 4, 1]);
+        assert_eq!(project.flow_state().resources[&2].assigned_tasks, [3]);
+        assert_eq!(project.flow_state().cache().task_end_date(4), Some(plan.milestone_date));
+
+        project.undo(timestamp, date).unwrap();
+        assert_eq!(project.flow_state().resources[&1].assigned_tasks, [1, 2]);
+        let control = InspectionControl::default();
+        control.cancel();
+        assert!(optimize_priorities(project.flow_state(), &goal, date, &control).unwrap().is_none());
+        assert!(optimize_priorities(project.flow_state(), &PriorityGoal { milestone: "Launch".to_string(), ..goal }, date, &control).is_err());
+    }
+
+    #[test]
+    fn test_forecast_scales_remaining_work_by_historical_ratios() {
+        let date = NaiveDate::from_ymd_opt(2026, 11, 2).unwrap();
+        let mut details = vec![
+            CommandDetails::CreateTeam { id: 1, name: "Backend".to_string() },
+            CommandDetails::CreateResource { id: 1, name: "Bob".to_string(), team_id: 1 },
+            create_task(1, "Schema", 2),
+            create_task(2, "Migration", 3),
+            CommandDetails::AssignTask { task_id: 2, resource_id: 1 },
+            CommandDetails::AddMilestone { title: "Code Freeze".to_string(), date: NaiveDate::from_ymd_opt(2026, 11, 6).unwrap() },
+        ];
+        // Bob took twice as long as estimated for T-1, which he finished last week.
+        for day in 26..30 {
+            details.push(CommandDetails::SetWorklog { task_id: 1, date: NaiveDate::from_ymd_opt(2026, 10, day).unwrap(), resource_id: 1, fraction: 100 });
+        }
+        let project = TestProject::seed("test_forecast_project.yaml", date, details);
+        assert_eq!(estimate_ratios(&project.applied_commands(), project.flow_state()), [(1, 2.0)].into());
+
+        // Without uncertainty T-2 takes six days instead of three, past the milestone.
+        let options = ForecastOptions { iterations: 10, optimistic_factor: 1.0, pessimistic_factor: 1.0, ..Default::default() };
+        let forecast = run_forecast(project.flow_state(), &project.applied_commands(), &options, date, &InspectionControl::default()).unwrap();
+        assert_eq!(forecast.tasks.len(), 1);
+        let distribution = &forecast.tasks[0].distribution;
+        assert_eq!(forecast.tasks[0].planned_end_date, NaiveDate::from_ymd_opt(2026, 11, 4).unwrap());
+        assert_eq!((distribution.p50, distribution.p95), (NaiveDate::from_ymd_opt(2026, 11, 9).unwrap(), NaiveDate::from_ymd_opt(2026, 11, 9).unwrap()));
+        assert_eq!(distribution.histogram, [(NaiveDate::from_ymd_opt(2026, 11, 9).unwrap(), 10)]);
+        assert_eq!(forecast.milestones[0].num_tasks, 1);
+        assert_eq!(forecast.milestones[0].probability, 0.0);
+
+        let options = ForecastOptions { iterations: 500, seed: 7, ..Default::default() };
+        let forecast = run_forecast(project.flow_state(), &project.applied_commands(), &options, date, &InspectionControl::default()).unwrap();
+        let distribution = &forecast.tasks[0].distribution;
+        assert!(distribution.p50 <= distribution.p80 && distribution.p80 <= distribution.p95);
+        assert!(distribution.p50 > NaiveDate::from_ymd_opt(2026, 11, 6).unwrap());
+        assert_eq!(distribution.histogram.iter().map(|(_, count)| count).sum::<usize>(), 500);
+        assert_eq!(forecast, run_forecast(project.flow_state(), &project.applied_commands(), &options, date, &InspectionControl::default()).unwrap());
+
+        let control = InspectionControl::default();
+        control.cancel();
+        assert!(run_forecast(project.flow_state(), &project.applied_commands(), &options, date, &control).is_none());
+    }
+
+    #[test]
+    fn test_three_point_estimates_schedule_by_the_project_basis() {
+        let date = NaiveDate::from_ymd_opt(2026, 11, 2).unwrap();
+        let day = |day: u32| NaiveDate::from_ymd_opt(2026, 11, day).unwrap();
+        let days = |days: u64| TaskDuration { days, fraction: 0 };
+        let estimate = ThreePointEstimate { optimistic: days(2), pessimistic: days(6) };
+        let mut project = TestProject::seed("test_three_point_estimates_project.yaml", date, [
+            CommandDetails::CreateTeam { id: 1, name: "Backend".to_string() },
+            CommandDetails::CreateResource { id: 1, name: "Bob".to_string(), team_id: 1 },
+            CommandDetails::CreateTask { id: 1, ticket: "T-1".to_string(), title: "Schema".to_string(), duration: days(3), estimate: Some(estimate) },
+            CommandDetails::AssignTask { task_id: 1, resource_id: 1 },
+        ]);
+        let timestamp = project.timestamp;
+        let end_date = |project: &Project| project.flow_state().cache().task_end_date(1).unwrap();
+        assert_eq!(end_date(&project), day(4));
+        let spread_days = project.flow_state().cache().task_spread_days(project.flow_state(), 1);
+        assert_eq!(spread_days, [day(5), day(6), day(9)]);
+
+        project.invoke(CommandDetails::SetEstimateBasis { basis: EstimateBasis::Pessimistic });
+        assert_eq!(end_date(&project), day(9));
+        assert!(project.flow_state().cache().task_spread_days(project.flow_state(), 1).is_empty());
+        project.invoke(CommandDetails::SetEstimateBasis { basis: EstimateBasis::PertMean });
+        assert_eq!(estimate.pert_mean(days(3)), TaskDuration { days: 3, fraction: 33 });
+        assert_eq!(end_date(&project), day(5));
+        project.undo(timestamp, date).unwrap();
+        assert_eq!(project.flow_state().estimate_basis, EstimateBasis::Pessimistic);
+        assert_eq!(end_date(&project), day(9));
+
+        // The duration must lie within the range, and updating without one drops it until undone.
+        let update = |duration: TaskDuration, estimate: Option<ThreePointEstimate>| Command { timestamp, details: CommandDetails::UpdateTask {
+            id: 1, ticket: "T-1".to_string(), title: "Schema".to_string(), duration, estimate,
+        }};
+        assert!(project.invoke_command(update(days(7), Some(estimate)), date).is_err());
+        project.invoke_command(update(days(3), None), date).unwrap();
+        assert_eq!(end_date(&project), day(4));
+        project.undo(timestamp, date).unwrap();
+        assert_eq!(project.flow_state().tasks[&1].estimate, Some(estimate));
+
+        // Plain durations serialize as before.
+        let plain = CommandDetails::CreateTask { id: 2, ticket: "T-2".to_string(), title: "Plain".to_string(), duration: days(1), estimate: None };
+        let yaml = serde_yaml::to_string(&plain).unwrap();
+        assert!(!yaml.contains("estimate"));
+        let parsed: CommandDetails = serde_yaml::from_str(&yaml).unwrap();
+        assert!(matches!(parsed, CommandDetails::CreateTask { estimate: None, .. }));
+
+        // Forecasts draw from the range of the task rather than the default one.
+        let options = ForecastOptions { iterations: 200, optimistic_factor: 1.0, pessimistic_factor: 1.0, ..Default::default() };
+        let forecast = run_forecast(project.flow_state(), &project.applied_commands(), &options, date, &InspectionControl::default()).unwrap();
+        let distribution = &forecast.tasks[0].distribution;
+        assert!(distribution.histogram.len() > 1);
+        assert!(distribution.histogram.first().unwrap().0 >= day(3) && distribution.histogram.last().unwrap().0 <= day(9));
+    }
+
+    #[test]
+    fn test_sqlite_storage_round_trip_and_conversion() {
+        let yaml_filename = "test_convert_project.yaml";
+        let sqlite_filename = "test_convert_project.db";
+        let _ = std::fs::remove_file(sqlite_filename);
+        let timestamp = Utc::now();
+        let date = timestamp.date_naive();
+        let mut project = Project::new(sqlite_filename);
+        project.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { id: 1, name: "Development".to_string() } }, date).unwrap();
+        project.invoke_command(Command { timestamp, details: CommandDetails::CreateTeam { id: 2, name: "QA".to_string() } }, date).unwrap();
+        project.undo(timestamp, date).unwrap();
+
+        let loaded = Project::load(sqlite_filename, date).unwrap();
+        assert_eq!(loaded.command_stack.len(), 2);
+        assert!(loaded.flow_state.teams.values().any(|team| team.name == "Development"));
+        assert!(!loaded.flow_state.teams.values().any(|team| team.name == "QA"));
+
+        // Saves write only what changed, including a replaced command and a deleted task.
+        let one_day = TaskDuration { days: 1, fraction: 0 };
+        for details in [
+            CommandDetails::CreateTeam { id: 3, name: "Support".to_string() },
+            CommandDetails::CreateTask { id: 1, ticket: "T-1".to_string(), title: "Kept".to_string(), duration: one_day, estimate: None },
+            CommandDetails::CreateTask { id: 2, ticket: "T-2".to_string(), title: "Deleted".to_string(), duration: one_day, estimate: None },
+            CommandDetails::DeleteTask { id: 2 },
+        ] {
+            project.invoke_command(Command { timestamp, details }, date).unwrap();
+        }
+        let contents = std::fs::read(sqlite_filename).unwrap();
+        let loaded = Project::load(sqlite_filename, date).unwrap();
+        assert_eq!(std::fs::read(sqlite_filename).unwrap(), contents);
+        assert_eq!(loaded.command_stack.len(), 5);
+        assert_eq!(loaded.branches().len(), 1);
+        assert!(loaded.flow_state.teams.values().any(|team| team.name == "Support"));
+        let connection = rusqlite::Connection::open(sqlite_filename).unwrap();
+        let task_titles: Vec<String> = connection.prepare("SELECT title FROM tasks").unwrap()
+            .query_map([], |row| row.get(0)).unwrap()
+            .collect::<Result<_, _>>().unwrap();
+        assert_eq!(task_titles, ["Kept"]);
+
+        convert_project_file(sqlite_filename, yaml_filename, date).unwrap();
+        let converted = Project::load(yaml_filename, date);
+        std::fs::remove_file(sqlite_filename).unwrap();
+        std::fs::remove_file(yaml_filename).unwrap();
+
+        let converted = converted.unwrap();
+        assert_eq!(converted.command_stack.len(), 5);
+        assert!(converted.flow_state.teams.values().any(|team| team.name == "Development"));
+        assert!(!converted.flow_state.teams.values().any(|team| team.name == "QA"));
+    }
+
+    #[test]
+    fn test_legacy_names_migrate_to_ids_across_renames() {
+        let timestamp = Utc::now();
+        let legacy = |details| LegacyCommand { timestamp, details };
+        let records = vec![
+            LegacyCommandDetails::CreateTeam { name: "Development".to_string() },
+            LegacyCommandDetails::RenameTeam { old_name: "Development".to_string(), new_name: "Engineering".to_string() },
+            LegacyCommandDetails::CreateResource { name: "Alice".to_string(), team_name: "Engineering".to_string() },
+            LegacyCommandDetails::CreateLabel { name: "Backend".to_string() },
+        ].into_iter()
+            .map(|details| LegacyCommandRecord { undo_command: legacy(LegacyCommandDetails::Void), redo_command: legacy(details) })
+            .collect();
+
+        let command_stack = migrate_legacy_command_stack(records).unwrap();
+        assert!(matches!(command_stack[1].redo_command.details, CommandDetails::RenameTeam { id: 1, .. }));
+        assert!(matches!(command_stack[2].redo_command.details, CommandDetails::CreateResource { id: 1, team_id: 1, .. }));
+        assert!(matches!(command_stack[1].undo_command.details, CommandDetails::RenameTeam { id: 1, ref new_name } if new_name == "Development"));
+
+        let mut flow_state = FlowState::from_commands(
+            &command_stack.iter().map(|record| record.redo_command.clone()).collect::<Vec<_>>(),
+            timestamp.date_naive()).unwrap();
+        assert_eq!(flow_state.resources[&1].team_id, 1);
+        flow_state.execute_command_and_generate_inverse(Command { timestamp, details: CommandDetails::CreateLabel { id: 2, name: "Frontend".to_string() } }).unwrap();
+        let duplicate = flow_state.execute_command_and_generate_inverse(Command { timestamp, details: CommandDetails::RenameLabel { id: 2, new_name: "Backend".to_string() } });
+        assert!(duplicate.is_err());
+    }
 }
@@ -0,0 +1,281 @@
+use crate::app::*;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::borrow::Cow;
+
+/// Version written by this build. Bump it together with a new entry in `MIGRATIONS`
+/// whenever the encoding of `CommandRecord` (or anything it contains) changes.
+pub const PROJECT_FILE_FORMAT_VERSION: u32 = 8;
+
+/// `MIGRATIONS[n]` upgrades a document of format version `n` to version `n + 1`.
+const MIGRATIONS: [fn(Value) -> FlowResult<Value>; PROJECT_FILE_FORMAT_VERSION as usize] = [
+    migrate_v0_to_v1,
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+    migrate_v4_to_v5,
+    migrate_v5_to_v6,
+    migrate_v6_to_v7,
+    migrate_v7_to_v8,
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectFileHeader {
+    pub format_version: u32,
+    pub app_version: String,
+}
+
+/// The history of a project as stored. Saving borrows it from the `Project`, loading owns it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectFile<'a> {
+    pub header: ProjectFileHeader,
+    pub num_commands_applied: usize,
+    pub command_stack: Cow<'a, [CommandRecord]>,
+    #[serde(default)]
+    pub checkpoints: Cow<'a, [Checkpoint]>,
+    /// Redo chains off the active command stack; older files have none.
+    #[serde(default)]
+    pub branches: Cow<'a, [UndoBranch]>,
+    #[serde(default)]
+    pub permissions: Cow<'a, Permissions>,
+    /// Undos, redos, jumps and branch switches; older files have none.
+    #[serde(default)]
+    pub history_events: Cow<'a, [HistoryEvent]>,
+}
+
+/// Result of reading a project file. `read_only` is set when the file was written by a newer
+/// version of FlowState; `warning` then explains what could not be understood.
+#[derive(Debug, Clone)]
+pub struct LoadedProjectFile {
+    pub file: ProjectFile<'static>,
+    pub read_only: bool,
+    pub warning: Option<String>,
+}
+
+impl<'a> ProjectFile<'a> {
+    pub fn new(
+        num_commands_applied: usize,
+        command_stack: &'a [CommandRecord],
+        checkpoints: &'a [Checkpoint],
+        branches: &'a [UndoBranch],
+        permissions: &'a Permissions,
+        history_events: &'a [HistoryEvent],
+    ) -> Self {
+        Self {
+            header: ProjectFileHeader {
+                format_version: PROJECT_FILE_FORMAT_VERSION,
+                app_version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            num_commands_applied,
+            command_stack: Cow::Borrowed(command_stack),
+            checkpoints: Cow::Borrowed(checkpoints),
+            branches: Cow::Borrowed(branches),
+            permissions: Cow::Borrowed(permissions),
+            history_events: Cow::Borrowed(history_events),
+        }
+    }
+
+    pub fn to_yaml_string(&self) -> FlowResult<String> {
+        serde_yaml::to_string(self).map_err(|e| FlowError::parse(format!("Failed to serialize to YAML: {}", e)))
+    }
+
+    pub fn from_yaml_str(contents: &str) -> FlowResult<LoadedProjectFile> {
+        let value: Value = serde_yaml::from_str(contents)
+            .map_err(|e| FlowError::parse(format!("Failed to parse YAML: {}", e)))?;
+        ProjectFile::from_value(value)
+    }
+
+    /// Migrates a document of any known format version to the current one before deserializing it.
+    pub fn from_value(mut value: Value) -> FlowResult<LoadedProjectFile> {
+        let version = ProjectFile::detect_format_version(&value)?;
+
+        if version > PROJECT_FILE_FORMAT_VERSION {
+            return ProjectFile::from_newer_version(value, version);
+        }
+        for migration in &MIGRATIONS[version as usize..] {
+            value = migration(value)?;
+        }
+        let file: ProjectFile = serde_yaml::from_value(value)
+            .map_err(|e| FlowError::parse(format!("Failed to deserialize YAML: {}", e)))?;
+        Ok(LoadedProjectFile { file, read_only: false, warning: None })
+    }
+
+    fn detect_format_version(value: &Value) -> FlowResult<u32> {
+        match value {
+            // Version 0 was a bare `(num_commands_applied, command_stack)` tuple,
+            // version 1 added the checkpoints as a third element.
+            Value::Sequence(items) if items.len() == 2 => Ok(0),
+            Value::Sequence(items) if items.len() == 3 => Ok(1),
+            Value::Mapping(_) => value.get("header")
+                .and_then(|header| header.get("format_version"))
+                .and_then(Value::as_u64)
+                .map(|version| version as u32)
+                .ok_or_else(|| FlowError::parse("Project file has no format version in its header")),
+            _ => Err(FlowError::parse("Unrecognized project file layout")),
+        }
+    }
+
+    /// Best-effort read of a file from a newer FlowState: keeps the longest prefix of the
+    /// command stack this build understands so the project can at least be viewed.
+    fn from_newer_version(value: Value, version: u32) -> FlowResult<LoadedProjectFile> {
+        let header: ProjectFileHeader = value.get("header").cloned()
+            .and_then(|header| serde_yaml::from_value(header).ok())
+            .ok_or_else(|| FlowError::parse("Failed to read project file header"))?;
+        let num_commands_applied = value.get("num_commands_applied")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| FlowError::parse("Failed to read number of applied commands"))? as usize;
+        let records = value.get("command_stack")
+            .and_then(Value::as_sequence)
+            .cloned()
+            .unwrap_or_default();
+        let total_records = records.len();
+        let command_stack: Vec<CommandRecord> = records.into_iter()
+            .map_while(|record| serde_yaml::from_value(record).ok())
+            .collect();
+
+        let mut warning = format!(
+            "Project file was written by FlowState {} (format version {}), newer than the supported version {}; it is opened read-only",
+            header.app_version, version, PROJECT_FILE_FORMAT_VERSION);
+        if command_stack.len() < total_records {
+            warning.push_str(&format!(
+                " and only the first {} of {} commands could be read",
+                command_stack.len(), total_records));
+        }
+        Ok(LoadedProjectFile {
+            file: ProjectFile {
+                header,
+                num_commands_applied: num_commands_applied.min(command_stack.len()),
+                command_stack: Cow::Owned(command_stack),
+                checkpoints: Cow::Owned(Vec::new()),
+                branches: Cow::Owned(Vec::new()),
+                permissions: Cow::Owned(Permissions::default()),
+                history_events: Cow::Owned(Vec::new()),
+            },
+            read_only: true,
+            warning: Some(warning),
+        })
+    }
+}
+
+/// Calls `f` on every serialized `Command` of a migrated document, including the ones
+/// nested inside `CompoundCommand`s and those of the undo branches, so migrations can rewrite
+/// individual command encodings.
+pub fn for_each_command_value(value: &mut Value, f: &mut dyn FnMut(&mut Value)) {
+    if let Some(records) = value.get_mut("command_stack").and_then(Value::as_sequence_mut) {
+        visit_record_values(records, f);
+    }
+    if let Some(branches) = value.get_mut("branches").and_then(Value::as_sequence_mut) {
+        for branch in branches {
+            if let Some(records) = branch.get_mut("records").and_then(Value::as_sequence_mut) {
+                visit_record_values(records, f);
+            }
+        }
+    }
+}
+
+fn visit_record_values(records: &mut [Value], f: &mut dyn FnMut(&mut Value)) {
+    for record in records {
+        for key in ["undo_command", "redo_command"] {
+            if let Some(command) = record.get_mut(key) {
+                visit_command_value(command, f);
+            }
+        }
+    }
+}
+
+fn visit_command_value(command: &mut Value, f: &mut dyn FnMut(&mut Value)) {
+    f(command);
+    if let Some(Value::Tagged(tagged)) = command.get_mut("details") {
+        if tagged.tag == "CompoundCommand" {
+            if let Some(commands) = tagged.value.get_mut("commands").and_then(Value::as_sequence_mut) {
+                for inner in commands {
+                    visit_command_value(inner, f);
+                }
+            }
+        }
+    }
+}
+
+fn migrate_v0_to_v1(value: Value) -> FlowResult<Value> {
+    match value {
+        Value::Sequence(mut items) => {
+            items.push(Value::Sequence(Vec::new()));
+            Ok(Value::Sequence(items))
+        }
+        _ => Err(FlowError::parse("Expected a version 0 project file")),
+    }
+}
+
+fn migrate_v1_to_v2(value: Value) -> FlowResult<Value> {
+    let Value::Sequence(items) = value else {
+        return Err(FlowError::parse("Expected a version 1 project file"));
+    };
+    let [num_commands_applied, command_stack, checkpoints]: [Value; 3] = items.try_into()
+        .map_err(|_| FlowError::parse("Expected a version 1 project file"))?;
+    let mut header = serde_yaml::Mapping::new();
+    header.insert("format_version".into(), 2.into());
+    header.insert("app_version".into(), env!("CARGO_PKG_VERSION").into());
+    let mut file = serde_yaml::Mapping::new();
+    file.insert("header".into(), Value::Mapping(header));
+    file.insert("num_commands_applied".into(), num_commands_applied);
+    file.insert("command_stack".into(), command_stack);
+    file.insert("checkpoints".into(), checkpoints);
+    Ok(Value::Mapping(file))
+}
+
+/// Commands switched from name-based to ID-based references. The stored checkpoints were
+/// hashed over the old encoding, so they are dropped and rebuilt on the next save.
+fn migrate_v2_to_v3(mut value: Value) -> FlowResult<Value> {
+    let records = value.get_mut("command_stack")
+        .map(std::mem::take)
+        .ok_or_else(|| FlowError::parse("Expected a version 2 project file"))?;
+    let records: Vec<LegacyCommandRecord> = serde_yaml::from_value(records)
+        .map_err(|e| FlowError::parse(format!("Failed to deserialize YAML: {}", e)))?;
+    let command_stack = migrate_legacy_command_stack(records)?;
+    let file = value.as_mapping_mut()
+        .ok_or_else(|| FlowError::parse("Expected a version 2 project file"))?;
+    file.insert("command_stack".into(), serde_yaml::to_value(command_stack)
+        .map_err(|e| FlowError::parse(format!("Failed to serialize to YAML: {}", e)))?);
+    file.insert("checkpoints".into(), Value::Sequence(Vec::new()));
+    if let Some(header) = file.get_mut("header").and_then(Value::as_mapping_mut) {
+        header.insert("format_version".into(), 3.into());
+    }
+    Ok(value)
+}
+
+/// Files gained the undo branches, which default to none when missing.
+fn migrate_v3_to_v4(value: Value) -> FlowResult<Value> {
+    bump_format_version(value, 3)
+}
+
+/// Command records gained their author, which is unknown for the older ones.
+fn migrate_v4_to_v5(value: Value) -> FlowResult<Value> {
+    bump_format_version(value, 4)
+}
+
+/// Files gained the permissions of their members, which default to an open project without
+/// members.
+fn migrate_v5_to_v6(value: Value) -> FlowResult<Value> {
+    bump_format_version(value, 5)
+}
+
+/// Tasks gained optional three-point estimates and the plan an estimate basis. Both default
+/// when missing, so version 6 commands read as they are.
+fn migrate_v6_to_v7(value: Value) -> FlowResult<Value> {
+    bump_format_version(value, 6)
+}
+
+/// Files gained the undos, redos, jumps and branch switches for the audit log, which default
+/// to none when missing.
+fn migrate_v7_to_v8(value: Value) -> FlowResult<Value> {
+    bump_format_version(value, 7)
+}
+
+/// Migration of a version whose additions all default when missing.
+fn bump_format_version(mut value: Value, version: u32) -> FlowResult<Value> {
+    let header = value.get_mut("header")
+        .and_then(Value::as_mapping_mut)
+        .ok_or_else(|| FlowError::parse(format!("Expected a version {} project file", version)))?;
+    header.insert("format_version".into(), (version + 1).into());
+    Ok(value)
+}
@@ -0,0 +1,614 @@
+use crate::app::*;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::cmp::Ordering;
+
+/// What a query lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryEntity {
+    Tasks,
+    Resources,
+    Worklogs,
+}
+
+impl QueryEntity {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "tasks" => Some(QueryEntity::Tasks),
+            "resources" => Some(QueryEntity::Resources),
+            "worklogs" => Some(QueryEntity::Worklogs),
+            _ => None,
+        }
+    }
+
+    /// Headers of the result columns and the fields shown in them.
+    fn columns(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            QueryEntity::Tasks => &[("Ticket", "ticket"), ("Title", "title"), ("Assignee", "assignee"), ("Start", "start"), ("End", "end"), ("Remaining", "remaining")],
+            QueryEntity::Resources => &[("Name", "name"), ("Team", "team"), ("Tasks", "tasks"), ("End", "end")],
+            QueryEntity::Worklogs => &[("Date", "date"), ("Task", "task"), ("Resource", "resource"), ("Fraction", "fraction")],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Literal(Value),
+    /// Field names looked up one after the other, starting from the row.
+    Path(Vec<String>),
+    Call(String, Vec<Expr>),
+    Range(Box<Expr>, Box<Expr>),
+    Compare(Box<Expr>, CompareOp, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+/// A value a query expression evaluates to. Entities compare by their name, or ticket for tasks.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Text(String),
+    Date(NaiveDate),
+    List(Vec<Value>),
+    DateRange(NaiveDate, NaiveDate),
+    Task(TaskId),
+    Resource(ResourceId),
+    Team(TeamId),
+    Worklog { task_id: TaskId, resource_id: ResourceId, date: NaiveDate },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Text(String),
+    Number(f64),
+    Date(NaiveDate),
+    Op(CompareOp),
+    LeftParen,
+    RightParen,
+    Comma,
+    Dot,
+    DotDot,
+}
+
+fn tokenize(text: &str) -> FlowResult<Vec<Token>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => { tokens.push(Token::LeftParen); i += 1; }
+            ')' => { tokens.push(Token::RightParen); i += 1; }
+            ',' => { tokens.push(Token::Comma); i += 1; }
+            '.' if next == Some('.') => { tokens.push(Token::DotDot); i += 2; }
+            '.' => { tokens.push(Token::Dot); i += 1; }
+            '=' => { tokens.push(Token::Op(CompareOp::Eq)); i += if next == Some('=') { 2 } else { 1 }; }
+            '!' if next == Some('=') => { tokens.push(Token::Op(CompareOp::Ne)); i += 2; }
+            '<' if next == Some('=') => { tokens.push(Token::Op(CompareOp::Le)); i += 2; }
+            '<' => { tokens.push(Token::Op(CompareOp::Lt)); i += 1; }
+            '>' if next == Some('=') => { tokens.push(Token::Op(CompareOp::Ge)); i += 2; }
+            '>' => { tokens.push(Token::Op(CompareOp::Gt)); i += 1; }
+            '"' | '\'' => {
+                let end = chars[i + 1..].iter().position(|&d| d == c)
+                    .ok_or_else(|| FlowError::query(format!("unterminated string starting at {}", i + 1)))?;
+                tokens.push(Token::Text(chars[i + 1..i + 1 + end].iter().collect()));
+                i += end + 2;
+            }
+            c if c.is_ascii_digit() => {
+                let candidate: String = chars[i..(i + 10).min(chars.len())].iter().collect();
+                if let Ok(date) = NaiveDate::parse_from_str(&candidate, "%Y-%m-%d") {
+                    tokens.push(Token::Date(date));
+                    i += 10;
+                    continue;
+                }
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit()
+                        || (chars[i] == '.' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit()))) {
+                    i += 1;
+                }
+                let number: String = chars[start..i].iter().collect();
+                tokens.push(Token::Number(number.parse().map_err(|_| FlowError::query(format!("invalid number '{number}'")))?));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            c => return Err(FlowError::query(format!("unexpected '{c}' at {}", i + 1))),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent over the tokens, lowest precedence first: `or`, `and`, `not`,
+/// comparisons, then values.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(name)) if name.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect(&mut self, expected: Token) -> FlowResult<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(FlowError::query(format!("expected {expected:?}, found {token:?}"))),
+            None => Err(FlowError::query(format!("expected {expected:?} at the end"))),
+        }
+    }
+
+    fn parse_or(&mut self) -> FlowResult<Expr> {
+        let mut expr = self.parse_and()?;
+        while self.is_keyword("or") {
+            self.position += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> FlowResult<Expr> {
+        let mut expr = self.parse_not()?;
+        while self.is_keyword("and") {
+            self.position += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_not()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> FlowResult<Expr> {
+        if self.is_keyword("not") {
+            self.position += 1;
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> FlowResult<Expr> {
+        let left = self.parse_value()?;
+        let op = match self.peek() {
+            Some(Token::Op(op)) => *op,
+            _ if self.is_keyword("contains") => CompareOp::Contains,
+            _ => return Ok(left),
+        };
+        self.position += 1;
+        let right = self.parse_value()?;
+        Ok(Expr::Compare(Box::new(left), op, Box::new(right)))
+    }
+
+    fn parse_value(&mut self) -> FlowResult<Expr> {
+        let value = match self.next() {
+            Some(Token::Text(text)) => Expr::Literal(Value::Text(text)),
+            Some(Token::Number(number)) => Expr::Literal(Value::Number(number)),
+            Some(Token::Date(date)) => Expr::Literal(Value::Date(date)),
+            Some(Token::LeftParen) => {
+                let expr = self.parse_or()?;
+                self.expect(Token::RightParen)?;
+                expr
+            }
+            Some(Token::Ident(name)) => match name.to_lowercase().as_str() {
+                "true" => Expr::Literal(Value::Bool(true)),
+                "false" => Expr::Literal(Value::Bool(false)),
+                "null" => Expr::Literal(Value::Null),
+                _ if self.peek() == Some(&Token::LeftParen) => {
+                    self.position += 1;
+                    let mut arguments = Vec::new();
+                    while self.peek() != Some(&Token::RightParen) {
+                        if !arguments.is_empty() {
+                            self.expect(Token::Comma)?;
+                        }
+                        arguments.push(self.parse_range()?);
+                    }
+                    self.position += 1;
+                    Expr::Call(name.to_lowercase(), arguments)
+                }
+                _ => {
+                    let mut path = vec![name];
+                    while self.peek() == Some(&Token::Dot) {
+                        self.position += 1;
+                        match self.next() {
+                            Some(Token::Ident(field)) => path.push(field),
+                            _ => return Err(FlowError::query(format!("expected a field name after '{}.'", path.join(".")))),
+                        }
+                    }
+                    Expr::Path(path)
+                }
+            },
+            Some(token) => return Err(FlowError::query(format!("unexpected {token:?}"))),
+            None => return Err(FlowError::query("unexpected end of query")),
+        };
+        Ok(value)
+    }
+
+    fn parse_range(&mut self) -> FlowResult<Expr> {
+        let start = self.parse_or()?;
+        if self.peek() != Some(&Token::DotDot) {
+            return Ok(start);
+        }
+        self.position += 1;
+        Ok(Expr::Range(Box::new(start), Box::new(self.parse_or()?)))
+    }
+}
+
+/// A parsed query: an entity to list, optionally followed by `where` and a condition.
+///
+/// Conditions compare fields with `=`, `!=`, `<`, `<=`, `>`, `>=` and `contains`, and combine
+/// them with `and`, `or`, `not` and parentheses. Dates are written `YYYY-MM-DD` and text in
+/// double or single quotes. Fields:
+/// - tasks: `id`, `ticket`, `title`, `duration`, `remaining` (days), `start`, `end`,
+///   `assignee`, `labels`, `watchers`, `unassigned`
+/// - resources: `id`, `name`, `team`, `tasks` (count), `end`, `load(date)` or
+///   `load(date..date)`, the average fraction of a weekday allocated
+/// - worklogs: `date`, `fraction`, `task`, `resource`
+/// - teams, reached through `resource.team`: `id`, `name`, `resources` (count)
+///
+/// Entity fields can be followed into, e.g. `assignee.team = "Backend"`. The functions
+/// `milestone("title")` and `today()` give dates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub entity: QueryEntity,
+    condition: Option<Expr>,
+}
+
+/// The rows a query matched, formatted for display.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryResult {
+    pub columns: Vec<&'static str>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Query {
+    pub fn parse(text: &str) -> FlowResult<Query> {
+        let mut parser = Parser { tokens: tokenize(text)?, position: 0 };
+        let entity = match parser.next() {
+            Some(Token::Ident(name)) => QueryEntity::parse(&name)
+                .ok_or_else(|| FlowError::query(format!("cannot query '{name}', use tasks, resources or worklogs")))?,
+            _ => return Err(FlowError::query("a query starts with tasks, resources or worklogs")),
+        };
+        let condition = if parser.is_keyword("where") {
+            parser.position += 1;
+            Some(parser.parse_or()?)
+        } else {
+            None
+        };
+        if let Some(token) = parser.peek() {
+            return Err(FlowError::query(format!("unexpected {token:?}")));
+        }
+        Ok(Query { entity, condition })
+    }
+
+    /// Evaluates the query against `flow_state`, with `today()` being `date`.
+    pub fn run(&self, flow_state: &FlowState, date: NaiveDate) -> FlowResult<QueryResult> {
+        let context = Context { flow_state, date };
+        let candidates: Vec<Value> = match self.entity {
+            QueryEntity::Tasks => flow_state.tasks.keys().map(|task_id| Value::Task(*task_id)).collect(),
+            QueryEntity::Resources => flow_state.resources.keys().map(|resource_id| Value::Resource(*resource_id)).collect(),
+            QueryEntity::Worklogs => {
+                let mut worklogs: Vec<Value> = flow_state.worklogs.iter()
+                    .flat_map(|(task_id, resource_map)| resource_map.iter()
+                        .flat_map(move |(resource_id, date_map)| date_map.keys()
+                            .map(move |date| Value::Worklog { task_id: *task_id, resource_id: *resource_id, date: *date })))
+                    .collect();
+                worklogs.sort_by_key(|worklog| match worklog {
+                    Value::Worklog { task_id, resource_id, date } => (*date, *task_id, *resource_id),
+                    _ => unreachable!(),
+                });
+                worklogs
+            }
+        };
+        let mut rows = Vec::new();
+        for row in candidates {
+            let matches = match &self.condition {
+                Some(condition) => match context.eval(condition, &row)? {
+                    Value::Bool(matches) => matches,
+                    Value::Null => false,
+                    value => return Err(FlowError::query(format!("the condition must be true or false, not {}", context.display(&value)))),
+                },
+                None => true,
+            };
+            if matches {
+                rows.push(context.columns(self.entity, &row));
+            }
+        }
+        let columns = self.entity.columns().iter().map(|(header, _)| *header).collect();
+        Ok(QueryResult { columns, rows })
+    }
+}
+
+impl QueryResult {
+    /// The rows as plain text with aligned columns, for the terminal.
+    pub fn to_text(&self) -> String {
+        let widths: Vec<usize> = self.columns.iter().enumerate()
+            .map(|(index, column)| self.rows.iter()
+                .map(|row| row[index].chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(column.len()))
+            .collect();
+        let line = |cells: Vec<&str>| cells.iter().zip(&widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string() + "\n";
+        let mut text = line(self.columns.clone());
+        for row in &self.rows {
+            text.push_str(&line(row.iter().map(String::as_str).collect()));
+        }
+        text
+    }
+}
+
+/// Parses and runs `text` against `flow_state`.
+pub fn run_query(text: &str, flow_state: &FlowState, date: NaiveDate) -> FlowResult<QueryResult> {
+    Query::parse(text)?.run(flow_state, date)
+}
+
+struct Context<'a> {
+    flow_state: &'a FlowState,
+    date: NaiveDate,
+}
+
+impl Context<'_> {
+    fn eval(&self, expr: &Expr, row: &Value) -> FlowResult<Value> {
+        match expr {
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Path(path) => path.iter().try_fold(row.clone(), |value, field| self.field(&value, field)),
+            Expr::Call(name, arguments) => {
+                let arguments = arguments.iter()
+                    .map(|argument| self.eval(argument, row))
+                    .collect::<FlowResult<Vec<_>>>()?;
+                self.call(name, &arguments, row)
+            }
+            Expr::Range(start, end) => match (self.eval(start, row)?, self.eval(end, row)?) {
+                (Value::Date(start), Value::Date(end)) => Ok(Value::DateRange(start, end)),
+                _ => Err(FlowError::query("ranges go from one date to another")),
+            },
+            Expr::Compare(left, op, right) => {
+                let left = self.eval(left, row)?;
+                let right = self.eval(right, row)?;
+                Ok(Value::Bool(self.compare(&left, *op, &right)))
+            }
+            Expr::And(left, right) => Ok(Value::Bool(self.is_true(left, row)? && self.is_true(right, row)?)),
+            Expr::Or(left, right) => Ok(Value::Bool(self.is_true(left, row)? || self.is_true(right, row)?)),
+            Expr::Not(expr) => Ok(Value::Bool(!self.is_true(expr, row)?)),
+        }
+    }
+
+    fn is_true(&self, expr: &Expr, row: &Value) -> FlowResult<bool> {
+        match self.eval(expr, row)? {
+            Value::Bool(value) => Ok(value),
+            Value::Null => Ok(false),
+            value => Err(FlowError::query(format!("expected true or false, not {}", self.display(&value)))),
+        }
+    }
+
+    fn field(&self, value: &Value, field: &str) -> FlowResult<Value> {
+        let flow_state = self.flow_state;
+        let cache = flow_state.cache();
+        let unknown = || FlowError::query(format!("unknown field '{field}'"));
+        let value = match value {
+            Value::Null => Value::Null,
+            Value::Task(task_id) => {
+                let Some(task) = flow_state.tasks.get(task_id) else {
+                    return Ok(Value::Null);
+                };
+                match field {
+                    "id" => Value::Number(*task_id as f64),
+                    "ticket" => Value::Text(task.ticket.clone()),
+                    "title" => Value::Text(task.title.clone()),
+                    "duration" => Value::Number(days(task.duration)),
+                    "remaining" => Value::Number(days(FlowStateCache::remaining_duration(flow_state, task, *task_id))),
+                    "start" => optional_date(cache.task_alloc_rendering.get(task_id).and_then(|days| days.keys().min().copied())),
+                    "end" => optional_date(cache.task_end_date(*task_id)),
+                    "assignee" => task.assignee.map(Value::Resource).unwrap_or(Value::Null),
+                    "unassigned" => Value::Bool(task.assignee.is_none()),
+                    "labels" => Value::List(task.label_ids.iter()
+                        .filter_map(|label_id| flow_state.labels.get(label_id))
+                        .map(|label| Value::Text(label.name.clone()))
+                        .collect()),
+                    "watchers" => Value::List(task.watchers.iter().map(|resource_id| Value::Resource(*resource_id)).collect()),
+                    _ => return Err(unknown()),
+                }
+            }
+            Value::Resource(resource_id) => {
+                let Some(resource) = flow_state.resources.get(resource_id) else {
+                    return Ok(Value::Null);
+                };
+                match field {
+                    "id" => Value::Number(*resource_id as f64),
+                    "name" => Value::Text(resource.name.clone()),
+                    "team" => Value::Team(resource.team_id),
+                    "tasks" => Value::Number(resource.assigned_tasks.len() as f64),
+                    "end" => optional_date(resource.assigned_tasks.iter()
+                        .filter_map(|task_id| cache.task_end_date(*task_id))
+                        .max()),
+                    _ => return Err(unknown()),
+                }
+            }
+            Value::Team(team_id) => {
+                let Some(team) = flow_state.teams.get(team_id) else {
+                    return Ok(Value::Null);
+                };
+                match field {
+                    "id" => Value::Number(*team_id as f64),
+                    "name" => Value::Text(team.name.clone()),
+                    "resources" => Value::Number(team.resources.len() as f64),
+                    _ => return Err(unknown()),
+                }
+            }
+            Value::Worklog { task_id, resource_id, date } => match field {
+                "date" => Value::Date(*date),
+                "fraction" => Value::Number(self.worklog_fraction(*task_id, *resource_id, *date)),
+                "task" => Value::Task(*task_id),
+                "resource" => Value::Resource(*resource_id),
+                _ => return Err(unknown()),
+            },
+            value => return Err(FlowError::query(format!("{} has no field '{field}'", self.display(value)))),
+        };
+        Ok(value)
+    }
+
+    fn call(&self, name: &str, arguments: &[Value], row: &Value) -> FlowResult<Value> {
+        match (name, arguments) {
+            ("today", []) => Ok(Value::Date(self.date)),
+            ("milestone", [Value::Text(title)]) => self.flow_state.milestones.iter()
+                .find(|milestone| &milestone.title == title)
+                .map(|milestone| Value::Date(milestone.date))
+                .ok_or_else(|| FlowError::query(format!("no milestone '{title}'"))),
+            ("load", [range]) => {
+                let Value::Resource(resource_id) = row else {
+                    return Err(FlowError::query("load() is only known for resources"));
+                };
+                let (start, end) = match range {
+                    Value::Date(date) => (*date, *date),
+                    Value::DateRange(start, end) => (*start, *end),
+                    _ => return Err(FlowError::query("load() takes a date or a range of dates")),
+                };
+                Ok(Value::Number(self.load(*resource_id, start, end)))
+            }
+            _ => Err(FlowError::query(format!("unknown function {name}() with {} arguments", arguments.len()))),
+        }
+    }
+
+    /// Average fraction of a weekday between `start` and `end` the resource is allocated or
+    /// has logged on tasks of others.
+    fn load(&self, resource_id: ResourceId, start: NaiveDate, end: NaiveDate) -> f64 {
+        let cache = self.flow_state.cache();
+        let Some(resource) = self.flow_state.resources.get(&resource_id) else {
+            return 0.0;
+        };
+        let mut total = 0.0;
+        let mut num_days = 0;
+        let mut day = start;
+        while day <= end {
+            if !matches!(day.weekday(), Weekday::Sat | Weekday::Sun) {
+                let allocated: u32 = resource.assigned_tasks.iter()
+                    .filter_map(|task_id| cache.task_alloc_rendering.get(task_id)?.get(&day))
+                    .map(|fraction| *fraction as u32)
+                    .sum();
+                let on_others = cache.worklogs_on_others_tasks.get(&resource_id)
+                    .and_then(|days| days.get(&day))
+                    .copied()
+                    .unwrap_or(0) as u32;
+                total += (allocated + on_others) as f64 / 100.0;
+                num_days += 1;
+            }
+            day += Duration::days(1);
+        }
+        if num_days == 0 { 0.0 } else { total / num_days as f64 }
+    }
+
+    fn worklog_fraction(&self, task_id: TaskId, resource_id: ResourceId, date: NaiveDate) -> f64 {
+        self.flow_state.worklogs.get(&task_id)
+            .and_then(|resource_map| resource_map.get(&resource_id))
+            .and_then(|date_map| date_map.get(&date))
+            .map(|worklog| worklog.fraction as f64 / 100.0)
+            .unwrap_or(0.0)
+    }
+
+    /// Entities compare by what they are called, everything else by value. Comparing with
+    /// null only tells whether the other side is null as well.
+    fn compare(&self, left: &Value, op: CompareOp, right: &Value) -> bool {
+        let left = self.scalar(left);
+        let right = self.scalar(right);
+        if op == CompareOp::Contains {
+            return match (&left, &right) {
+                (Value::List(items), _) => items.iter().any(|item| self.scalar(item) == right),
+                (Value::Text(text), Value::Text(part)) => text.to_lowercase().contains(&part.to_lowercase()),
+                _ => false,
+            };
+        }
+        let ordering = match (&left, &right) {
+            (Value::Null, Value::Null) => Some(Ordering::Equal),
+            (Value::Null, _) | (_, Value::Null) => None,
+            (Value::Number(a), Value::Number(b)) => a.partial_cmp(b),
+            (Value::Text(a), Value::Text(b)) => Some(a.cmp(b)),
+            (Value::Date(a), Value::Date(b)) => Some(a.cmp(b)),
+            (Value::Date(a), Value::Text(b)) => NaiveDate::parse_from_str(b, "%Y-%m-%d").ok().map(|b| a.cmp(&b)),
+            (Value::Text(a), Value::Date(b)) => NaiveDate::parse_from_str(a, "%Y-%m-%d").ok().map(|a| a.cmp(b)),
+            (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+            _ => None,
+        };
+        match (op, ordering) {
+            (CompareOp::Ne, None) => true,
+            (_, None) => false,
+            (CompareOp::Eq, Some(ordering)) => ordering == Ordering::Equal,
+            (CompareOp::Ne, Some(ordering)) => ordering != Ordering::Equal,
+            (CompareOp::Lt, Some(ordering)) => ordering == Ordering::Less,
+            (CompareOp::Le, Some(ordering)) => ordering != Ordering::Greater,
+            (CompareOp::Gt, Some(ordering)) => ordering == Ordering::Greater,
+            (CompareOp::Ge, Some(ordering)) => ordering != Ordering::Less,
+            (CompareOp::Contains, _) => unreachable!(),
+        }
+    }
+
+    fn scalar(&self, value: &Value) -> Value {
+        match value {
+            Value::Task(_) | Value::Resource(_) | Value::Team(_) => Value::Text(self.display(value)),
+            value => value.clone(),
+        }
+    }
+
+    fn display(&self, value: &Value) -> String {
+        let flow_state = self.flow_state;
+        match value {
+            Value::Null => String::new(),
+            Value::Bool(value) => value.to_string(),
+            Value::Number(number) => format!("{number:.2}").trim_end_matches('0').trim_end_matches('.').to_string(),
+            Value::Text(text) => text.clone(),
+            Value::Date(date) => date.to_string(),
+            Value::List(items) => items.iter().map(|item| self.display(item)).collect::<Vec<_>>().join(", "),
+            Value::DateRange(start, end) => format!("{start}..{end}"),
+            Value::Task(task_id) => flow_state.tasks.get(task_id).map(|task| task.ticket.clone()).unwrap_or_else(|| format!("task #{task_id}")),
+            Value::Resource(resource_id) => flow_state.resources.get(resource_id).map(|resource| resource.name.clone()).unwrap_or_else(|| format!("resource #{resource_id}")),
+            Value::Team(team_id) => flow_state.teams.get(team_id).map(|team| team.name.clone()).unwrap_or_else(|| format!("team #{team_id}")),
+            Value::Worklog { task_id, resource_id, date } => format!("worklog of {} on {} for {date}",
+                self.display(&Value::Resource(*resource_id)), self.display(&Value::Task(*task_id))),
+        }
+    }
+
+    fn columns(&self, entity: QueryEntity, row: &Value) -> Vec<String> {
+        entity.columns().iter()
+            .map(|(_, field)| self.field(row, field).map(|value| self.display(&value)).unwrap_or_default())
+            .collect()
+    }
+}
+
+fn days(duration: TaskDuration) -> f64 {
+    duration.days as f64 + duration.fraction as f64 / 100.0
+}
+
+fn optional_date(date: Option<NaiveDate>) -> Value {
+    date.map(Value::Date).unwrap_or(Value::Null)
+}
@@ -0,0 +1,86 @@
+use crate::app::*;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// How the queue of one resource was scheduled on each day of the project's history. Every
+/// history map is keyed by the snapshot date first and the scheduled day second.
+#[derive(Debug, Clone)]
+pub struct ResourceInspection {
+    pub resource_id: ResourceId,
+    /// Tasks in the order they first showed up in the queue; `task_label` numbers them.
+    pub tasks: Vec<TaskId>,
+    pub allocations_history: HashMap<NaiveDate, HashMap<NaiveDate, Vec<(TaskId, Fraction)>>>,
+    pub absences_history: HashMap<NaiveDate, HashMap<NaiveDate, Fraction>>,
+    pub worklogs_history: HashMap<NaiveDate, HashMap<NaiveDate, Fraction>>,
+    pub flow_state: FlowState,
+    pub start_date: NaiveDate,
+}
+
+impl ResourceInspection {
+    pub fn new(inspected_resource_id: ResourceId) -> Self {
+        ResourceInspection {
+            resource_id: inspected_resource_id,
+            tasks: Vec::new(),
+            allocations_history: HashMap::new(),
+            absences_history: HashMap::new(),
+            worklogs_history: HashMap::new(),
+            flow_state: FlowState::new(),
+            start_date: NaiveDate::MAX,
+        }
+    }
+
+    /// Replays `commands` day by day and records the inspected resource's queue on each day.
+    /// Returns `None` if cancelled through `control`.
+    pub fn build(
+        inspected_resource_id: ResourceId,
+        commands: &[Command],
+        checkpoints: &[Checkpoint],
+        date: NaiveDate,
+        snapshots: &Mutex<InspectionSnapshots>,
+        control: &InspectionControl,
+    ) -> FlowResult<Option<Self>> {
+        let mut resource_inspector = ResourceInspection::new(inspected_resource_id);
+        resource_inspector.start_date = creation_date(commands, |details| {
+            matches!(details, CommandDetails::CreateResource { id, .. } if *id == inspected_resource_id)
+        }).ok_or_else(|| FlowError::not_found(EntityKind::Resource, inspected_resource_id))?;
+
+        let flow_state = replay_days(commands, checkpoints, date, snapshots, control, |flow_state, day, _| {
+            resource_inspector.record_day(flow_state, day);
+        })?;
+        Ok(flow_state.map(|flow_state| {
+            resource_inspector.flow_state = flow_state;
+            resource_inspector
+        }))
+    }
+
+    /// Short label of a task in this inspection, `T1` for the first task of the queue.
+    pub fn task_label(&self, task_id: TaskId) -> String {
+        match self.tasks.iter().position(|id| *id == task_id) {
+            Some(index) => format!("T{}", index + 1),
+            None => "?".to_string(),
+        }
+    }
+
+    fn record_day(&mut self, flow_state: &FlowState, date: NaiveDate) {
+        let Some(resource) = flow_state.resources.get(&self.resource_id) else {
+            return;
+        };
+        let mut allocations: HashMap<NaiveDate, Vec<(TaskId, Fraction)>> = HashMap::new();
+        for task_id in &resource.assigned_tasks {
+            if !self.tasks.contains(task_id) {
+                self.tasks.push(*task_id);
+            }
+            for (day, fraction) in flow_state.cache().task_alloc_rendering.get(task_id).into_iter().flatten() {
+                allocations.entry(*day).or_default().push((*task_id, *fraction));
+            }
+        }
+        self.allocations_history.insert(date, allocations);
+        self.absences_history.insert(date,
+                flow_state.cache().resource_absence_rendering
+                    .get(&self.resource_id).cloned().unwrap_or_default());
+        self.worklogs_history.insert(date,
+                flow_state.cache().worklog_totals
+                    .get(&self.resource_id).cloned().unwrap_or_default());
+    }
+}
@@ -0,0 +1,146 @@
+use crate::app::*;
+use chrono::NaiveDate;
+
+/// A named sandbox forked from the plan. Commands invoked while it is active change only its
+/// own copy of the state, until it is merged into the plan or discarded.
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    pub name: String,
+    command_stack: Vec<CommandRecord>,
+    num_commands_applied: usize,
+    flow_state: FlowState,
+}
+
+impl Scenario {
+    pub fn fork(name: &str, flow_state: &FlowState) -> Self {
+        Scenario {
+            name: name.to_string(),
+            command_stack: Vec::new(),
+            num_commands_applied: 0,
+            flow_state: flow_state.clone(),
+        }
+    }
+
+    /// The commands applied in this scenario since it was forked, in order.
+    pub fn commands(&self) -> Vec<Command> {
+        self.command_stack.iter().take(self.num_commands_applied)
+            .map(|record| record.redo_command.clone())
+            .collect()
+    }
+
+    pub fn flow_state(&self) -> &FlowState {
+        &self.flow_state
+    }
+
+    pub fn flow_state_mut(&mut self) -> &mut FlowState {
+        &mut self.flow_state
+    }
+
+    pub fn invoke_command(&mut self, command: Command, date: NaiveDate) -> FlowResult<()> {
+        let undo_command = self.flow_state.execute_command_generate_inverse_and_rebuild_cache(command.clone(), date)?;
+        self.command_stack.truncate(self.num_commands_applied);
+        self.command_stack.push(CommandRecord {
+            undo_command,
+            redo_command: command,
+            author: None,
+        });
+        self.num_commands_applied = self.command_stack.len();
+        Ok(())
+    }
+
+    pub fn undo(&mut self, date: NaiveDate) -> FlowResult<()> {
+        if self.num_commands_applied == 0 {
+            return Err(FlowError::invariant(EntityKind::Scenario, &self.name, "has no commands to undo"));
+        }
+        let undo_command = self.command_stack[self.num_commands_applied - 1].undo_command.clone();
+        self.flow_state.execute_command_generate_inverse_and_rebuild_cache(undo_command, date)?;
+        self.num_commands_applied -= 1;
+        Ok(())
+    }
+
+    pub fn redo(&mut self, date: NaiveDate) -> FlowResult<()> {
+        if self.num_commands_applied >= self.command_stack.len() {
+            return Err(FlowError::invariant(EntityKind::Scenario, &self.name, "has no commands to redo"));
+        }
+        let redo_command = self.command_stack[self.num_commands_applied].redo_command.clone();
+        self.flow_state.execute_command_generate_inverse_and_rebuild_cache(redo_command, date)?;
+        self.num_commands_applied += 1;
+        Ok(())
+    }
+}
+
+/// A task whose projected end date differs between the plan and a scenario. A missing date
+/// means the task does not exist or is not scheduled on that side.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskEndDateChange {
+    pub task_id: TaskId,
+    pub ticket: String,
+    pub title: String,
+    pub plan_end_date: Option<NaiveDate>,
+    pub scenario_end_date: Option<NaiveDate>,
+}
+
+impl TaskEndDateChange {
+    /// Days the scenario moves the end date by, positive if it finishes later.
+    pub fn shift_days(&self) -> Option<i64> {
+        Some((self.scenario_end_date? - self.plan_end_date?).num_days())
+    }
+}
+
+/// How many tasks are projected to be done by a milestone in the plan and in a scenario.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MilestoneImpact {
+    pub title: String,
+    pub date: NaiveDate,
+    pub tasks_done_in_plan: usize,
+    pub tasks_done_in_scenario: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioDiff {
+    pub tasks: Vec<TaskEndDateChange>,
+    pub milestones: Vec<MilestoneImpact>,
+}
+
+impl ScenarioDiff {
+    /// Compares the projected end dates of `scenario` against `plan`. Milestones are taken
+    /// from the scenario, which may have added or moved some.
+    pub fn between(plan: &FlowState, scenario: &FlowState) -> Self {
+        let mut tasks: Vec<TaskEndDateChange> = scenario.tasks.keys()
+            .chain(plan.tasks.keys().filter(|task_id| !scenario.tasks.contains_key(task_id)))
+            .filter_map(|task_id| {
+                let plan_end_date = plan.cache().task_end_date(*task_id);
+                let scenario_end_date = scenario.cache().task_end_date(*task_id);
+                if plan_end_date == scenario_end_date {
+                    return None;
+                }
+                let task = scenario.tasks.get(task_id).or_else(|| plan.tasks.get(task_id))?;
+                Some(TaskEndDateChange {
+                    task_id: *task_id,
+                    ticket: task.ticket.clone(),
+                    title: task.title.clone(),
+                    plan_end_date,
+                    scenario_end_date,
+                })
+            })
+            .collect();
+        tasks.sort_by(|a, b| b.shift_days().cmp(&a.shift_days()).then(a.task_id.cmp(&b.task_id)));
+
+        let tasks_done_by = |flow_state: &FlowState, date: NaiveDate| {
+            flow_state.tasks.keys()
+                .filter(|task_id| flow_state.cache().task_end_date(**task_id).is_some_and(|end_date| end_date <= date))
+                .count()
+        };
+        let mut milestones: Vec<MilestoneImpact> = scenario.milestones.iter()
+            .map(|milestone| MilestoneImpact {
+                title: milestone.title.clone(),
+                date: milestone.date,
+                tasks_done_in_plan: tasks_done_by(plan, milestone.date),
+                tasks_done_in_scenario: tasks_done_by(scenario, milestone.date),
+            })
+            .collect();
+        milestones.sort_by_key(|impact| impact.date);
+
+        ScenarioDiff { tasks, milestones }
+    }
+}
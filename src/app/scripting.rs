@@ -0,0 +1,214 @@
+use crate::app::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map, Scope};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Extension of the script files looked up next to a project.
+pub const SCRIPT_EXTENSION: &str = "rhai";
+
+/// Operations a script may run before it is stopped, so a runaway loop cannot hang the app.
+const MAX_SCRIPT_OPERATIONS: u64 = 10_000_000;
+
+/// Sizes a script's strings, arrays and maps may grow to, so it cannot exhaust memory in
+/// fewer operations than that. Arrays and maps must still hold every task of a large plan.
+const MAX_SCRIPT_STRING_SIZE: usize = 10_000_000;
+const MAX_SCRIPT_ARRAY_SIZE: usize = 1_000_000;
+const MAX_SCRIPT_MAP_SIZE: usize = 1_000_000;
+
+/// What running a script produced: the lines it printed and the commands it emitted.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptOutput {
+    pub printed: Vec<String>,
+    pub commands: Vec<CommandDetails>,
+}
+
+impl ScriptOutput {
+    /// The emitted commands as a single compound command, so that one undo takes back the
+    /// whole script, or `None` if the script emitted nothing.
+    pub fn into_command(self, timestamp: DateTime<Utc>) -> Option<Command> {
+        if self.commands.is_empty() {
+            return None;
+        }
+        let commands = self.commands.into_iter()
+            .map(|details| Command { timestamp, details })
+            .collect();
+        Some(Command { timestamp, details: CommandDetails::CompoundCommand { commands } })
+    }
+}
+
+/// A Rhai script stored next to the project file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Script {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl Script {
+    /// The scripts in the directory of `project_filename`, sorted by name.
+    pub fn discover(project_filename: &str) -> Vec<Script> {
+        let directory = match Path::new(project_filename).parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let Ok(entries) = std::fs::read_dir(&directory) else {
+            return Vec::new();
+        };
+        let mut scripts: Vec<Script> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|extension| extension == SCRIPT_EXTENSION))
+            .filter_map(|path| Some(Script { name: path.file_stem()?.to_string_lossy().to_string(), path }))
+            .collect();
+        scripts.sort_by(|a, b| a.name.cmp(&b.name));
+        scripts
+    }
+
+    pub fn run(&self, flow_state: &FlowState, date: NaiveDate) -> FlowResult<ScriptOutput> {
+        let filename = self.path.to_string_lossy().to_string();
+        let source = std::fs::read_to_string(&self.path).map_err(|e| FlowError::io(&filename, e))?;
+        run_script(&self.name, &source, flow_state, date)
+    }
+}
+
+/// Runs `source` in a sandbox without access to files or the rest of the app. The script reads
+/// the project through the `state` constant and `today`, prints with `print`, and changes the
+/// plan only by emitting commands, which it does not see the effect of while it runs.
+///
+/// Commands are emitted with `emit(#{ UnassignTask: #{ task_id: 3 } })`, using the names of
+/// `CommandDetails`, or with the shorthands `assign_task`, `unassign_task`,
+/// `add_label_to_task` and `remove_label_from_task`.
+pub fn run_script(name: &str, source: &str, flow_state: &FlowState, date: NaiveDate) -> FlowResult<ScriptOutput> {
+    let output = Rc::new(RefCell::new(ScriptOutput::default()));
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_SCRIPT_OPERATIONS);
+    engine.set_max_call_levels(64);
+    engine.set_max_expr_depths(64, 64);
+    engine.set_max_string_size(MAX_SCRIPT_STRING_SIZE);
+    engine.set_max_array_size(MAX_SCRIPT_ARRAY_SIZE);
+    engine.set_max_map_size(MAX_SCRIPT_MAP_SIZE);
+    engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+    engine.disable_symbol("eval");
+
+    let printed = output.clone();
+    engine.on_print(move |text| printed.borrow_mut().printed.push(text.to_string()));
+    let printed = output.clone();
+    engine.on_debug(move |text, _, _| printed.borrow_mut().printed.push(text.to_string()));
+
+    let emitted = output.clone();
+    engine.register_fn("emit", move |command: Dynamic| -> Result<(), Box<EvalAltResult>> {
+        let details: CommandDetails = rhai::serde::from_dynamic(&command)?;
+        emitted.borrow_mut().commands.push(details);
+        Ok(())
+    });
+    let emitted = output.clone();
+    engine.register_fn("assign_task", move |task_id: i64, resource_id: i64| {
+        emitted.borrow_mut().commands.push(CommandDetails::AssignTask { task_id: task_id as TaskId, resource_id: resource_id as ResourceId });
+    });
+    let emitted = output.clone();
+    engine.register_fn("unassign_task", move |task_id: i64| {
+        emitted.borrow_mut().commands.push(CommandDetails::UnassignTask { task_id: task_id as TaskId });
+    });
+    let emitted = output.clone();
+    engine.register_fn("add_label_to_task", move |task_id: i64, label_id: i64| {
+        emitted.borrow_mut().commands.push(CommandDetails::AddLabelToTask { task_id: task_id as TaskId, label_id: label_id as LabelId });
+    });
+    let emitted = output.clone();
+    engine.register_fn("remove_label_from_task", move |task_id: i64, label_id: i64| {
+        emitted.borrow_mut().commands.push(CommandDetails::RemoveLabelFromTask { task_id: task_id as TaskId, label_id: label_id as LabelId });
+    });
+
+    let mut scope = Scope::new();
+    scope.push_constant("state", script_state(flow_state));
+    scope.push_constant("today", date.to_string());
+    engine.run_with_scope(&mut scope, source)
+        .map_err(|e| FlowError::script(name, e.to_string()))?;
+    Ok(output.take())
+}
+
+fn id(id: u64) -> Dynamic {
+    Dynamic::from_int(id as rhai::INT)
+}
+
+fn ids<'a>(ids: impl IntoIterator<Item = &'a u64>) -> Dynamic {
+    Dynamic::from_array(ids.into_iter().map(|value| id(*value)).collect())
+}
+
+fn date_or_unit(date: Option<NaiveDate>) -> Dynamic {
+    date.map(|date| Dynamic::from(date.to_string())).unwrap_or(Dynamic::UNIT)
+}
+
+/// The project as Rhai maps. Dates are `YYYY-MM-DD` strings, which compare in date order, and
+/// fractions are percentages of a day.
+fn script_state(flow_state: &FlowState) -> Dynamic {
+    let cache = flow_state.cache();
+    let mut state = Map::new();
+
+    let teams: Array = flow_state.teams.iter().map(|(team_id, team)| {
+        let mut map = Map::new();
+        map.insert("id".into(), id(*team_id));
+        map.insert("name".into(), team.name.clone().into());
+        map.insert("resources".into(), ids(&team.resources));
+        Dynamic::from_map(map)
+    }).collect();
+    state.insert("teams".into(), teams.into());
+
+    let resources: Array = flow_state.resources.iter().map(|(resource_id, resource)| {
+        let mut load: BTreeMap<NaiveDate, rhai::INT> = BTreeMap::new();
+        for task_id in &resource.assigned_tasks {
+            for (day, fraction) in cache.task_alloc_rendering.get(task_id).into_iter().flatten() {
+                *load.entry(*day).or_default() += *fraction as rhai::INT;
+            }
+        }
+        let mut map = Map::new();
+        map.insert("id".into(), id(*resource_id));
+        map.insert("name".into(), resource.name.clone().into());
+        map.insert("team_id".into(), id(resource.team_id));
+        map.insert("assigned_tasks".into(), ids(&resource.assigned_tasks));
+        map.insert("load".into(), Dynamic::from_map(load.into_iter()
+            .map(|(day, fraction)| (day.to_string().into(), Dynamic::from_int(fraction)))
+            .collect()));
+        Dynamic::from_map(map)
+    }).collect();
+    state.insert("resources".into(), resources.into());
+
+    let tasks: Array = flow_state.tasks.iter().map(|(task_id, task)| {
+        let mut map = Map::new();
+        map.insert("id".into(), id(*task_id));
+        map.insert("ticket".into(), task.ticket.clone().into());
+        map.insert("title".into(), task.title.clone().into());
+        map.insert("days".into(), Dynamic::from_float(task.duration.days as rhai::FLOAT + task.duration.fraction as rhai::FLOAT / 100.0));
+        map.insert("assignee".into(), task.assignee.map(id).unwrap_or(Dynamic::UNIT));
+        map.insert("label_ids".into(), ids(&task.label_ids));
+        map.insert("labels".into(), Dynamic::from_array(task.label_ids.iter()
+            .filter_map(|label_id| flow_state.labels.get(label_id))
+            .map(|label| label.name.clone().into())
+            .collect()));
+        map.insert("end_date".into(), date_or_unit(cache.task_end_date(*task_id)));
+        Dynamic::from_map(map)
+    }).collect();
+    state.insert("tasks".into(), tasks.into());
+
+    let labels: Array = flow_state.labels.iter().map(|(label_id, label)| {
+        let mut map = Map::new();
+        map.insert("id".into(), id(*label_id));
+        map.insert("name".into(), label.name.clone().into());
+        Dynamic::from_map(map)
+    }).collect();
+    state.insert("labels".into(), labels.into());
+
+    let milestones: Array = flow_state.milestones.iter().map(|milestone| {
+        let mut map = Map::new();
+        map.insert("title".into(), milestone.title.clone().into());
+        map.insert("date".into(), milestone.date.to_string().into());
+        Dynamic::from_map(map)
+    }).collect();
+    state.insert("milestones".into(), milestones.into());
+
+    state.insert("unassigned_tasks".into(), ids(&cache.unassigned_tasks));
+    state.insert("start_date".into(), cache.start_date.to_string().into());
+    state.insert("end_date".into(), cache.end_date.to_string().into());
+    Dynamic::from_map(state)
+}
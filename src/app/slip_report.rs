@@ -0,0 +1,244 @@
+use crate::app::*;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Why a task's projected end date moved, in days of slip; negative amounts pulled it in.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SlipCauses {
+    pub estimate_growth: f32,
+    pub missed_work: f32,
+    pub absences: f32,
+    pub pushed: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskSlip {
+    pub task_id: TaskId,
+    pub ticket: String,
+    pub title: String,
+    pub first_end_date: NaiveDate,
+    pub last_end_date: NaiveDate,
+    pub causes: SlipCauses,
+}
+
+impl TaskSlip {
+    pub fn slip_days(&self) -> i64 {
+        (self.last_end_date - self.first_end_date).num_days()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlipColumn {
+    Ticket,
+    Title,
+    FirstEndDate,
+    LastEndDate,
+    Slip,
+    EstimateGrowth,
+    MissedWork,
+    Absences,
+    Pushed,
+}
+
+impl SlipColumn {
+    pub const ALL: [SlipColumn; 9] = [
+        SlipColumn::Ticket,
+        SlipColumn::Title,
+        SlipColumn::FirstEndDate,
+        SlipColumn::LastEndDate,
+        SlipColumn::Slip,
+        SlipColumn::EstimateGrowth,
+        SlipColumn::MissedWork,
+        SlipColumn::Absences,
+        SlipColumn::Pushed,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SlipColumn::Ticket => "Ticket",
+            SlipColumn::Title => "Title",
+            SlipColumn::FirstEndDate => "First End Date",
+            SlipColumn::LastEndDate => "Last End Date",
+            SlipColumn::Slip => "Slip (days)",
+            SlipColumn::EstimateGrowth => "Estimate Growth",
+            SlipColumn::MissedWork => "Missed Work",
+            SlipColumn::Absences => "Absences",
+            SlipColumn::Pushed => "Pushed",
+        }
+    }
+
+    pub fn value(&self, slip: &TaskSlip) -> String {
+        match self {
+            SlipColumn::Ticket => slip.ticket.clone(),
+            SlipColumn::Title => slip.title.clone(),
+            SlipColumn::FirstEndDate => slip.first_end_date.to_string(),
+            SlipColumn::LastEndDate => slip.last_end_date.to_string(),
+            SlipColumn::Slip => slip.slip_days().to_string(),
+            SlipColumn::EstimateGrowth => format!("{:.1}", slip.causes.estimate_growth),
+            SlipColumn::MissedWork => format!("{:.1}", slip.causes.missed_work),
+            SlipColumn::Absences => format!("{:.1}", slip.causes.absences),
+            SlipColumn::Pushed => format!("{:.1}", slip.causes.pushed),
+        }
+    }
+
+    fn compare(&self, a: &TaskSlip, b: &TaskSlip) -> std::cmp::Ordering {
+        match self {
+            SlipColumn::Ticket => a.ticket.cmp(&b.ticket),
+            SlipColumn::Title => a.title.cmp(&b.title),
+            SlipColumn::FirstEndDate => a.first_end_date.cmp(&b.first_end_date),
+            SlipColumn::LastEndDate => a.last_end_date.cmp(&b.last_end_date),
+            SlipColumn::Slip => a.slip_days().cmp(&b.slip_days()),
+            SlipColumn::EstimateGrowth => a.causes.estimate_growth.total_cmp(&b.causes.estimate_growth),
+            SlipColumn::MissedWork => a.causes.missed_work.total_cmp(&b.causes.missed_work),
+            SlipColumn::Absences => a.causes.absences.total_cmp(&b.causes.absences),
+            SlipColumn::Pushed => a.causes.pushed.total_cmp(&b.causes.pushed),
+        }
+    }
+}
+
+/// What the replay of one day showed about a task, compared with the following day.
+#[derive(Debug, Clone, Copy)]
+struct TaskDay {
+    end_date: NaiveDate,
+    estimate: TaskDuration,
+    assignee: Option<ResourceId>,
+    /// Sum of the estimates of the tasks ahead of it in its assignee's queue, in fractions.
+    work_ahead: u64,
+}
+
+/// How far the projected end date of every task moved between two snapshot dates, and why.
+#[derive(Debug, Clone)]
+pub struct SlipReport {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub slips: Vec<TaskSlip>,
+}
+
+impl SlipReport {
+    /// Replays `commands` day by day and compares the projected end dates of each task from
+    /// `from` (or its creation) to `to` (or `date`). Each day's move is split evenly over the
+    /// causes seen that day: a bigger estimate, an absence of the assignee, or more work ahead
+    /// in the queue. A move without any of those is put down to work not done as scheduled.
+    /// Returns `None` if cancelled through `control`.
+    pub fn build(
+        commands: &[Command],
+        checkpoints: &[Checkpoint],
+        date: NaiveDate,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        snapshots: &Mutex<InspectionSnapshots>,
+        control: &InspectionControl,
+    ) -> FlowResult<Option<Self>> {
+        let mut previous_days: HashMap<TaskId, TaskDay> = HashMap::new();
+        let mut slips: HashMap<TaskId, TaskSlip> = HashMap::new();
+        let in_range = |day: NaiveDate| from.is_none_or(|from| day >= from) && to.is_none_or(|to| day <= to);
+
+        let flow_state = replay_days(commands, checkpoints, date, snapshots, control, |flow_state, day, day_commands| {
+            if !in_range(day) {
+                return;
+            }
+            let absent_resources: Vec<ResourceId> = flatten_commands(day_commands).into_iter()
+                .filter_map(|details| match details {
+                    CommandDetails::SetAbsence { resource_id, .. } => Some(*resource_id),
+                    _ => None,
+                })
+                .collect();
+            let days = SlipReport::task_days(flow_state);
+            for (task_id, task_day) in &days {
+                let Some(task) = flow_state.tasks.get(task_id) else {
+                    continue;
+                };
+                let slip = slips.entry(*task_id).or_insert_with(|| TaskSlip {
+                    task_id: *task_id,
+                    ticket: String::new(),
+                    title: String::new(),
+                    first_end_date: task_day.end_date,
+                    last_end_date: task_day.end_date,
+                    causes: SlipCauses::default(),
+                });
+                slip.ticket = task.ticket.clone();
+                slip.title = task.title.clone();
+                slip.last_end_date = task_day.end_date;
+
+                let Some(previous) = previous_days.get(task_id) else {
+                    continue;
+                };
+                let moved = (task_day.end_date - previous.end_date).num_days() as f32;
+                if moved == 0.0 {
+                    continue;
+                }
+                let estimate_grew = task_day.estimate > previous.estimate;
+                let absent = task_day.assignee.is_some_and(|assignee| absent_resources.contains(&assignee));
+                let pushed = task_day.assignee.is_some()
+                    && task_day.assignee == previous.assignee
+                    && task_day.work_ahead > previous.work_ahead;
+                let num_causes = [estimate_grew, absent, pushed].iter().filter(|cause| **cause).count();
+                if num_causes == 0 {
+                    slip.causes.missed_work += moved;
+                    continue;
+                }
+                let share = moved / num_causes as f32;
+                if estimate_grew {
+                    slip.causes.estimate_growth += share;
+                }
+                if absent {
+                    slip.causes.absences += share;
+                }
+                if pushed {
+                    slip.causes.pushed += share;
+                }
+            }
+            previous_days = days;
+        })?;
+
+        Ok(flow_state.map(|_| {
+            let mut slips: Vec<TaskSlip> = slips.into_values().collect();
+            slips.sort_by(|a, b| b.slip_days().cmp(&a.slip_days()).then(a.task_id.cmp(&b.task_id)));
+            SlipReport { from, to, slips }
+        }))
+    }
+
+    fn task_days(flow_state: &FlowState) -> HashMap<TaskId, TaskDay> {
+        let mut work_ahead: HashMap<TaskId, u64> = HashMap::new();
+        for resource in flow_state.resources.values() {
+            let mut ahead = 0;
+            for task_id in &resource.assigned_tasks {
+                work_ahead.insert(*task_id, ahead);
+                if let Some(task) = flow_state.tasks.get(task_id) {
+                    ahead += task.duration.days * 100 + task.duration.fraction as u64;
+                }
+            }
+        }
+        flow_state.tasks.iter()
+            .filter_map(|(task_id, task)| {
+                let end_date = flow_state.cache().task_end_date(*task_id)?;
+                Some((*task_id, TaskDay {
+                    end_date,
+                    estimate: task.duration,
+                    assignee: task.assignee,
+                    work_ahead: work_ahead.get(task_id).copied().unwrap_or(0),
+                }))
+            })
+            .collect()
+    }
+
+    pub fn sort_by(&mut self, column: SlipColumn, ascending: bool) {
+        self.slips.sort_by(|a, b| {
+            let ordering = column.compare(a, b);
+            if ascending { ordering } else { ordering.reverse() }
+        });
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = csv_row(SlipColumn::ALL.iter().map(|column| column.name()));
+        for slip in &self.slips {
+            csv.push_str(&csv_row(SlipColumn::ALL.iter().map(|column| column.value(slip))));
+        }
+        csv
+    }
+
+    pub fn export_csv(&self, filename: &str) -> FlowResult<()> {
+        std::fs::write(filename, self.to_csv()).map_err(|e| FlowError::io(filename, e))
+    }
+}
@@ -0,0 +1,290 @@
+use crate::app::*;
+use rusqlite::{params, Connection, OpenFlags};
+use serde_yaml::{Mapping, Value};
+use std::collections::HashSet;
+
+/// Commands, undo branches, checkpoints and history events are stored as YAML text, one row
+/// each, so the migrations of `ProjectFile` apply unchanged. A save writes only the rows that changed;
+/// `tasks` and `worklogs` follow the current `FlowState` and are never read back, they only
+/// serve queries from outside FlowState.
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+    CREATE TABLE IF NOT EXISTS commands (
+        command_index INTEGER PRIMARY KEY,
+        undo_command TEXT NOT NULL,
+        redo_command TEXT NOT NULL,
+        author TEXT
+    );
+    CREATE TABLE IF NOT EXISTS branches (
+        branch_index INTEGER PRIMARY KEY,
+        fork_index INTEGER NOT NULL,
+        records TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS checkpoints (
+        command_index INTEGER PRIMARY KEY,
+        commands_hash INTEGER NOT NULL,
+        flow_state TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS history_events (
+        event_index INTEGER PRIMARY KEY,
+        event TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS tasks (
+        id INTEGER PRIMARY KEY,
+        ticket TEXT NOT NULL,
+        title TEXT NOT NULL,
+        duration_days INTEGER NOT NULL,
+        duration_fraction INTEGER NOT NULL,
+        assignee INTEGER
+    );
+    CREATE TABLE IF NOT EXISTS worklogs (
+        task_id INTEGER NOT NULL,
+        resource_id INTEGER NOT NULL,
+        date TEXT NOT NULL,
+        fraction INTEGER NOT NULL,
+        PRIMARY KEY (task_id, resource_id, date)
+    );
+";
+
+pub struct SqliteStorage {
+    filename: String,
+}
+
+impl SqliteStorage {
+    pub fn new(filename: &str) -> Self {
+        Self { filename: filename.to_string() }
+    }
+
+    /// Opens the file for saving, creating the tables it lacks.
+    fn open(&self) -> FlowResult<Connection> {
+        let connection = Connection::open(&self.filename).map_err(|e| self.sql_error(e))?;
+        connection.execute_batch(SCHEMA).map_err(|e| self.sql_error(e))?;
+        self.add_missing_columns(&connection)?;
+        Ok(connection)
+    }
+
+    /// `CREATE TABLE IF NOT EXISTS` leaves tables of older files as they were.
+    fn add_missing_columns(&self, connection: &Connection) -> FlowResult<()> {
+        if !self.has_column(connection, "commands", "author")? {
+            connection.execute_batch("ALTER TABLE commands ADD COLUMN author TEXT;").map_err(|e| self.sql_error(e))?;
+        }
+        Ok(())
+    }
+
+    /// Whether `table` exists with `column`, for reading older files as they are.
+    fn has_column(&self, connection: &Connection, table: &str, column: &str) -> FlowResult<bool> {
+        let mut statement = connection.prepare("SELECT name FROM pragma_table_info(?1)").map_err(|e| self.sql_error(e))?;
+        let columns = statement.query_map([table], |row| row.get::<_, String>(0))
+            .map_err(|e| self.sql_error(e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| self.sql_error(e))?;
+        Ok(columns.iter().any(|name| name == column))
+    }
+
+    fn sql_error(&self, e: rusqlite::Error) -> FlowError {
+        FlowError::io(&self.filename, e)
+    }
+}
+
+fn parse_yaml(text: &str) -> FlowResult<Value> {
+    serde_yaml::from_str(text).map_err(|e| FlowError::parse(format!("Failed to parse YAML: {}", e)))
+}
+
+fn to_yaml<T: serde::Serialize>(value: &T) -> FlowResult<String> {
+    serde_yaml::to_string(value).map_err(|e| FlowError::parse(format!("Failed to serialize to YAML: {}", e)))
+}
+
+impl ProjectStorage for SqliteStorage {
+    /// Reassembles the rows into the same document layout as the YAML backend and hands it
+    /// to `ProjectFile::from_value`, so version detection and migrations are shared.
+    fn load(&self) -> FlowResult<LoadedProjectFile> {
+        if !std::path::Path::new(&self.filename).exists() {
+            return Err(FlowError::io(&self.filename, "file does not exist"));
+        }
+        let connection = Connection::open_with_flags(&self.filename, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| self.sql_error(e))?;
+
+        let mut header = Mapping::new();
+        let mut document = Mapping::new();
+        let mut statement = connection.prepare("SELECT key, value FROM meta").map_err(|e| self.sql_error(e))?;
+        let rows = statement.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| self.sql_error(e))?;
+        for row in rows {
+            let (key, value) = row.map_err(|e| self.sql_error(e))?;
+            match key.as_str() {
+                "format_version" | "app_version" => { header.insert(key.into(), parse_yaml(&value)?); }
+                _ => { document.insert(key.into(), parse_yaml(&value)?); }
+            }
+        }
+        document.insert("header".into(), Value::Mapping(header));
+
+        let mut command_stack = Vec::new();
+        let author = if self.has_column(&connection, "commands", "author")? { "author" } else { "NULL" };
+        let mut statement = connection.prepare(&format!("SELECT undo_command, redo_command, {} FROM commands ORDER BY command_index", author))
+            .map_err(|e| self.sql_error(e))?;
+        let rows = statement.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?)))
+            .map_err(|e| self.sql_error(e))?;
+        for row in rows {
+            let (undo_command, redo_command, author) = row.map_err(|e| self.sql_error(e))?;
+            let mut record = Mapping::new();
+            record.insert("undo_command".into(), parse_yaml(&undo_command)?);
+            record.insert("redo_command".into(), parse_yaml(&redo_command)?);
+            if let Some(author) = author {
+                record.insert("author".into(), author.into());
+            }
+            command_stack.push(Value::Mapping(record));
+        }
+        document.insert("command_stack".into(), Value::Sequence(command_stack));
+
+        let mut checkpoints = Vec::new();
+        let mut statement = connection.prepare("SELECT command_index, commands_hash, flow_state FROM checkpoints ORDER BY command_index")
+            .map_err(|e| self.sql_error(e))?;
+        let rows = statement.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?)))
+            .map_err(|e| self.sql_error(e))?;
+        for row in rows {
+            let (command_index, commands_hash, flow_state) = row.map_err(|e| self.sql_error(e))?;
+            let mut checkpoint = Mapping::new();
+            checkpoint.insert("command_index".into(), (command_index as u64).into());
+            checkpoint.insert("commands_hash".into(), (commands_hash as u64).into());
+            checkpoint.insert("flow_state".into(), parse_yaml(&flow_state)?);
+            checkpoints.push(Value::Mapping(checkpoint));
+        }
+        document.insert("checkpoints".into(), Value::Sequence(checkpoints));
+
+        let mut branches = Vec::new();
+        if self.has_column(&connection, "branches", "records")? {
+            let mut statement = connection.prepare("SELECT fork_index, records FROM branches ORDER BY branch_index")
+                .map_err(|e| self.sql_error(e))?;
+            let rows = statement.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
+                .map_err(|e| self.sql_error(e))?;
+            for row in rows {
+                let (fork_index, records) = row.map_err(|e| self.sql_error(e))?;
+                let mut branch = Mapping::new();
+                branch.insert("fork_index".into(), (fork_index as u64).into());
+                branch.insert("records".into(), parse_yaml(&records)?);
+                branches.push(Value::Mapping(branch));
+            }
+        }
+        document.insert("branches".into(), Value::Sequence(branches));
+
+        let mut history_events = Vec::new();
+        if self.has_column(&connection, "history_events", "event")? {
+            let mut statement = connection.prepare("SELECT event FROM history_events ORDER BY event_index")
+                .map_err(|e| self.sql_error(e))?;
+            let rows = statement.query_map([], |row| row.get::<_, String>(0)).map_err(|e| self.sql_error(e))?;
+            for row in rows {
+                history_events.push(parse_yaml(&row.map_err(|e| self.sql_error(e))?)?);
+            }
+        }
+        document.insert("history_events".into(), Value::Sequence(history_events));
+
+        ProjectFile::from_value(Value::Mapping(document))
+    }
+
+    fn save(&self, file: &ProjectFile, flow_state: &FlowState, changes: &UnsavedChanges) -> FlowResult<()> {
+        let mut connection = self.open()?;
+        let transaction = connection.transaction().map_err(|e| self.sql_error(e))?;
+
+        {
+            let mut upsert_meta = transaction.prepare("INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)").map_err(|e| self.sql_error(e))?;
+            upsert_meta.execute(params!["format_version", to_yaml(&file.header.format_version)?]).map_err(|e| self.sql_error(e))?;
+            upsert_meta.execute(params!["app_version", to_yaml(&file.header.app_version)?]).map_err(|e| self.sql_error(e))?;
+            upsert_meta.execute(params!["num_commands_applied", to_yaml(&file.num_commands_applied)?]).map_err(|e| self.sql_error(e))?;
+            upsert_meta.execute(params!["permissions", to_yaml(&file.permissions)?]).map_err(|e| self.sql_error(e))?;
+
+            let first_command = changes.first_command.min(file.command_stack.len());
+            transaction.execute("DELETE FROM commands WHERE command_index >= ?1", [first_command as i64]).map_err(|e| self.sql_error(e))?;
+            let mut insert_command = transaction.prepare("INSERT INTO commands (command_index, undo_command, redo_command, author) VALUES (?1, ?2, ?3, ?4)")
+                .map_err(|e| self.sql_error(e))?;
+            for (index, record) in file.command_stack.iter().enumerate().skip(first_command) {
+                insert_command.execute(params![index as i64, to_yaml(&record.undo_command)?, to_yaml(&record.redo_command)?, record.author])
+                    .map_err(|e| self.sql_error(e))?;
+            }
+
+            if changes.branches {
+                transaction.execute_batch("DELETE FROM branches;").map_err(|e| self.sql_error(e))?;
+                let mut insert_branch = transaction.prepare("INSERT INTO branches (branch_index, fork_index, records) VALUES (?1, ?2, ?3)")
+                    .map_err(|e| self.sql_error(e))?;
+                for (index, branch) in file.branches.iter().enumerate() {
+                    insert_branch.execute(params![index as i64, branch.fork_index as i64, to_yaml(&branch.records)?])
+                        .map_err(|e| self.sql_error(e))?;
+                }
+            }
+
+            // History events are only ever appended.
+            let num_stored_events: i64 = transaction.query_row("SELECT COUNT(*) FROM history_events", [], |row| row.get(0))
+                .map_err(|e| self.sql_error(e))?;
+            let mut insert_event = transaction.prepare("INSERT INTO history_events (event_index, event) VALUES (?1, ?2)")
+                .map_err(|e| self.sql_error(e))?;
+            for (index, event) in file.history_events.iter().enumerate().skip(num_stored_events as usize) {
+                insert_event.execute(params![index as i64, to_yaml(event)?]).map_err(|e| self.sql_error(e))?;
+            }
+
+            // Checkpoints never change once taken, so only the new ones are written.
+            let stored_checkpoints: HashSet<(i64, i64)> = transaction.prepare("SELECT command_index, commands_hash FROM checkpoints")
+                .and_then(|mut statement| statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?.collect())
+                .map_err(|e| self.sql_error(e))?;
+            let checkpoint_keys: HashSet<(i64, i64)> = file.checkpoints.iter()
+                .map(|checkpoint| (checkpoint.command_index as i64, checkpoint.commands_hash as i64))
+                .collect();
+            for (command_index, _) in stored_checkpoints.difference(&checkpoint_keys) {
+                transaction.execute("DELETE FROM checkpoints WHERE command_index = ?1", [command_index]).map_err(|e| self.sql_error(e))?;
+            }
+            let mut insert_checkpoint = transaction.prepare("INSERT INTO checkpoints (command_index, commands_hash, flow_state) VALUES (?1, ?2, ?3)")
+                .map_err(|e| self.sql_error(e))?;
+            for checkpoint in file.checkpoints.iter() {
+                if !stored_checkpoints.contains(&(checkpoint.command_index as i64, checkpoint.commands_hash as i64)) {
+                    insert_checkpoint.execute(params![checkpoint.command_index as i64, checkpoint.commands_hash as i64, to_yaml(&checkpoint.flow_state)?])
+                        .map_err(|e| self.sql_error(e))?;
+                }
+            }
+
+            let task_ids: Vec<TaskId> = match &changes.tasks {
+                Some(task_ids) => {
+                    let mut delete_task = transaction.prepare("DELETE FROM tasks WHERE id = ?1").map_err(|e| self.sql_error(e))?;
+                    let mut delete_worklogs = transaction.prepare("DELETE FROM worklogs WHERE task_id = ?1").map_err(|e| self.sql_error(e))?;
+                    for task_id in task_ids {
+                        delete_task.execute([*task_id as i64]).map_err(|e| self.sql_error(e))?;
+                        delete_worklogs.execute([*task_id as i64]).map_err(|e| self.sql_error(e))?;
+                    }
+                    task_ids.iter().copied().collect()
+                }
+                None => {
+                    transaction.execute_batch("DELETE FROM tasks; DELETE FROM worklogs;").map_err(|e| self.sql_error(e))?;
+                    flow_state.tasks.keys().copied().collect()
+                }
+            };
+
+            let mut insert_task = transaction.prepare(
+                "INSERT INTO tasks (id, ticket, title, duration_days, duration_fraction, assignee) VALUES (?1, ?2, ?3, ?4, ?5, ?6)")
+                .map_err(|e| self.sql_error(e))?;
+            let mut insert_worklog = transaction.prepare(
+                "INSERT INTO worklogs (task_id, resource_id, date, fraction) VALUES (?1, ?2, ?3, ?4)")
+                .map_err(|e| self.sql_error(e))?;
+            for task_id in task_ids {
+                if let Some(task) = flow_state.tasks.get(&task_id) {
+                    insert_task.execute(params![
+                        task_id as i64,
+                        task.ticket,
+                        task.title,
+                        task.duration.days as i64,
+                        task.duration.fraction as i64,
+                        task.assignee.map(|resource_id| resource_id as i64),
+                    ]).map_err(|e| self.sql_error(e))?;
+                }
+                for worklogs_of_resource in flow_state.worklogs.get(&task_id).into_iter().flat_map(|resource_map| resource_map.values()) {
+                    for worklog in worklogs_of_resource.values() {
+                        insert_worklog.execute(params![
+                            worklog.task_id as i64,
+                            worklog.resource_id as i64,
+                            worklog.date.to_string(),
+                            worklog.fraction as i64,
+                        ]).map_err(|e| self.sql_error(e))?;
+                    }
+                }
+            }
+        }
+
+        transaction.commit().map_err(|e| self.sql_error(e))
+    }
+}
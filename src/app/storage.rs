@@ -0,0 +1,104 @@
+use crate::app::*;
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// File extensions that select the SQLite backend; anything else is stored as YAML.
+pub const SQLITE_EXTENSIONS: [&str; 3] = ["db", "sqlite", "sqlite3"];
+
+/// Where a `Project` keeps its command log and checkpoints. `flow_state` is passed on save
+/// so backends can additionally materialize the current state for external tooling, and
+/// `changes` so that they can write only what changed since the last save.
+pub trait ProjectStorage {
+    /// Reads the file without writing to it.
+    fn load(&self) -> FlowResult<LoadedProjectFile>;
+    fn save(&self, file: &ProjectFile, flow_state: &FlowState, changes: &UnsavedChanges) -> FlowResult<()>;
+}
+
+/// What changed since the project was last loaded or saved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsavedChanges {
+    /// Records of the command stack from this index on were added or replaced.
+    pub first_command: usize,
+    pub branches: bool,
+    /// Tasks whose row or worklogs may have changed, or `None` if any may have.
+    pub tasks: Option<BTreeSet<TaskId>>,
+}
+
+impl UnsavedChanges {
+    /// Nothing changed in a project of `num_commands` command records.
+    pub fn none(num_commands: usize) -> Self {
+        UnsavedChanges { first_command: num_commands, branches: false, tasks: Some(BTreeSet::new()) }
+    }
+
+    pub fn everything() -> Self {
+        UnsavedChanges { first_command: 0, branches: true, tasks: None }
+    }
+
+    pub fn add_commands_from(&mut self, index: usize) {
+        self.first_command = self.first_command.min(index);
+    }
+
+    pub fn add_tasks(&mut self, task_ids: impl IntoIterator<Item = TaskId>) {
+        if let Some(tasks) = &mut self.tasks {
+            tasks.extend(task_ids);
+        }
+    }
+}
+
+impl Default for UnsavedChanges {
+    fn default() -> Self {
+        UnsavedChanges::everything()
+    }
+}
+
+pub struct YamlStorage {
+    filename: String,
+}
+
+impl YamlStorage {
+    pub fn new(filename: &str) -> Self {
+        Self { filename: filename.to_string() }
+    }
+}
+
+impl ProjectStorage for YamlStorage {
+    fn load(&self) -> FlowResult<LoadedProjectFile> {
+        let contents = std::fs::read_to_string(&self.filename).map_err(|e| FlowError::io(&self.filename, e))?;
+        ProjectFile::from_yaml_str(&contents)
+    }
+
+    fn save(&self, file: &ProjectFile, _flow_state: &FlowState, _changes: &UnsavedChanges) -> FlowResult<()> {
+        let yaml_string = file.to_yaml_string()?;
+        std::fs::write(&self.filename, yaml_string).map_err(|e| FlowError::io(&self.filename, e))
+    }
+}
+
+pub fn is_sqlite_filename(filename: &str) -> bool {
+    Path::new(filename).extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| SQLITE_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+pub fn storage_for(filename: &str) -> Box<dyn ProjectStorage> {
+    if is_sqlite_filename(filename) {
+        Box::new(SqliteStorage::new(filename))
+    } else {
+        Box::new(YamlStorage::new(filename))
+    }
+}
+
+/// Copies the project in `source` to `destination`, each using the backend selected by its extension.
+pub fn convert_project_file(source: &str, destination: &str, date: NaiveDate) -> FlowResult<()> {
+    let loaded = storage_for(source).load()?;
+    if loaded.read_only {
+        return Err(FlowError::invariant(EntityKind::Project, source, format!(
+            "cannot be converted: {}", loaded.warning.unwrap_or_default())));
+    }
+    let file = loaded.file;
+    let applied_commands = file.command_stack.iter().take(file.num_commands_applied)
+        .map(|record| record.redo_command.clone()).collect::<Vec<_>>();
+    let flow_state = FlowState::from_checkpoints(&applied_commands, &file.checkpoints, date)?;
+    let file = ProjectFile::new(file.num_commands_applied, &file.command_stack, &file.checkpoints, &file.branches, &file.permissions, &file.history_events);
+    storage_for(destination).save(&file, &flow_state, &UnsavedChanges::everything())
+}
@@ -2,6 +2,7 @@ use crate::app::*;
 use chrono::NaiveDate;
 use chrono::Duration;
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 #[derive(Debug, Clone)]
 pub struct TaskInspection {
@@ -10,6 +11,9 @@ pub struct TaskInspection {
     pub absences_history: HashMap<NaiveDate, HashMap<NaiveDate, Fraction>>,
     pub worklogs_history: HashMap<NaiveDate, HashMap<NaiveDate, Fraction>>,
     pub assignee_history: HashMap<NaiveDate, Option<ResourceId>>,
+    pub estimate_history: HashMap<NaiveDate, TaskDuration>,
+    /// Descriptions of the commands of each day that touched the task or its assignee.
+    pub changes_history: HashMap<NaiveDate, Vec<String>>,
     pub flow_state: FlowState,
     pub start_date: NaiveDate,
 }
@@ -22,85 +26,308 @@ impl TaskInspection {
             absences_history: HashMap::new(),
             worklogs_history: HashMap::new(),
             assignee_history: HashMap::new(),
+            estimate_history: HashMap::new(),
+            changes_history: HashMap::new(),
             flow_state: FlowState::new(),
             start_date: NaiveDate::MAX,
         }
     }
 
-    pub fn from(inspected_task_id: TaskId, commands: Vec<Command>, date: NaiveDate) -> Self {
+    pub fn from(inspected_task_id: TaskId, commands: Vec<Command>, checkpoints: &[Checkpoint], date: NaiveDate) -> FlowResult<Self> {
+        let snapshots = Mutex::new(InspectionSnapshots::default());
+        let inspection = TaskInspection::build(inspected_task_id, &commands, checkpoints, date, &snapshots, &InspectionControl::default())?;
+        Ok(inspection.expect("an inspection without a control is never cancelled"))
+    }
+
+    /// Replays `commands` day by day and records how the inspected task was scheduled on each
+    /// day. Returns `None` if cancelled through `control`.
+    pub fn build(
+        inspected_task_id: TaskId,
+        commands: &[Command],
+        checkpoints: &[Checkpoint],
+        date: NaiveDate,
+        snapshots: &Mutex<InspectionSnapshots>,
+        control: &InspectionControl,
+    ) -> FlowResult<Option<Self>> {
         let mut task_inspector = TaskInspection::new(inspected_task_id);
-            let extract_create_date = |mut commands: Vec<Command>| -> Option<NaiveDate> {
-                while let Some(c) = commands.pop() {
-                    if let CommandDetails::CreateTask { id, .. } = c.details {
-                        if id == inspected_task_id {
-                            return Some(c.timestamp.date_naive());
-                        }
-                    } else if let CommandDetails::CompoundCommand { commands: inner_commands } = c.details {
-                        commands.extend(inner_commands);
-                    }
+        task_inspector.start_date = creation_date(commands, |details| {
+            matches!(details, CommandDetails::CreateTask { id, .. } if *id == inspected_task_id)
+        }).ok_or_else(|| FlowError::not_found(EntityKind::Task, inspected_task_id))?;
+
+        let flow_state = replay_days(commands, checkpoints, date, snapshots, control, |flow_state, day, day_commands| {
+            task_inspector.record_changes(flow_state, day, day_commands);
+            task_inspector.record_day(flow_state, day);
+        })?;
+        Ok(flow_state.map(|flow_state| {
+            task_inspector.flow_state = flow_state;
+            task_inspector
+        }))
+    }
+
+    /// Must run before `record_day` so the previous day's assignee and estimate are at hand.
+    fn record_changes(&mut self, flow_state: &FlowState, date: NaiveDate, commands: &[Command]) {
+        let previous_day = date - Duration::days(1);
+        let previous_assignee = self.assignee_history.get(&previous_day).copied().flatten();
+        let assignee = flow_state.tasks.get(&self.task_id).and_then(|task| task.assignee);
+        let mut estimate = self.estimate_history.get(&previous_day).copied();
+        let resource_name = |resource_id: &ResourceId| flow_state.resources.get(resource_id)
+            .map(|resource| resource.name.clone())
+            .unwrap_or_else(|| format!("resource {}", resource_id));
+        let ticket = |task_id: &TaskId| flow_state.tasks.get(task_id)
+            .map(|task| task.ticket.clone())
+            .unwrap_or_else(|| format!("task {}", task_id));
+        let is_assignee = |resource_id: &ResourceId| Some(*resource_id) == assignee || Some(*resource_id) == previous_assignee;
+
+        let mut changes = Vec::new();
+        for details in flatten_commands(commands) {
+            let change = match details {
+                CommandDetails::CreateTask { id, duration, .. } if *id == self.task_id => {
+                    estimate = Some(*duration);
+                    format!("Created with an estimate of {}", duration)
                 }
-                None
+                CommandDetails::UpdateTask { id, duration, .. } if *id == self.task_id => {
+                    let change = match estimate {
+                        Some(previous) if previous != *duration => format!("Estimate changed from {} to {}", previous, duration),
+                        _ => "Ticket or title updated".to_string(),
+                    };
+                    estimate = Some(*duration);
+                    change
+                }
+                CommandDetails::AssignTask { task_id, resource_id } if *task_id == self.task_id =>
+                    format!("Assigned to {}", resource_name(resource_id)),
+                CommandDetails::AssignTask { task_id, resource_id } if is_assignee(resource_id) =>
+                    format!("{} assigned to {} ahead of this task", ticket(task_id), resource_name(resource_id)),
+                CommandDetails::UnassignTask { task_id } if *task_id == self.task_id => "Unassigned".to_string(),
+                CommandDetails::PrioritizeTask { task_id, to_top } if *task_id == self.task_id =>
+                    if *to_top { "Moved to the top of the queue" } else { "Moved up the queue" }.to_string(),
+                CommandDetails::DeprioritizeTask { task_id, to_bottom } if *task_id == self.task_id =>
+                    if *to_bottom { "Moved to the bottom of the queue" } else { "Moved down the queue" }.to_string(),
+                CommandDetails::ChangeTaskPriority { task_id, delta } if *task_id == self.task_id =>
+                    format!("Moved by {} in the queue", delta),
+                CommandDetails::SetAbsence { resource_id, start_date, days } if is_assignee(resource_id) =>
+                    format!("{} absent for {} from {}", resource_name(resource_id), days, start_date),
+                _ => continue,
             };
-        task_inspector.start_date = extract_create_date(commands.clone()).unwrap();
-
-        let (start_date, end_date) = {
-            let flow_state = FlowState::from_commands(&commands, date).unwrap();
-            let s = flow_state.cache().day(0);
-            let e = flow_state.cache().day(flow_state.cache().num_days() - 1);
-            dbg!((s, e));
-            (s, e)
-        };
+            changes.push(change);
+        }
+        if !changes.is_empty() {
+            self.changes_history.insert(date, changes);
+        }
+    }
 
-        let mut failed_commands: Vec<Command> = Vec::new();
-        let mut commands_by_date = HashMap::new();
-        for cmd in commands {
-            let date = cmd.timestamp.date_naive();
-            commands_by_date.entry(date).or_insert_with(Vec::new).push(cmd);
+    fn record_day(&mut self, flow_state: &FlowState, date: NaiveDate) {
+        let assignee = flow_state.tasks.get(&self.task_id)
+            .and_then(|task| task.assignee);
+        self.assignee_history.insert(date, assignee);
+        if let Some(task) = flow_state.tasks.get(&self.task_id) {
+            self.estimate_history.insert(date, task.duration);
         }
-        let mut flow_state = FlowState::new();
-        let mut date_it = start_date;
-        while date_it <= end_date {
-            failed_commands.retain(|cmd| {
-                flow_state
-                    .execute_command_and_generate_inverse(cmd.clone())
-                    .is_err()
-            });
-            if let Some(cmds) = commands_by_date.get(&date_it) {
-                for cmd in cmds {
-                    println!("executing command: {:?}", cmd);
-                    if flow_state
-                        .execute_command_and_generate_inverse(cmd.clone())
-                        .is_err()
-                    {
-                        failed_commands.push(cmd.clone());
-                    }
-                }
+        self.allocations_history.insert(date,
+                flow_state.cache().task_alloc_rendering
+                    .get(&self.task_id).cloned().unwrap_or_default());
+        if let Some(assignee) = assignee {
+            self.absences_history.insert(date,
+                    flow_state.cache().resource_absence_rendering
+                        .get(&assignee).cloned().unwrap_or_default());
+        }
+        self.worklogs_history.insert(date,
+                flow_state.worklogs.get(&self.task_id)
+                    .and_then(|resource_map| resource_map.get(&assignee.unwrap_or(0)))
+                    .map(|date_map| {
+                        date_map.iter()
+                            .map(|(d, w)| (*d, w.fraction))
+                            .collect::<HashMap<NaiveDate, Fraction>>()
+                    }).unwrap_or_default());
+    }
+}
+
+/// Date of the earliest command matching `is_creation`, looking inside compound commands too.
+pub(crate) fn creation_date(commands: &[Command], is_creation: impl Fn(&CommandDetails) -> bool) -> Option<NaiveDate> {
+    let mut commands = commands.to_vec();
+    let mut earliest: Option<NaiveDate> = None;
+    while let Some(c) = commands.pop() {
+        if is_creation(&c.details) {
+            let date = c.timestamp.date_naive();
+            earliest = Some(earliest.map_or(date, |earliest| earliest.min(date)));
+        } else if let CommandDetails::CompoundCommand { commands: inner_commands } = c.details {
+            commands.extend(inner_commands);
+        }
+    }
+    earliest
+}
+
+/// The details of `commands` with compound commands expanded, in order of execution.
+pub(crate) fn flatten_commands(commands: &[Command]) -> Vec<&CommandDetails> {
+    let mut flattened = Vec::new();
+    for command in commands {
+        match &command.details {
+            CommandDetails::CompoundCommand { commands } => flattened.extend(flatten_commands(commands)),
+            details => flattened.push(details),
+        }
+    }
+    flattened
+}
+
+/// Replays `commands` day by day over the scheduled range and hands the state at the end of
+/// each day to `record`, with the commands of that day. The replay resumes from the
+/// `snapshots` another inspection of the same history already took, which the history is
+/// told by its hash continued from the latest of `checkpoints`. Returns the final state, or
+/// `None` if cancelled.
+pub(crate) fn replay_days(
+    commands: &[Command],
+    checkpoints: &[Checkpoint],
+    date: NaiveDate,
+    snapshots: &Mutex<InspectionSnapshots>,
+    control: &InspectionControl,
+    mut record: impl FnMut(&FlowState, NaiveDate, &[Command]),
+) -> FlowResult<Option<FlowState>> {
+    let commands_hash = Checkpoint::hash_commands_from(checkpoints, commands);
+    let cached_range = snapshots.lock().unwrap().range(commands_hash, date);
+    let (start_date, end_date) = match cached_range {
+        Some(range) => range,
+        None => {
+            let flow_state = FlowState::from_checkpoints(commands, checkpoints, date)?;
+            let range = (flow_state.cache().day(0), flow_state.cache().day(flow_state.cache().num_days() - 1));
+            snapshots.lock().unwrap().set_range(commands_hash, date, range);
+            range
+        }
+    };
+
+    let mut commands_by_date = HashMap::new();
+    for cmd in commands {
+        let date = cmd.timestamp.date_naive();
+        commands_by_date.entry(date).or_insert_with(Vec::new).push(cmd.clone());
+    }
+    let num_days = (end_date - start_date).num_days() + 1;
+    let mut previous_snapshot: Option<Arc<DaySnapshot>> = None;
+    let mut date_it = start_date;
+    while date_it <= end_date {
+        if control.is_cancelled() {
+            return Ok(None);
+        }
+        let cached_snapshot = snapshots.lock().unwrap().get(commands_hash, date, date_it);
+        let snapshot = match cached_snapshot {
+            Some(snapshot) => snapshot,
+            None => {
+                let snapshot = Arc::new(DaySnapshot::replay(previous_snapshot.as_deref(), commands_by_date.get(&date_it), date_it));
+                snapshots.lock().unwrap().insert(commands_hash, date, start_date, date_it, snapshot.clone());
+                snapshot
             }
-            flow_state.rebuild_cache(date_it);
-            let assignee = flow_state.tasks.get(&inspected_task_id)
-                .and_then(|task| task.assignee);
-            task_inspector.assignee_history.insert(date_it, assignee);
-            task_inspector.allocations_history.insert(date_it, 
-                    flow_state.cache().task_alloc_rendering
-                        .get(&inspected_task_id).cloned().unwrap_or_default());
-            if let Some(assignee) = assignee {
-                task_inspector.absences_history.insert(date_it, 
-                        flow_state.cache().resource_absence_rendering
-                            .get(&assignee).cloned().unwrap_or_default());
+        };
+        record(&snapshot.flow_state, date_it, commands_by_date.get(&date_it).map(Vec::as_slice).unwrap_or_default());
+        previous_snapshot = Some(snapshot);
+        control.set_progress((date_it - start_date).num_days() as f32 / num_days as f32);
+        date_it = date_it + Duration::days(1);
+    }
+
+    control.set_progress(1.0);
+    Ok(Some(previous_snapshot.map(|snapshot| snapshot.flow_state.clone()).unwrap_or_default()))
+}
+
+/// The replayed state at the end of one day, with the commands that could not be applied yet
+/// and are retried on the following days.
+#[derive(Debug, Clone)]
+pub struct DaySnapshot {
+    pub flow_state: FlowState,
+    pub failed_commands: Vec<Command>,
+}
+
+impl DaySnapshot {
+    fn replay(previous: Option<&DaySnapshot>, commands: Option<&Vec<Command>>, date: NaiveDate) -> Self {
+        let (mut flow_state, mut failed_commands) = match previous {
+            Some(previous) => (previous.flow_state.clone(), previous.failed_commands.clone()),
+            None => (FlowState::new(), Vec::new()),
+        };
+        failed_commands.retain(|cmd| {
+            flow_state
+                .execute_command_and_generate_inverse(cmd.clone())
+                .is_err()
+        });
+        for cmd in commands.into_iter().flatten() {
+            if flow_state
+                .execute_command_and_generate_inverse(cmd.clone())
+                .is_err()
+            {
+                failed_commands.push(cmd.clone());
             }
-            task_inspector.worklogs_history.insert(date_it, 
-                    flow_state.worklogs.get(&inspected_task_id)
-                        .and_then(|resource_map| resource_map.get(&assignee.unwrap_or(0)))
-                        .map(|date_map| {
-                            date_map.iter()
-                                .map(|(d, w)| (*d, w.fraction))
-                                .collect::<HashMap<NaiveDate, Fraction>>()
-                        }).unwrap_or_default());
-            date_it = date_it + Duration::days(1);
         }
-        
-        task_inspector.flow_state = flow_state;
-        dbg!((task_inspector.flow_state.cache().start_date, task_inspector.flow_state.cache().end_date));
-        task_inspector
+        flow_state.rebuild_cache(date);
+        DaySnapshot { flow_state, failed_commands }
+    }
+}
+
+/// Days between the snapshots kept for inspections, until there are too many of them.
+pub const INSPECTION_SNAPSHOT_INTERVAL_DAYS: i64 = 7;
+/// Most day snapshots kept for inspections. Beyond it the interval between them is doubled.
+pub const MAX_INSPECTION_SNAPSHOTS: usize = 64;
+
+/// Day snapshots shared by all inspections of the same command history on the same date,
+/// with the range of days they replay. Only every `interval_days`-th day is kept, so the days
+/// in between are replayed again from the snapshot before them. They are dropped as soon as
+/// a snapshot of a different history or date is requested.
+#[derive(Debug)]
+pub struct InspectionSnapshots {
+    commands_hash: u64,
+    date: Option<NaiveDate>,
+    range: Option<(NaiveDate, NaiveDate)>,
+    interval_days: i64,
+    days: HashMap<NaiveDate, Arc<DaySnapshot>>,
+}
+
+impl Default for InspectionSnapshots {
+    fn default() -> Self {
+        InspectionSnapshots {
+            commands_hash: 0,
+            date: None,
+            range: None,
+            interval_days: INSPECTION_SNAPSHOT_INTERVAL_DAYS,
+            days: HashMap::new(),
+        }
+    }
+}
+
+impl InspectionSnapshots {
+    fn range(&mut self, commands_hash: u64, date: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        self.invalidate_unless(commands_hash, date);
+        self.range
+    }
+
+    fn set_range(&mut self, commands_hash: u64, date: NaiveDate, range: (NaiveDate, NaiveDate)) {
+        self.invalidate_unless(commands_hash, date);
+        self.range = Some(range);
+    }
+
+    fn get(&mut self, commands_hash: u64, date: NaiveDate, day: NaiveDate) -> Option<Arc<DaySnapshot>> {
+        self.invalidate_unless(commands_hash, date);
+        self.days.get(&day).cloned()
+    }
+
+    /// Keeps `snapshot` if `day` is a whole number of intervals after `start_date`, thinning
+    /// out every other snapshot once there are more than `MAX_INSPECTION_SNAPSHOTS`.
+    fn insert(&mut self, commands_hash: u64, date: NaiveDate, start_date: NaiveDate, day: NaiveDate, snapshot: Arc<DaySnapshot>) {
+        self.invalidate_unless(commands_hash, date);
+        if (day - start_date).num_days() % self.interval_days != 0 {
+            return;
+        }
+        self.days.insert(day, snapshot);
+        if self.days.len() > MAX_INSPECTION_SNAPSHOTS {
+            self.interval_days *= 2;
+            let interval_days = self.interval_days;
+            self.days.retain(|day, _| (*day - start_date).num_days() % interval_days == 0);
+        }
     }
-}
\ No newline at end of file
+
+    fn invalidate_unless(&mut self, commands_hash: u64, date: NaiveDate) {
+        if self.commands_hash != commands_hash || self.date != Some(date) {
+            *self = InspectionSnapshots { commands_hash, date: Some(date), ..InspectionSnapshots::default() };
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.days.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.days.is_empty()
+    }
+}
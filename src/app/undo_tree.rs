@@ -0,0 +1,73 @@
+use crate::app::*;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A redo chain that is not on the active command stack, kept when a new command is invoked
+/// after undoing or when switching to another branch. It continues the first `fork_index`
+/// records of the active command stack, so together they form an undo tree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoBranch {
+    pub fork_index: usize,
+    pub records: Vec<CommandRecord>,
+}
+
+/// Cuts the records after the first `fork_index` off `command_stack` and keeps them as a
+/// branch. Branches forking off inside the cut records are rebased onto `fork_index` by
+/// prefixing them with the records they were continuing.
+pub fn split_off_branch(command_stack: &mut Vec<CommandRecord>, branches: &mut Vec<UndoBranch>, fork_index: usize) {
+    if fork_index >= command_stack.len() {
+        return;
+    }
+    let records = command_stack.split_off(fork_index);
+    for branch in branches.iter_mut().filter(|branch| branch.fork_index > fork_index) {
+        let mut rebased = records[..branch.fork_index - fork_index].to_vec();
+        rebased.append(&mut branch.records);
+        branch.records = rebased;
+        branch.fork_index = fork_index;
+    }
+    branches.push(UndoBranch { fork_index, records });
+}
+
+/// A move through the history of the plan that invokes no new command, kept so that the audit
+/// log can tell who undid, redid or jumped over whose commands.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEvent {
+    pub timestamp: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub kind: HistoryEventKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum HistoryEventKind {
+    /// The command, as invoked, was undone.
+    Undo { command: Command },
+    /// The command, as invoked, was executed again after being undone.
+    Redo { command: Command },
+    /// The number of applied commands went from `from` to `to` in one go.
+    Jump { from: usize, to: usize },
+    /// The undo branch of `num_commands` records forking at `fork_index` became the active
+    /// command stack.
+    SwitchBranch { fork_index: usize, num_commands: usize },
+}
+
+impl HistoryEventKind {
+    pub fn describe(&self, flow_state: &FlowState) -> String {
+        match self {
+            HistoryEventKind::Undo { command } => format!("Undo: {}", command.details.describe(flow_state)),
+            HistoryEventKind::Redo { command } => format!("Redo: {}", command.details.describe(flow_state)),
+            HistoryEventKind::Jump { from, to } => format!("Jump from {} to {} applied commands", from, to),
+            HistoryEventKind::SwitchBranch { fork_index, num_commands } =>
+                format!("Switch to the undo branch of {} commands after command {}", num_commands, fork_index),
+        }
+    }
+
+    /// The entities of the undone or redone command; moves of the whole history are about the
+    /// project.
+    pub fn subjects(&self) -> Vec<(EntityKind, EntityKey)> {
+        match self {
+            HistoryEventKind::Undo { command } | HistoryEventKind::Redo { command } => command.details.subjects(),
+            HistoryEventKind::Jump { .. } | HistoryEventKind::SwitchBranch { .. } => vec![(EntityKind::Project, "history".into())],
+        }
+    }
+}
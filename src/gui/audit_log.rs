@@ -0,0 +1,156 @@
+use crate::gui::*;
+use crate::gui::utils::*;
+use crate::gui_log;
+use crate::gui_notify;
+
+/// Kinds of entity commands are about, offered by the audit log's entity filter.
+const AUDIT_ENTITY_KINDS: [EntityKind; 8] = [
+    EntityKind::Team,
+    EntityKind::Resource,
+    EntityKind::Task,
+    EntityKind::Label,
+    EntityKind::Filter,
+    EntityKind::Milestone,
+    EntityKind::Worklog,
+    EntityKind::Project,
+];
+
+impl Gui {
+    pub(super) fn draw_audit_log_tab(&mut self, ui: &Ui) {
+        if !self.show_audit_log {
+            return;
+        }
+        let mut open = true;
+        if let Some(_tab_token) = TabItem::new("Audit Log")
+            .opened(&mut open)
+            .begin(ui)
+        {
+            let filter = self.draw_audit_log_filter(ui);
+            let audit_log = AuditLog::build(
+                &self.project.command_stack,
+                self.project.num_commands_applied(),
+                self.project.history_events(),
+                self.project.plan_flow_state(),
+                &filter);
+            ui.same_line();
+            if ui.button("Export CSV...") {
+                self.export_audit_log(&audit_log);
+            }
+            self.draw_audit_log_table(ui, &audit_log);
+        }
+        if !open {
+            self.show_audit_log = false;
+        }
+    }
+
+    fn draw_audit_log_filter(&mut self, ui: &Ui) -> AuditFilter {
+        let mut filter = AuditFilter::default();
+
+        ui.set_next_item_width(AUDIT_LOG_COMBO_WIDTH);
+        let author_preview = self.audit_author.clone().unwrap_or_else(|| "Any author".to_string());
+        if let Some(_combo) = ui.begin_combo("##audit_author", &author_preview) {
+            if ui.selectable_config("Any author").selected(self.audit_author.is_none()).build() {
+                self.audit_author = None;
+            }
+            for author in AuditLog::authors(&self.project.command_stack, self.project.history_events()) {
+                let is_selected = self.audit_author.as_ref() == Some(&author);
+                if ui.selectable_config(&author).selected(is_selected).build() {
+                    self.audit_author = Some(author);
+                }
+            }
+        }
+        filter.author = self.audit_author.clone();
+
+        ui.same_line();
+        ui.set_next_item_width(AUDIT_LOG_COMBO_WIDTH);
+        let kind_preview = self.audit_entity_kind.map(|kind| kind.to_string()).unwrap_or_else(|| "Any entity".to_string());
+        if let Some(_combo) = ui.begin_combo("##audit_entity_kind", &kind_preview) {
+            if ui.selectable_config("Any entity").selected(self.audit_entity_kind.is_none()).build() {
+                self.audit_entity_kind = None;
+            }
+            for kind in AUDIT_ENTITY_KINDS {
+                if ui.selectable_config(kind.to_string()).selected(self.audit_entity_kind == Some(kind)).build() {
+                    self.audit_entity_kind = Some(kind);
+                }
+            }
+        }
+        filter.entity_kind = self.audit_entity_kind;
+
+        if let Some(kind) = self.audit_entity_kind {
+            ui.same_line();
+            ui.set_next_item_width(AUDIT_LOG_COMBO_WIDTH);
+            ui.input_text("##audit_entity", &mut self.audit_entity_input_text_buffer)
+                .hint("Name, ticket or id")
+                .build();
+            let text = self.audit_entity_input_text_buffer.trim();
+            if !text.is_empty() {
+                filter.entity_key = Some(AuditFilter::resolve_entity_key(self.project.plan_flow_state(), kind, text));
+            }
+        }
+
+        ui.same_line();
+        ui.set_next_item_width(SLIP_REPORT_DATE_INPUT_WIDTH);
+        ui.input_text("##audit_from", &mut self.audit_from_input_text_buffer)
+            .hint("From (YYYY-MM-DD)")
+            .build();
+        ui.same_line();
+        ui.set_next_item_width(SLIP_REPORT_DATE_INPUT_WIDTH);
+        ui.input_text("##audit_to", &mut self.audit_to_input_text_buffer)
+            .hint("To (YYYY-MM-DD)")
+            .build();
+        // Dates are applied once they parse, so half-typed ones do not clear the log.
+        filter.from = parse_optional_date(&self.audit_from_input_text_buffer).unwrap_or(None);
+        filter.to = parse_optional_date(&self.audit_to_input_text_buffer).unwrap_or(None);
+        filter
+    }
+
+    fn export_audit_log(&mut self, audit_log: &AuditLog) {
+        if let Some(file_path) = rfd::FileDialog::new()
+            .add_filter("CSV files", &["csv"])
+            .set_directory(".")
+            .save_file()
+        {
+            let filename = file_path.to_string_lossy().to_string();
+            match audit_log.export_csv(&filename) {
+                Ok(()) => gui_log!(self, "Exported audit log to {filename}"),
+                Err(e) => gui_notify!(self, "Failed to export audit log: {e}"),
+            }
+        }
+    }
+
+    fn draw_audit_log_table(&self, ui: &Ui, audit_log: &AuditLog) {
+        let flags = TableFlags::BORDERS | TableFlags::ROW_BG | TableFlags::SCROLL_Y | TableFlags::RESIZABLE;
+        let Some(_table) = ui.begin_table_with_flags("##audit_log_table", 4, flags) else {
+            return;
+        };
+        ui.table_setup_scroll_freeze(0, 1);
+        for name in ["#", "Timestamp", "Author", "Command"] {
+            ui.table_setup_column(name);
+        }
+        ui.table_headers_row();
+        let mut clipper = ListClipper::new(audit_log.entries.len() as i32).begin(ui);
+        while clipper.step() {
+            for row in clipper.display_start()..clipper.display_end() {
+                let entry = &audit_log.entries[row as usize];
+                ui.table_next_row();
+                ui.table_next_column();
+                if let Some(index) = entry.index {
+                    ui.text(index.to_string());
+                }
+                ui.table_next_column();
+                ui.text(entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string());
+                ui.table_next_column();
+                ui.text(entry.author.as_deref().unwrap_or("unknown"));
+                ui.table_next_column();
+                if entry.applied {
+                    ui.text(&entry.description);
+                } else {
+                    ui.text_disabled(format!("{} (undone)", entry.description));
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text(&entry.description);
+                }
+            }
+        }
+    }
+}
@@ -9,6 +9,13 @@ pub struct GuiConfig {
     pub hide_non_deviations_in_inspection: bool,
     pub debug_mode: bool,
     pub recent_project_files: Vec<String>,
+    /// Recorded as the author of the commands invoked from this GUI.
+    #[serde(default = "default_author")]
+    pub author: String,
+}
+
+fn default_author() -> String {
+    std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default()
 }
 
 impl GuiConfig {
@@ -26,6 +33,7 @@ impl GuiConfig {
             hide_non_deviations_in_inspection: false,
             debug_mode: false,
             recent_project_files: Vec::new(),
+            author: default_author(),
         }
     }
 
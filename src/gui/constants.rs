@@ -4,8 +4,38 @@ pub const CREATE_TEAM_CHILD_WINDOW_SIZE: [f32; 2] = NEW_PROJECT_CHILD_WINDOW_SIZ
 pub const RENAME_TEAM_CHILD_WINDOW_SIZE: [f32; 2] = CREATE_TEAM_CHILD_WINDOW_SIZE;
 pub const CREATE_RESOURCE_CHILD_WINDOW_SIZE: [f32; 2] = CREATE_TEAM_CHILD_WINDOW_SIZE;
 pub const RENAME_RESOURCE_CHILD_WINDOW_SIZE: [f32; 2] = CREATE_RESOURCE_CHILD_WINDOW_SIZE;
-pub const CREATE_TASK_CHILD_WINDOW_SIZE: [f32; 2] = [180.0, 150.0];
+pub const CREATE_TASK_CHILD_WINDOW_SIZE: [f32; 2] = [180.0, 210.0];
 pub const UPDATE_TASK_CHILD_WINDOW_SIZE: [f32; 2] = CREATE_TASK_CHILD_WINDOW_SIZE;
 pub const CREATE_MILESTONE_CHILD_WINDOW_SIZE: [f32; 2] = [240.0, 70.0];
 pub const SET_WORKLOG_CHILD_WINDOW_SIZE: [f32; 2] = [260.0, 100.0];
 pub const CREATE_LABEL_CHILD_WINDOW_SIZE: [f32; 2] = [240.0, 70.0];
+pub const NOTIFICATION_DURATION: std::time::Duration = std::time::Duration::from_secs(5);
+pub const NOTIFICATION_MARGIN: f32 = 10.0;
+pub const NOTIFICATION_MAX_WIDTH: f32 = 400.0;
+pub const NOTIFICATION_TEXT_COLOR: [f32; 4] = [0.8, 0.1, 0.1, 1.0];
+pub const INSPECTION_PROGRESS_BAR_SIZE: [f32; 2] = [300.0, 0.0];
+pub const INSPECTION_CHANGE_ICON_COLOR: [f32; 4] = [0.95, 0.6, 0.1, 1.0];
+pub const SLIP_REPORT_DATE_INPUT_WIDTH: f32 = 150.0;
+pub const AS_OF_INPUT_WIDTH: f32 = 100.0;
+pub const TIME_TRAVEL_BANNER_COLOR: [f32; 4] = [0.85, 0.4, 0.0, 1.0];
+pub const NEW_SCENARIO_CHILD_WINDOW_SIZE: [f32; 2] = NEW_PROJECT_CHILD_WINDOW_SIZE;
+pub const SCENARIO_BANNER_COLOR: [f32; 4] = [0.3, 0.7, 1.0, 1.0];
+pub const SCENARIO_DIFF_WORSE_COLOR: [f32; 4] = [1.0, 0.4, 0.4, 1.0];
+pub const SCENARIO_DIFF_BETTER_COLOR: [f32; 4] = [0.4, 0.9, 0.4, 1.0];
+pub const HISTORY_INDEX_COLUMN_WIDTH: f32 = 50.0;
+pub const HISTORY_TIMESTAMP_COLUMN_WIDTH: f32 = 140.0;
+pub const HISTORY_ACTIONS_COLUMN_WIDTH: f32 = 130.0;
+pub const HISTORY_CURRENT_ROW_COLOR: [f32; 4] = [0.2, 0.4, 0.2, 1.0];
+pub const AUDIT_LOG_COMBO_WIDTH: f32 = 150.0;
+pub const MEMBER_COMBO_WIDTH: f32 = 120.0;
+pub const QUERY_INPUT_WIDTH: f32 = 320.0;
+pub const GANTT_SPREAD_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.2];
+pub const GANTT_CHART_DAY_COLUMN_WIDTH: f32 = 24.0;
+/// Kept well under the 512 columns an ImGui table can have.
+pub const GANTT_CHART_MAX_DAY_COLUMNS: usize = 256;
+pub const GANTT_CHART_WHEEL_SCROLL_DAYS: usize = 7;
+pub const FORECAST_INPUT_WIDTH: f32 = 100.0;
+pub const FORECAST_MAX_ITERATIONS: i32 = 100_000;
+pub const FORECAST_CHART_HEIGHT: f32 = 120.0;
+/// Chance of reaching a milestone in time below which it is shown as at risk.
+pub const FORECAST_LIKELY_PROBABILITY: f64 = 0.8;
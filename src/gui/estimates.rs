@@ -0,0 +1,60 @@
+use crate::gui::*;
+use crate::gui_log;
+use crate::gui_notify;
+
+impl Gui {
+    /// The project setting choosing which estimate of the tasks the schedule is built from.
+    pub(super) fn draw_estimate_basis_menu(&mut self, ui: &Ui) {
+        let Some(_basis_menu) = ui.begin_menu("Schedule By") else {
+            return;
+        };
+        let current = self.project.flow_state().estimate_basis;
+        for basis in EstimateBasis::ALL {
+            if ui.menu_item_config(basis.name()).selected(basis == current).build() && basis != current {
+                let timestamp = self.get_timestamp();
+                let command = Command { timestamp, details: CommandDetails::SetEstimateBasis { basis } };
+                match self.project.invoke_command(command, timestamp.date_naive()) {
+                    Ok(()) => gui_log!(self, "Scheduling tasks by their {} estimate", basis.name().to_lowercase()),
+                    Err(e) => gui_notify!(self, "Failed to change the estimate basis: {e}"),
+                }
+            }
+        }
+    }
+
+    /// The optional best and worst case inputs of the task forms, below the duration.
+    pub(super) fn draw_task_estimate_inputs(&mut self, ui: &Ui) {
+        ui.checkbox("Range##task_estimate", &mut self.task_estimate_enabled);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Give the best and worst case durations, the duration above being the most likely");
+        }
+        if !self.task_estimate_enabled {
+            return;
+        }
+        ui.input_float("##optimistic_input", &mut self.task_optimistic_days)
+            .display_format("%.2f days at best")
+            .step(1.0)
+            .build();
+        ui.input_float("##pessimistic_input", &mut self.task_pessimistic_days)
+            .display_format("%.2f days at worst")
+            .step(1.0)
+            .build();
+    }
+
+    pub(super) fn task_duration_input(&self) -> TaskDuration {
+        days_to_duration(self.task_duration_days)
+    }
+
+    pub(super) fn task_estimate_input(&self) -> Option<ThreePointEstimate> {
+        self.task_estimate_enabled.then(|| ThreePointEstimate {
+            optimistic: days_to_duration(self.task_optimistic_days),
+            pessimistic: days_to_duration(self.task_pessimistic_days),
+        })
+    }
+}
+
+fn days_to_duration(days: f32) -> TaskDuration {
+    TaskDuration {
+        days: days as u64,
+        fraction: (days.fract() * 100.0) as u8,
+    }
+}
@@ -0,0 +1,197 @@
+use crate::gui::*;
+use crate::gui_log;
+use crate::gui_notify;
+use std::collections::HashMap;
+
+impl Gui {
+    pub(super) fn draw_forecast_tab(&mut self, ui: &Ui) {
+        if !self.show_forecast {
+            return;
+        }
+        self.poll_forecast_job();
+        let mut open = true;
+        if let Some(_tab_token) = TabItem::new("Forecast")
+            .opened(&mut open)
+            .begin(ui)
+        {
+            self.draw_forecast_options(ui);
+            if let Some(forecast) = self.forecast.clone() {
+                self.draw_forecast_chart(ui);
+                self.draw_forecast_milestones(ui, &forecast);
+                self.draw_forecast_tasks(ui, &forecast);
+            }
+        }
+        if !open {
+            self.show_forecast = false;
+            self.forecast = None;
+            self.forecast_chart = None;
+            if let Some(job) = self.forecast_job.take() {
+                job.cancel();
+            }
+        }
+    }
+
+    fn start_forecast(&mut self) {
+        let flow_state = self.project.flow_state().clone();
+        let commands = self.project.applied_commands();
+        let options = self.forecast_options;
+        let date = self.get_timestamp().date_naive();
+        self.forecast_job = Some(BackgroundJob::spawn("forecast", move |control| {
+            Ok(run_forecast(&flow_state, &commands, &options, date, control))
+        }));
+    }
+
+    fn poll_forecast_job(&mut self) {
+        let Some(job) = &self.forecast_job else {
+            return;
+        };
+        match job.poll() {
+            BackgroundJobStatus::Running => return,
+            BackgroundJobStatus::Finished(forecast) => {
+                gui_log!(self, "Forecasted {} tasks over {} simulations", forecast.tasks.len(), forecast.iterations);
+                self.forecast_chart = forecast.milestones.iter()
+                    .find_map(|milestone| Some((milestone.title.clone(), milestone.distribution.clone()?)));
+                self.forecast = Some(forecast);
+            }
+            BackgroundJobStatus::Cancelled => {
+                gui_log!(self, "Cancelled forecast");
+            }
+            BackgroundJobStatus::Failed(e) => {
+                gui_notify!(self, "Failed to forecast: {e}");
+            }
+        }
+        self.forecast_job = None;
+    }
+
+    fn draw_forecast_options(&mut self, ui: &Ui) {
+        let mut iterations = self.forecast_options.iterations as i32;
+        ui.set_next_item_width(FORECAST_INPUT_WIDTH);
+        if ui.input_int("Simulations", &mut iterations).step(100).build() {
+            self.forecast_options.iterations = iterations.clamp(1, FORECAST_MAX_ITERATIONS) as usize;
+        }
+        ui.same_line();
+        let mut optimistic_factor = self.forecast_options.optimistic_factor as f32;
+        ui.set_next_item_width(FORECAST_INPUT_WIDTH);
+        if ui.input_float("Optimistic", &mut optimistic_factor).display_format("%.2f").build() {
+            self.forecast_options.optimistic_factor = optimistic_factor.clamp(0.0, 1.0) as f64;
+        }
+        ui.same_line();
+        let mut pessimistic_factor = self.forecast_options.pessimistic_factor as f32;
+        ui.set_next_item_width(FORECAST_INPUT_WIDTH);
+        if ui.input_float("Pessimistic", &mut pessimistic_factor).display_format("%.2f").build() {
+            self.forecast_options.pessimistic_factor = pessimistic_factor.max(1.0) as f64;
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Remaining work ranges from the optimistic to the pessimistic factor of the estimate, most likely as estimated");
+        }
+        ui.same_line();
+        if let Some(job) = &self.forecast_job {
+            ProgressBar::new(job.progress())
+                .overlay_text(format!("{:.0}%", job.progress() * 100.0))
+                .size(INSPECTION_PROGRESS_BAR_SIZE)
+                .build(ui);
+            ui.same_line();
+            if ui.button("Cancel") {
+                job.cancel();
+            }
+        } else if ui.button("Run") {
+            self.start_forecast();
+        }
+        if let Some(forecast) = &self.forecast {
+            let ratios: Vec<String> = forecast.ratios.iter()
+                .filter_map(|(resource_id, ratio)| Some(format!("{} x{:.2}", self.project.flow_state().resources.get(resource_id)?.name, ratio)))
+                .collect();
+            if !ratios.is_empty() {
+                ui.text_disabled(format!("Actual over estimated work: {}", ratios.join(", ")));
+            }
+        }
+    }
+
+    fn draw_forecast_chart(&self, ui: &Ui) {
+        let Some((name, distribution)) = &self.forecast_chart else {
+            ui.text_disabled("Select a milestone or a task to see its distribution.");
+            return;
+        };
+        let (Some((first_date, _)), Some((last_date, _))) = (distribution.histogram.first(), distribution.histogram.last()) else {
+            return;
+        };
+        // One bar per day, so weekends and days no simulation ends on show as gaps.
+        let counts: HashMap<NaiveDate, usize> = distribution.histogram.iter().copied().collect();
+        let values: Vec<f32> = first_date.iter_days()
+            .take_while(|day| day <= last_date)
+            .map(|day| counts.get(&day).copied().unwrap_or(0) as f32)
+            .collect();
+        ui.text(format!("{name}: P50 {}, P80 {}, P95 {}", distribution.p50, distribution.p80, distribution.p95));
+        ui.plot_histogram("##forecast_chart", &values)
+            .scale_min(0.0)
+            .graph_size([ui.content_region_avail()[0], FORECAST_CHART_HEIGHT])
+            .overlay_text(format!("{first_date} - {last_date}"))
+            .build();
+    }
+
+    fn draw_forecast_milestones(&mut self, ui: &Ui, forecast: &Forecast) {
+        ui.separator();
+        if forecast.milestones.is_empty() {
+            ui.text("No milestones.");
+            return;
+        }
+        let flags = TableFlags::BORDERS | TableFlags::ROW_BG | TableFlags::RESIZABLE;
+        let Some(_table) = ui.begin_table_with_flags("##forecast_milestones_table", 7, flags) else {
+            return;
+        };
+        for name in ["Milestone", "Date", "Tasks", "P50", "P80", "P95", "Chance"] {
+            ui.table_setup_column(name);
+        }
+        ui.table_headers_row();
+        for milestone in &forecast.milestones {
+            ui.table_next_row();
+            ui.table_next_column();
+            let is_selected = self.forecast_chart.as_ref().is_some_and(|(name, _)| *name == milestone.title);
+            if ui.selectable_config(&milestone.title).selected(is_selected).span_all_columns(true).build() {
+                self.forecast_chart = milestone.distribution.clone().map(|distribution| (milestone.title.clone(), distribution));
+            }
+            ui.table_next_column();
+            ui.text(milestone.date.to_string());
+            ui.table_next_column();
+            ui.text(milestone.num_tasks.to_string());
+            self.draw_forecast_percentiles(ui, milestone.distribution.as_ref());
+            ui.table_next_column();
+            let color = if milestone.probability < FORECAST_LIKELY_PROBABILITY { SCENARIO_DIFF_WORSE_COLOR } else { SCENARIO_DIFF_BETTER_COLOR };
+            ui.text_colored(color, format!("{:.0}%", milestone.probability * 100.0));
+        }
+    }
+
+    fn draw_forecast_tasks(&mut self, ui: &Ui, forecast: &Forecast) {
+        ui.separator();
+        let flags = TableFlags::BORDERS | TableFlags::ROW_BG | TableFlags::SCROLL_Y | TableFlags::RESIZABLE;
+        let Some(_table) = ui.begin_table_with_flags("##forecast_tasks_table", 6, flags) else {
+            return;
+        };
+        ui.table_setup_scroll_freeze(0, 1);
+        for name in ["Ticket", "Title", "Planned", "P50", "P80", "P95"] {
+            ui.table_setup_column(name);
+        }
+        ui.table_headers_row();
+        for task in &forecast.tasks {
+            ui.table_next_row();
+            ui.table_next_column();
+            let is_selected = self.forecast_chart.as_ref().is_some_and(|(name, _)| *name == task.ticket);
+            if ui.selectable_config(&task.ticket).selected(is_selected).span_all_columns(true).build() {
+                self.forecast_chart = Some((task.ticket.clone(), task.distribution.clone()));
+            }
+            ui.table_next_column();
+            ui.text(&task.title);
+            ui.table_next_column();
+            ui.text(task.planned_end_date.to_string());
+            self.draw_forecast_percentiles(ui, Some(&task.distribution));
+        }
+    }
+
+    fn draw_forecast_percentiles(&self, ui: &Ui, distribution: Option<&DateDistribution>) {
+        let percentiles = distribution.map(|distribution| [distribution.p50, distribution.p80, distribution.p95]);
+        for index in 0..3 {
+            ui.table_next_column();
+            ui.text(percentiles.map(|dates| dates[index].to_string()).unwrap_or_else(|| "-".to_string()));
+        }
+    }
+}
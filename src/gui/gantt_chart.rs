@@ -1,32 +1,122 @@
 use crate::gui::*;
+use crate::gui::utils::GanttNode;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// What the rows of both gantt charts are flattened from.
+#[derive(PartialEq)]
+pub(super) struct GanttRowsKey {
+    revision: u64,
+    filtered_labels: Vec<LabelId>,
+    find: String,
+    collapsed: HashSet<GanttNode>,
+}
+
+/// Flattened rows of a gantt chart, kept until the flow state, the filters or the collapsed
+/// nodes change rather than rebuilt from every task each frame.
+pub(super) struct GanttRows<Row> {
+    key: Option<GanttRowsKey>,
+    rows: Rc<Vec<Row>>,
+}
+
+impl<Row> GanttRows<Row> {
+    pub(super) fn new() -> Self {
+        GanttRows { key: None, rows: Rc::new(Vec::new()) }
+    }
+
+    pub(super) fn is_stale(&self, key: &GanttRowsKey) -> bool {
+        self.key.as_ref() != Some(key)
+    }
+
+    pub(super) fn set(&mut self, key: GanttRowsKey, rows: Vec<Row>) {
+        self.key = Some(key);
+        self.rows = Rc::new(rows);
+    }
+
+    pub(super) fn rows(&self) -> Rc<Vec<Row>> {
+        self.rows.clone()
+    }
+}
 
 impl Gui {
-    pub(super) fn draw_gantt_chart_table(&mut self, _ui: &Ui, id: &str) -> bool {
+    /// Begins a gantt table with a fixed window of day columns, as many as fit in the available
+    /// width. The columns show the days from `first_visible_day` on, which the scrollbar under
+    /// the table moves, so the cost of a frame does not grow with the span of the project.
+    pub(super) fn draw_gantt_chart_table(&mut self, ui: &Ui, id: &str) -> bool {
         let table_id = std::ffi::CString::new(id).unwrap();
         let flags = imgui::sys::ImGuiTableFlags_Borders
             | imgui::sys::ImGuiTableFlags_HighlightHoveredColumn
             | imgui::sys::ImGuiTableFlags_SizingFixedFit
-            | imgui::sys::ImGuiTableFlags_ScrollX
             | imgui::sys::ImGuiTableFlags_ScrollY
             | imgui::sys::ImGuiTableFlags_Resizable
             | imgui::sys::ImGuiTableFlags_NoPadOuterX
             | imgui::sys::ImGuiTableFlags_NoPadInnerX;
-        let num_columns = self.project.flow_state().cache().num_days() + 1;
+        let num_days = self.project.flow_state().cache().num_days();
+        let fitting_days = (ui.content_region_avail()[0] / GANTT_CHART_DAY_COLUMN_WIDTH).ceil() as usize;
+        let window_days = fitting_days.clamp(1, GANTT_CHART_MAX_DAY_COLUMNS).min(num_days.max(1));
+        self.drawing_aids.first_visible_day = self.drawing_aids.first_visible_day.min(num_days.saturating_sub(1));
         unsafe {imgui::sys::igBeginTable(
             table_id.as_ptr(),
-            num_columns as i32,
+            (window_days + 1) as i32,
             flags as i32,
-            imgui::sys::ImVec2 { x: 0.0, y: 0.0 },
+            imgui::sys::ImVec2 { x: 0.0, y: -ui.frame_height_with_spacing() },
             0.0,
         )}
     }
 
+    pub(super) fn gantt_chart_rows_key(&self) -> GanttRowsKey {
+        GanttRowsKey {
+            revision: self.project.flow_state().cache().revision(),
+            filtered_labels: self.filtered_labels.clone(),
+            find: self.find_input_buffer.clone(),
+            collapsed: self.drawing_aids.collapsed_gantt_nodes.clone(),
+        }
+    }
+
+    /// Day shown in the day `column` of a gantt table.
+    pub(super) fn gantt_chart_day(&self, column: usize) -> NaiveDate {
+        self.project.flow_state().cache().day(self.drawing_aids.first_visible_day + column - 1)
+    }
+
+    /// Scrolling horizontally over the table, or with shift held, moves the day columns by a week.
+    fn scroll_gantt_chart_days(&mut self, ui: &Ui) {
+        if !ui.is_window_hovered_with_flags(WindowHoveredFlags::CHILD_WINDOWS) {
+            return;
+        }
+        let io = ui.io();
+        let wheel = if io.key_shift { io.mouse_wheel_h + io.mouse_wheel } else { io.mouse_wheel_h };
+        let days = (wheel * GANTT_CHART_WHEEL_SCROLL_DAYS as f32).round() as i64;
+        let first_day = (self.drawing_aids.first_visible_day as i64 - days).max(0) as usize;
+        self.drawing_aids.first_visible_day = first_day.min(self.gantt_chart_max_first_day());
+    }
+
+    /// Last day that can be shown first, such that the last day of the project fits in the
+    /// visible day columns.
+    fn gantt_chart_max_first_day(&self) -> usize {
+        let num_days = self.project.flow_state().cache().num_days();
+        num_days.saturating_sub(self.drawing_aids.visible_day_columns.len().max(1))
+    }
+
+    /// Draws the scrollbar that moves the window of day columns, right after the table ends.
+    pub(super) fn draw_gantt_chart_day_scrollbar(&mut self, ui: &Ui) {
+        let max_first_day = self.gantt_chart_max_first_day();
+        let mut first_day = self.drawing_aids.first_visible_day.min(max_first_day) as u32;
+        let first_date = self.project.flow_state().cache().day(first_day as usize).format("%Y-%m-%d").to_string();
+        ui.set_next_item_width(-1.0);
+        ui.slider_config("##gantt_chart_first_day", 0, max_first_day as u32)
+            .display_format(&first_date)
+            .build(&mut first_day);
+        self.drawing_aids.first_visible_day = first_day as usize;
+    }
+
     pub(super) fn draw_gantt_chart_calendar_row(&mut self, ui: &Ui) {
+        self.scroll_gantt_chart_days(ui);
         let mut table_column_data = TableColumnSetup::new("Calendar");
         table_column_data.flags = TableColumnFlags::NO_HIDE | TableColumnFlags::NO_REORDER;
         ui.table_setup_column_with(table_column_data);
-        for i in 0..self.project.flow_state().cache().num_days() {
-            let day: chrono::NaiveDate = self.project.flow_state().cache().day(i);
+        let num_columns = unsafe { imgui::sys::igTableGetColumnCount() } as usize;
+        for i in 1..num_columns {
+            let day = self.gantt_chart_day(i);
             let day_str = day.format("%m/%d").to_string();
             let day_cstr = std::ffi::CString::new(day_str).unwrap();
             unsafe {imgui::sys::igTableSetupColumn(
@@ -34,7 +124,7 @@ impl Gui {
                 (imgui::sys::ImGuiTableColumnFlags_AngledHeader
                     | imgui::sys::ImGuiTableColumnFlags_WidthFixed
                     | imgui::sys::ImGuiTableColumnFlags_NoResize) as i32,
-                0.0,
+                GANTT_CHART_DAY_COLUMN_WIDTH,
                 0,
             );}
         }
@@ -43,12 +133,50 @@ impl Gui {
         ui.table_headers_row();
 
        
+        self.update_visible_day_columns();
         let today = self.get_timestamp().date_naive();
-        for i in 0..self.project.flow_state().cache().num_days() {
-            let day: chrono::NaiveDate = self.project.flow_state().cache().day(i);
-            if day == today {
-                let pink = [1.0, 0.75, 0.8, 1.0];
-                ui.table_set_bg_color_with_column(TableBgTarget::CELL_BG, pink, i + 1);
+        let today_column = (today - self.project.flow_state().cache().start_date).num_days() + 1 - self.drawing_aids.first_visible_day as i64;
+        if self.drawing_aids.visible_day_columns.contains(&(today_column.max(0) as usize)) {
+            let pink = [1.0, 0.75, 0.8, 1.0];
+            ui.table_set_bg_color_with_column(TableBgTarget::CELL_BG, pink, today_column as usize);
+        }
+    }
+
+    /// Finds the day columns not clipped by the right edge of the table, which may cut off the
+    /// last columns of the window when the first column is widened.
+    fn update_visible_day_columns(&mut self) {
+        let num_columns = unsafe { imgui::sys::igTableGetColumnCount() } as usize;
+        let is_visible = |column: usize| unsafe {
+            imgui::sys::igTableGetColumnFlags(column as i32) & imgui::sys::ImGuiTableColumnFlags_IsVisible as i32 != 0
+        };
+        let end = (1..num_columns).find(|&column| !is_visible(column)).unwrap_or(num_columns);
+        self.drawing_aids.visible_day_columns = 1..end;
+    }
+
+    /// Draws the label of a gantt row indented to its depth in the tree. Rows are drawn flat
+    /// through a list clipper, so the label never pushes onto the ID stack, and the open state
+    /// of a collapsible `node` is kept in the drawing aids while the row is scrolled out of view.
+    pub(super) fn draw_gantt_chart_row_label(&mut self, label: &str, depth: usize, node: Option<GanttNode>, flags: ImGuiTreeNodeFlags_) {
+        let indent = depth as f32 * unsafe { (*igGetStyle()).IndentSpacing };
+        let label_cstr = std::ffi::CString::new(label).unwrap();
+        let flags = flags | ImGuiTreeNodeFlags_SpanFullWidth | ImGuiTreeNodeFlags_NoTreePushOnOpen;
+        unsafe {
+            if indent > 0.0 {
+                igIndent(indent);
+            }
+            if let Some(node) = node {
+                let collapsed = self.drawing_aids.collapsed_gantt_nodes.contains(&node);
+                igSetNextItemOpen(!collapsed, ImGuiCond_Always as i32);
+                if igTreeNodeEx_Str(label_cstr.as_ptr(), flags as i32) {
+                    self.drawing_aids.collapsed_gantt_nodes.remove(&node);
+                } else {
+                    self.drawing_aids.collapsed_gantt_nodes.insert(node);
+                }
+            } else {
+                igTreeNodeEx_Str(label_cstr.as_ptr(), flags as i32);
+            }
+            if indent > 0.0 {
+                igUnindent(indent);
             }
         }
     }
@@ -57,10 +185,10 @@ impl Gui {
         ui.table_next_row();
         ui.table_next_column();
         ui.text("  Milestones");
-        for i in 1..=self.project.flow_state().cache().num_days() {
-            if ui.table_next_column() {
-                let _id = ui.push_id_usize(i);
-                let day = self.project.flow_state().cache().day(i - 1);
+        for i in self.drawing_aids.visible_day_columns.clone() {
+            if ui.table_set_column_index(i) {
+                let _id = ui.push_id_usize(self.drawing_aids.first_visible_day + i);
+                let day = self.gantt_chart_day(i);
                 if let Some(milestones) = self.project.flow_state().cache().date_to_milestones.get(&day) {
                     for milestone in milestones {
                         let cursor_pos = ui.cursor_screen_pos();
@@ -79,8 +207,8 @@ impl Gui {
             }
         }
         ui.table_next_row();
-        for _i in 1..=self.project.flow_state().cache().num_days() {
-            if ui.table_next_column() {
+        for i in self.drawing_aids.visible_day_columns.clone() {
+            if ui.table_set_column_index(i) {
                 let bg_color = ui.style_color(StyleColor::TableHeaderBg);
                 ui.table_set_bg_color(TableBgTarget::CELL_BG, bg_color);
             }
@@ -1,10 +1,20 @@
 use crate::gui::*;
-use crate::gui_log;
+use crate::gui_notify;
+use crate::gui::utils::GanttNode;
 
 /// Drag-and-drop payload name for moving tasks between resource rows in the resources Gantt.
 /// ImGui limits this string to fewer than 32 bytes including the trailing NUL.
 const GANTT_RESOURCES_TASK_DRAG: &str = "FS_GANTT_RES_TASK";
 
+pub(super) enum ResourcesGanttRow {
+    Team { team_id: TeamId },
+    Resource { team_id: TeamId, resource_id: ResourceId, row_counter: usize },
+    AssignedTask { team_id: TeamId, resource_id: ResourceId, task_id: TaskId, row_counter: usize },
+    WatchedTask { team_id: TeamId, resource_id: ResourceId, task_id: TaskId, row_counter: usize },
+    Unassigned,
+    UnassignedTask { task_id: TaskId, row_counter: usize },
+}
+
 impl Gui {
     /// Drag `dragged_task_id` onto the row for `drop_target_task_id` under `target_resource`:
     /// same resource → priority change only; from unassigned → assign (+ priority on target);
@@ -21,12 +31,12 @@ impl Gui {
         let date = timestamp.date_naive();
         let flow_state = self.project.flow_state();
         let Some(target_resource) = flow_state.resources.get(target_resource_id) else {
-            gui_log!(self, "Drag-and-drop: target resource not found");
+            gui_notify!(self, "Drag-and-drop: target resource not found");
             return;
         };
         let assigned_tasks_of_target_resource = &target_resource.assigned_tasks;
         let Some(drop_idx) = assigned_tasks_of_target_resource.iter().position(|&id| id == drop_target_task_id) else {
-            gui_log!(self, "Drag-and-drop: drop target task is not on the target resource");
+            gui_notify!(self, "Drag-and-drop: drop target task is not on the target resource");
             return;
         };
         let source_resource = flow_state
@@ -36,7 +46,7 @@ impl Gui {
 
         if source_resource == Some(*target_resource_id) {
             let Some(from_idx) = assigned_tasks_of_target_resource.iter().position(|&id| id == dragged_task_id) else {
-                gui_log!(self, "Drag-and-drop: dragged task is not on the target resource");
+                gui_notify!(self, "Drag-and-drop: dragged task is not on the target resource");
                 return;
             };
             let insert_to = if from_idx < drop_idx {
@@ -60,7 +70,7 @@ impl Gui {
                     date,
                 )
                 .unwrap_or_else(|e| {
-                    gui_log!(self, "Failed to reorder task via drag-drop: {e}");
+                    gui_notify!(self, "Failed to reorder task via drag-drop: {e}");
                 });
         } else if source_resource.is_none() {
             if drop_idx == 0 {
@@ -70,13 +80,13 @@ impl Gui {
                             timestamp,
                             details: CommandDetails::AssignTask {
                                 task_id: dragged_task_id,
-                                resource_name: target_resource.name.clone(),
+                                resource_id: *target_resource_id,
                             },
                         },
                         date,
                     )
                     .unwrap_or_else(|e| {
-                        gui_log!(self, "Failed to assign task via drag-drop: {e}");
+                        gui_notify!(self, "Failed to assign task via drag-drop: {e}");
                     });
             } else {
                 let commands = vec![
@@ -84,7 +94,7 @@ impl Gui {
                         timestamp,
                         details: CommandDetails::AssignTask {
                             task_id: dragged_task_id,
-                            resource_name: target_resource.name.clone(),
+                            resource_id: *target_resource_id,
                         },
                     },
                     Command {
@@ -104,7 +114,7 @@ impl Gui {
                         date,
                     )
                     .unwrap_or_else(|e| {
-                        gui_log!(self, "Failed to assign and prioritize task via drag-drop: {e}");
+                        gui_notify!(self, "Failed to assign and prioritize task via drag-drop: {e}");
                     });
             }
         } else {
@@ -120,7 +130,7 @@ impl Gui {
                     timestamp,
                     details: CommandDetails::AssignTask {
                         task_id: dragged_task_id,
-                        resource_name: target_resource.name.clone(),
+                        resource_id: *target_resource_id,
                     },
                 },
                 Command {
@@ -140,24 +150,21 @@ impl Gui {
                     date,
                 )
                 .unwrap_or_else(|e| {
-                    gui_log!(self, "Failed to move task across resources via drag-drop: {e}");
+                    gui_notify!(self, "Failed to move task across resources via drag-drop: {e}");
                 });
         }
     }
 
     /// "Assign to" from the resources Gantt: when the task already has an assignee, run
     /// `PrioritizeTask` then `AssignTask` so undo restores the source list order.
-    fn gantt_resources_assign_task_from_menu(&mut self, task_id: TaskId, resource_name: ResourceName) {
+    fn gantt_resources_assign_task_from_menu(&mut self, task_id: TaskId, resource_id: ResourceId) {
         let timestamp = self.get_timestamp();
         let date = timestamp.date_naive();
         let fs = self.project.flow_state();
         let Some(task) = fs.tasks.get(&task_id) else {
             return;
         };
-        let already_on_this_resource = task
-            .assignee
-            .and_then(|rid| fs.resources.get(&rid).map(|r| r.name.clone()));
-        if already_on_this_resource.as_ref() == Some(&resource_name) {
+        if task.assignee == Some(resource_id) {
             return;
         }
 
@@ -174,7 +181,7 @@ impl Gui {
                     timestamp,
                     details: CommandDetails::AssignTask {
                         task_id,
-                        resource_name,
+                        resource_id,
                     },
                 },
             ];
@@ -187,7 +194,7 @@ impl Gui {
                     date,
                 )
                 .unwrap_or_else(|e| {
-                    gui_log!(self, "Failed to assign task to resource: {e}");
+                    gui_notify!(self, "Failed to assign task to resource: {e}");
                 });
         } else {
             self.project
@@ -196,13 +203,13 @@ impl Gui {
                         timestamp,
                         details: CommandDetails::AssignTask {
                             task_id,
-                            resource_name,
+                            resource_id,
                         },
                     },
                     date,
                 )
                 .unwrap_or_else(|e| {
-                    gui_log!(self, "Failed to assign task to resource: {e}");
+                    gui_notify!(self, "Failed to assign task to resource: {e}");
                 });
         }
     }
@@ -213,62 +220,134 @@ impl Gui {
             self.draw_gantt_chart_milestones_row(ui);
             self.draw_gantt_chart_resources_contents(ui);
             unsafe {imgui::sys::igEndTable();}
+            self.draw_gantt_chart_day_scrollbar(ui);
         }
     }
 
     fn draw_gantt_chart_resources_contents(&mut self, ui: &Ui) {
-        let team_ids: Vec<TeamId> = self.project.flow_state().teams.iter()
-            .map(|team| team.0.clone()).collect();
-        for team_id in team_ids.iter(){
-            self.draw_gantt_chart_resources_team(ui, team_id);
+        let key = self.gantt_chart_rows_key();
+        if self.drawing_aids.resources_gantt_rows.is_stale(&key) {
+            let rows = self.gantt_chart_resources_rows();
+            self.drawing_aids.resources_gantt_rows.set(key, rows);
+        }
+        let rows = self.drawing_aids.resources_gantt_rows.rows();
+        let mut clipper = ListClipper::new(rows.len() as i32).begin(ui);
+        while clipper.step() {
+            for row in clipper.display_start()..clipper.display_end() {
+                match &rows[row as usize] {
+                    ResourcesGanttRow::Team { team_id } => {
+                        self.draw_gantt_chart_resources_team(ui, team_id);
+                    }
+                    ResourcesGanttRow::Resource { team_id, resource_id, row_counter } => {
+                        self.drawing_aids.row_counter = *row_counter;
+                        self.draw_gantt_chart_resources_team_resource(ui, team_id, resource_id);
+                    }
+                    ResourcesGanttRow::AssignedTask { team_id, resource_id, task_id, row_counter } => {
+                        self.drawing_aids.row_counter = *row_counter;
+                        let resource = self.project.flow_state().resources[resource_id].clone();
+                        self.draw_gantt_chart_resources_team_resource_task(ui, team_id, resource_id, &resource, task_id);
+                    }
+                    ResourcesGanttRow::WatchedTask { team_id, resource_id, task_id, row_counter } => {
+                        self.drawing_aids.row_counter = *row_counter;
+                        let resource = self.project.flow_state().resources[resource_id].clone();
+                        self.draw_gantt_chart_resources_team_resource_task_as_watcher(ui, team_id, resource_id, &resource, task_id);
+                    }
+                    ResourcesGanttRow::Unassigned => {
+                        self.draw_gantt_chart_resources_team_unassigned(ui);
+                    }
+                    ResourcesGanttRow::UnassignedTask { task_id, row_counter } => {
+                        self.drawing_aids.row_counter = *row_counter;
+                        self.draw_gantt_chart_resources_team_unassigned_task(ui, task_id);
+                    }
+                }
+            }
         }
-        self.draw_gantt_chart_resources_team_unassigned(ui);
+    }
+
+    /// Rows of the teams, each followed by its resources and their tasks that pass the filters
+    /// as far as expanded, and then the unassigned tasks.
+    fn gantt_chart_resources_rows(&self) -> Vec<ResourcesGanttRow> {
+        let flow_state = self.project.flow_state();
+        let collapsed = &self.drawing_aids.collapsed_gantt_nodes;
+        let should_show = |task_id: &TaskId| {
+            let task = &flow_state.tasks[task_id];
+            (
+                self.filtered_labels.is_empty()
+                    || self.filtered_labels.iter().all(|label_id| task.label_ids.contains(label_id))
+            ) && (
+                self.find_input_buffer.is_empty()
+                    || task.title.contains(&self.find_input_buffer)
+                    || task.ticket.contains(&self.find_input_buffer)
+            )
+        };
+        let mut rows = Vec::new();
+        for (team_id, team) in flow_state.teams.iter() {
+            rows.push(ResourcesGanttRow::Team { team_id: *team_id });
+            if collapsed.contains(&GanttNode::Team(*team_id)) {
+                continue;
+            }
+            let mut resources: Vec<ResourceId> = team.resources.iter().cloned().collect();
+            resources.sort_by_key(|r| flow_state.resources[r].name.clone());
+            for (i, resource_id) in resources.into_iter().enumerate() {
+                rows.push(ResourcesGanttRow::Resource { team_id: *team_id, resource_id, row_counter: i });
+                if collapsed.contains(&GanttNode::Resource(resource_id)) {
+                    continue;
+                }
+                let resource = &flow_state.resources[&resource_id];
+                for task_id in resource.assigned_tasks.iter().filter(|task_id| should_show(task_id)) {
+                    rows.push(ResourcesGanttRow::AssignedTask { team_id: *team_id, resource_id, task_id: *task_id, row_counter: i });
+                }
+                for task_id in resource.watched_tasks.iter().filter(|task_id| should_show(task_id)) {
+                    rows.push(ResourcesGanttRow::WatchedTask { team_id: *team_id, resource_id, task_id: *task_id, row_counter: i });
+                }
+            }
+        }
+        rows.push(ResourcesGanttRow::Unassigned);
+        if !collapsed.contains(&GanttNode::Unassigned) {
+            for (i, task_id) in flow_state.cache().unassigned_tasks.iter().enumerate() {
+                let task = &flow_state.tasks[task_id];
+                if self.filtered_labels.is_empty() || self.filtered_labels.iter().all(|label_id| task.label_ids.contains(label_id)) {
+                    rows.push(ResourcesGanttRow::UnassignedTask { task_id: *task_id, row_counter: i });
+                }
+            }
+        }
+        rows
     }
 
     fn draw_gantt_chart_resources_team(&mut self, ui: &Ui, team_id: &TeamId) {
         ui.table_next_row();
         ui.table_next_column();
+        let day_columns = self.drawing_aids.visible_day_columns.clone();
 
+        let _team_token_id = ui.push_id_int(*team_id as i32);
         let team = self.project.flow_state().teams.get(team_id).unwrap().clone();
-        let team_name_cstr = std::ffi::CString::new(team.name.clone()).unwrap();
-        let flags = imgui::sys::ImGuiTreeNodeFlags_SpanFullWidth | imgui::sys::ImGuiTreeNodeFlags_DefaultOpen;
-        let expand_team = unsafe {
+        {
             let bold = self.bold_font.borrow().unwrap();
             let _h = ui.push_font(bold);
             let bg_color = ui.style_color(StyleColor::TableHeaderBg);
             ui.table_set_bg_color(TableBgTarget::CELL_BG, bg_color);
-            imgui::sys::igTreeNodeEx_Str(team_name_cstr.as_ptr(), flags as i32)
-        };
+            self.draw_gantt_chart_row_label(&team.name, 0, Some(GanttNode::Team(*team_id)), 0);
+        }
         self.draw_gantt_chart_resources_team_popup(ui, team_id, &team);
 
-        for i in 1..=self.project.flow_state().cache().num_days() {
-            if ui.table_next_column() {
+        for i in day_columns {
+            if ui.table_set_column_index(i) {
                 let bg_color = ui.style_color(StyleColor::TableHeaderBg);
                 ui.table_set_bg_color(TableBgTarget::CELL_BG, bg_color);
-                let day = self.project.flow_state().cache().day(i - 1);
+                let day = self.gantt_chart_day(i);
                 self.draw_milestone(ui, &day);
             }
         }
-
-        if expand_team {
-            let mut resources: Vec<ResourceId> = self.project.flow_state().teams[team_id]
-                .resources.iter().map(|r| r.clone()).collect();
-            resources.sort_by_key(|r| self.project.flow_state().resources[r].name.clone());
-            for (i, resource_id) in resources.iter().enumerate() {
-                self.drawing_aids.row_counter = i;
-                self.draw_gantt_chart_resources_team_resource(ui, resource_id);
-            }
-            unsafe {imgui::sys::igTreePop();}
-        }
     }
 
-    fn draw_gantt_chart_resources_team_resource(&mut self, ui: &Ui, resource_id: &ResourceId) {
+    fn draw_gantt_chart_resources_team_resource(&mut self, ui: &Ui, team_id: &TeamId, resource_id: &ResourceId) {
         ui.table_next_row();
         ui.table_next_column();
+        let day_columns = self.drawing_aids.visible_day_columns.clone();
+        let _team_token_id = ui.push_id_int(*team_id as i32);
+        let _resource_token_id = ui.push_id_int(*resource_id as i32);
         let resource = self.project.flow_state().resources.get(resource_id).unwrap().clone();
-        let resource_name_cstr = std::ffi::CString::new(resource.name.clone()).unwrap();
-        let flags = imgui::sys::ImGuiTreeNodeFlags_SpanFullWidth | imgui::sys::ImGuiTreeNodeFlags_DefaultOpen;
-        let expand_resource = unsafe {
+        {
             let bold = self.bold_font.borrow().unwrap();
             let _h = ui.push_font(bold);
             let bg_color = if self.drawing_aids.row_counter % 2 == 0 {
@@ -277,14 +356,14 @@ impl Gui {
                 ui.style_color(StyleColor::TableRowBgAlt)
             };
             ui.table_set_bg_color(TableBgTarget::CELL_BG, bg_color);
-            imgui::sys::igTreeNodeEx_Str(resource_name_cstr.as_ptr(), flags as i32)
-        };
+            self.draw_gantt_chart_row_label(&resource.name, 1, Some(GanttNode::Resource(*resource_id)), 0);
+        }
         self.draw_gantt_chart_resources_team_resource_popup(ui, resource_id, &resource);
 
-        for i in 1..=self.project.flow_state().cache().num_days() {
-            if ui.table_next_column() {
-                let _id = ui.push_id_usize(i);
-                let day = self.project.flow_state().cache().day(i - 1);
+        for i in day_columns {
+            if ui.table_set_column_index(i) {
+                let _id = ui.push_id_usize(self.drawing_aids.first_visible_day + i);
+                let day = self.gantt_chart_day(i);
                 self.draw_cell_background(ui, &day);
                 self.draw_absence(ui, &day, resource_id, &resource);
                 if !self.gui_config.hide_worklogs {
@@ -295,55 +374,23 @@ impl Gui {
                 self.draw_gantt_chart_resources_team_resource_content_popup(ui, resource_id, &resource, &day);
             }
         }
-        if expand_resource {
-            for task_id in resource.assigned_tasks.iter() {
-                let task = self.project.flow_state().tasks.get(task_id).unwrap().clone();
-                let should_show = (
-                    self.filtered_labels.is_empty()
-                        || self.filtered_labels.iter().all(|label_id| task.label_ids.contains(label_id))
-                ) && (
-                    self.find_input_buffer.is_empty()
-                        || task.title.contains(&self.find_input_buffer)
-                        || task.ticket.contains(&self.find_input_buffer)
-                );
-                if should_show {
-                    self.draw_gantt_chart_resources_team_resource_task(ui, resource_id, &resource, task_id);
-                }
-            }
-            for task_id in resource.watched_tasks.iter() {
-                let task = self.project.flow_state().tasks.get(task_id).unwrap().clone();
-                let should_show = (
-                    self.filtered_labels.is_empty()
-                        || self.filtered_labels.iter().all(|label_id| task.label_ids.contains(label_id))
-                ) && (
-                    self.find_input_buffer.is_empty()
-                        || task.title.contains(&self.find_input_buffer)
-                        || task.ticket.contains(&self.find_input_buffer)
-                );
-                if should_show {
-                    self.draw_gantt_chart_resources_team_resource_task_as_watcher(ui, resource_id, &resource, task_id);
-                }
-            }
-            unsafe {imgui::sys::igTreePop();}
-        }
     }
 
-    fn draw_gantt_chart_resources_team_resource_task(&mut self, ui: &Ui, resource_id: &ResourceId, resource: &Resource, task_id: &TaskId) {
+    fn draw_gantt_chart_resources_team_resource_task(&mut self, ui: &Ui, team_id: &TeamId, resource_id: &ResourceId, resource: &Resource, task_id: &TaskId) {
         ui.table_next_row();
         ui.table_next_column();
+        let day_columns = self.drawing_aids.visible_day_columns.clone();
+        let _team_token_id = ui.push_id_int(*team_id as i32);
+        let _resource_token_id = ui.push_id_int(*resource_id as i32);
         let _task_token_id = ui.push_id_int(*task_id as i32);
         let task = self.project.flow_state().tasks.get(task_id).unwrap().clone();
-        let task_title_cstr = std::ffi::CString::new(format!("{} - {}", task.ticket, task.title)).unwrap();
-        let flags = imgui::sys::ImGuiTreeNodeFlags_SpanFullWidth | imgui::sys::ImGuiTreeNodeFlags_Bullet;
-        let expand_task = unsafe {
-            let bg_color = if self.drawing_aids.row_counter % 2 == 0 {
-                ui.style_color(StyleColor::TableRowBg)
-            } else {
-                ui.style_color(StyleColor::TableRowBgAlt)
-            };
-            ui.table_set_bg_color(TableBgTarget::CELL_BG, bg_color);
-            imgui::sys::igTreeNodeEx_Str(task_title_cstr.as_ptr(), flags as i32)
+        let bg_color = if self.drawing_aids.row_counter % 2 == 0 {
+            ui.style_color(StyleColor::TableRowBg)
+        } else {
+            ui.style_color(StyleColor::TableRowBgAlt)
         };
+        ui.table_set_bg_color(TableBgTarget::CELL_BG, bg_color);
+        self.draw_gantt_chart_row_label(&format!("{} - {}", task.ticket, task.title), 2, None, ImGuiTreeNodeFlags_Bullet);
 
         if let Some(_tooltip) = ui.drag_drop_source_config(GANTT_RESOURCES_TASK_DRAG).begin_payload(*task_id) {}
 
@@ -370,11 +417,12 @@ impl Gui {
         }
         self.draw_gantt_chart_resources_team_resource_task_popup(ui, task_id, &task);
 
+        let spread_days = self.project.flow_state().cache().task_spread_days(self.project.flow_state(), *task_id);
         self.drawing_aids.previous_rect = None;
-        for i in 1..=self.project.flow_state().cache().num_days() {
-            if ui.table_next_column() {
-                let _day_token_id = ui.push_id_usize(i);
-                let day = self.project.flow_state().cache().day(i - 1);
+        for i in day_columns {
+            if ui.table_set_column_index(i) {
+                let _day_token_id = ui.push_id_usize(self.drawing_aids.first_visible_day + i);
+                let day = self.gantt_chart_day(i);
                 self.draw_cell_background(ui, &day);
                 if !self.gui_config.hide_worklogs {
                     self.draw_worklog(ui, &day, resource_id, resource, task_id, &task);
@@ -389,6 +437,9 @@ impl Gui {
                     self.project.flow_state().cache().task_alloc_rendering.get(task_id)
                         .and_then(|r| r.get(&day)).copied();
                 self.draw_alloc(ui, worklog.clone(), alloc);
+                if spread_days.contains(&day) {
+                    self.draw_spread(ui);
+                }
                 self.draw_milestone(ui, &day);
                 ui.invisible_button("##invisible_button", [-1.0, unsafe { igGetTextLineHeight() }]);
                 self.draw_gantt_chart_resources_team_resource_task_content_popup(ui, resource_id, &resource, task_id, &task, &day);
@@ -409,34 +460,32 @@ impl Gui {
             }
         }
 
-        if expand_task {
-            unsafe {imgui::sys::igTreePop();}
-        }
     }
 
-    fn draw_gantt_chart_resources_team_resource_task_as_watcher(&mut self, ui: &Ui, resource_id: &ResourceId, resource: &Resource, task_id: &TaskId) {
+    fn draw_gantt_chart_resources_team_resource_task_as_watcher(&mut self, ui: &Ui, team_id: &TeamId, resource_id: &ResourceId, resource: &Resource, task_id: &TaskId) {
         ui.table_next_row();
         ui.table_next_column();
+        let day_columns = self.drawing_aids.visible_day_columns.clone();
+        let _team_token_id = ui.push_id_int(*team_id as i32);
+        let _resource_token_id = ui.push_id_int(*resource_id as i32);
         let _watcher_token_id = ui.push_id("##as_watcher");
         let _task_token_id = ui.push_id_int(*task_id as i32);
         let task = self.project.flow_state().tasks.get(task_id).unwrap().clone();
-            let task_title_cstr = std::ffi::CString::new(format!("{} - {}", task.ticket, task.title)).unwrap();
-        let flags = imgui::sys::ImGuiTreeNodeFlags_SpanFullWidth | imgui::sys::ImGuiTreeNodeFlags_Bullet;
-        let expand_task = unsafe {
+        {
             let disabled_color = ui.style_color(StyleColor::TextDisabled);
             let _style = ui.push_style_color(imgui::StyleColor::Text, disabled_color);
-            imgui::sys::igTreeNodeEx_Str(task_title_cstr.as_ptr(), flags as i32)
-        };
+            self.draw_gantt_chart_row_label(&format!("{} - {}", task.ticket, task.title), 2, None, ImGuiTreeNodeFlags_Bullet);
+        }
         if ui.is_item_hovered() && ui.is_mouse_clicked(MouseButton::Middle) {
             self.open_task_in_jira(ui, &task);
         }
         self.draw_gantt_chart_resources_team_resource_task_as_watcher_popup(ui, task_id, &task, resource_id, resource);
 
         self.drawing_aids.previous_rect = None;
-        for i in 1..=self.project.flow_state().cache().num_days() {
-            if ui.table_next_column() {
-                let _day_token_id = ui.push_id_usize(i);
-                let day = self.project.flow_state().cache().day(i - 1);
+        for i in day_columns {
+            if ui.table_set_column_index(i) {
+                let _day_token_id = ui.push_id_usize(self.drawing_aids.first_visible_day + i);
+                let day = self.gantt_chart_day(i);
                 self.draw_cell_background(ui, &day);
                 if !self.gui_config.hide_worklogs {
                     self.draw_worklog(ui, &day, resource_id, resource, task_id, &task);
@@ -448,84 +497,70 @@ impl Gui {
             }
         }
 
-        if expand_task {
-            unsafe {imgui::sys::igTreePop();}
-        }
     }
 
     fn draw_gantt_chart_resources_team_unassigned(&mut self, ui: &Ui) {
         ui.table_next_row();
         ui.table_next_column();
-        let name_cstr = std::ffi::CString::new("Unassigned").unwrap();
-        let flags = imgui::sys::ImGuiTreeNodeFlags_SpanFullWidth | imgui::sys::ImGuiTreeNodeFlags_DefaultOpen;
-        let expand_unassigned = unsafe {
+        let day_columns = self.drawing_aids.visible_day_columns.clone();
+        {
             let bold = self.bold_font.borrow().unwrap();
             let _h = ui.push_font(bold);
             let bg_color = ui.style_color(StyleColor::TableHeaderBg);
             ui.table_set_bg_color(TableBgTarget::CELL_BG, bg_color);
-            imgui::sys::igTreeNodeEx_Str(name_cstr.as_ptr(), flags as i32)
-        };
+            self.draw_gantt_chart_row_label("Unassigned", 0, Some(GanttNode::Unassigned), 0);
+        }
         self.draw_gantt_chart_resources_team_unassigned_popup(ui);
         
-        for i in 1..=self.project.flow_state().cache().num_days() {
-            if ui.table_next_column() {
+        for i in day_columns {
+            if ui.table_set_column_index(i) {
                 let bg_color = ui.style_color(StyleColor::TableHeaderBg);
                 ui.table_set_bg_color(TableBgTarget::CELL_BG, bg_color);
-                let day = self.project.flow_state().cache().day(i - 1);
+                let day = self.gantt_chart_day(i);
                 self.draw_milestone(ui, &day);
             }
         }
 
-        if expand_unassigned {
-            for task_id in self.project.flow_state().cache().unassigned_tasks.clone().iter() {
-                let task = self.project.flow_state().tasks.get(task_id).unwrap().clone();
-                if self.filtered_labels.is_empty() || self.filtered_labels.iter().all(|label_id| task.label_ids.contains(label_id)) {
-                    self.draw_gantt_chart_resources_team_unassigned_task(ui, task_id);
-                }
-            }
-
-            unsafe {imgui::sys::igTreePop();}
-        }
     }
 
     fn draw_gantt_chart_resources_team_unassigned_task(&mut self, ui: &Ui, task_id: &TaskId) {
         ui.table_next_row();
         ui.table_next_column();
+        let day_columns = self.drawing_aids.visible_day_columns.clone();
+        let _unassigned_token_id = ui.push_id("##unassigned");
         let _task_token_id = ui.push_id_int(*task_id as i32);
         let task = self.project.flow_state().tasks.get(task_id).unwrap().clone();
-        let task_title_cstr = std::ffi::CString::new(format!("{} - {}", task.ticket, task.title)).unwrap();
-        let flags = imgui::sys::ImGuiTreeNodeFlags_SpanFullWidth | imgui::sys::ImGuiTreeNodeFlags_Bullet;
-        let expand_task = unsafe {
-            imgui::sys::igTreeNodeEx_Str(task_title_cstr.as_ptr(), flags as i32)
-        };
+        self.draw_gantt_chart_row_label(&format!("{} - {}", task.ticket, task.title), 1, None, ImGuiTreeNodeFlags_Bullet);
         if ui.is_item_hovered() && ui.is_mouse_clicked(MouseButton::Middle) {
             self.open_task_in_jira(ui, &task);
         }
         self.draw_gantt_chart_resources_team_unassigned_task_popup(ui, task_id, &task);
 
+        let spread_days = self.project.flow_state().cache().task_spread_days(self.project.flow_state(), *task_id);
         self.drawing_aids.previous_rect = None;
-        for i in 1..=self.project.flow_state().cache().num_days() {
-            if ui.table_next_column() {
-                let _day_token_id = ui.push_id_usize(i);
-                let day = self.project.flow_state().cache().day(i - 1);
+        for i in day_columns {
+            if ui.table_set_column_index(i) {
+                let _day_token_id = ui.push_id_usize(self.drawing_aids.first_visible_day + i);
+                let day = self.gantt_chart_day(i);
                 self.draw_cell_background(ui, &day);
             let alloc = 
                 self.project.flow_state().cache().task_alloc_rendering.get(task_id)
                     .and_then(|r| r.get(&day)).copied();
 
                 self.draw_alloc(ui, None, alloc);
+                if spread_days.contains(&day) {
+                    self.draw_spread(ui);
+                }
                 self.draw_milestone(ui, &day);
                 ui.invisible_button("##invisible_button", [-1.0, unsafe { igGetTextLineHeight() }]);
                 self.draw_gantt_chart_resources_team_unassigned_task_content_popup(ui, task_id, &task, &day);
             }
         }
-        if expand_task {
-            unsafe {imgui::sys::igTreePop();}
-        }
     }
 
-    fn draw_gantt_chart_resources_team_popup(&mut self, ui: &Ui, _team_id: &TeamId, team: &Team) {
+    fn draw_gantt_chart_resources_team_popup(&mut self, ui: &Ui, team_id: &TeamId, team: &Team) {
         if let Some(_popup) = ui.begin_popup_context_item() {
+            let _read_only = ui.begin_disabled(!self.can_edit_plan());
             if let Some(_rename_team_menu) = ui.begin_menu("Rename Team") {
                 if let Some(_child_window) = ui.child_window("##rename_team_menu")
                         .size(RENAME_TEAM_CHILD_WINDOW_SIZE)
@@ -544,10 +579,10 @@ impl Gui {
                     if can_create_team {
                         ui.close_current_popup();
                         self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::RenameTeam {
-                            old_name: team.name.clone(),
+                            id: *team_id,
                             new_name: self.team_input_text_buffer.clone(),
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to rename team: {e}");
+                            gui_notify!(self, "Failed to rename team: {e}");
                         });
                         self.team_input_text_buffer.clear();
                     }
@@ -555,9 +590,9 @@ impl Gui {
             }
             if ui.menu_item("Delete Team") {
                 self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::DeleteTeam {
-                    name: team.name.clone(),
+                    id: *team_id,
                 }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                    eprintln!("Failed to delete team: {e}");
+                    gui_notify!(self, "Failed to delete team: {e}");
                 });
             }
             ui.separator();
@@ -578,11 +613,13 @@ impl Gui {
                     }
                     if can_create_resource {
                         ui.close_current_popup();
+                        let resource_id = self.project.flow_state_mut().next_resource_id();
                         self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::CreateResource {
+                            id: resource_id,
                             name: self.resource_input_text_buffer.clone(),
-                            team_name: team.name.clone(),
+                            team_id: *team_id,
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to create resource: {e}");
+                            gui_notify!(self, "Failed to create resource: {e}");
                         });
                         self.resource_input_text_buffer.clear();
                     }
@@ -591,12 +628,13 @@ impl Gui {
         }
     }
 
-    pub(super) fn draw_gantt_chart_resources_team_resource_popup(&mut self, ui: &Ui, _resource_id: &ResourceId, resource: &Resource) {
+    pub(super) fn draw_gantt_chart_resources_team_resource_popup(&mut self, ui: &Ui, resource_id: &ResourceId, resource: &Resource) {
         let is_info_filled_in =
                 |task_title: &str, ticket: &str, duration: f32| {
             !task_title.is_empty() && !ticket.is_empty() && duration > 0.0
         };
         if let Some(_popup) = ui.begin_popup_context_item() {
+            let read_only = ui.begin_disabled(!self.can_edit_plan());
             if let Some(_create_task_menu) = ui.begin_menu("Create Task") {
                 if let Some(_child_window) = ui.child_window("##create_task_menu")
                         .size(CREATE_TASK_CHILD_WINDOW_SIZE)
@@ -618,6 +656,7 @@ impl Gui {
                         .display_format("%.2f days")
                         .step(1.0)
                         .build();
+                    self.draw_task_estimate_inputs(ui);
                     ui.disabled(!is_info_filled_in(
                             &self.task_title_input_text_buffer,
                             &self.ticket_input_text_buffer,
@@ -631,28 +670,26 @@ impl Gui {
                                     id: task_id,
                                     ticket: self.ticket_input_text_buffer.clone(),
                                     title: self.task_title_input_text_buffer.clone(),
-                                    duration: TaskDuration {
-                                        days: self.task_duration_days as u64,
-                                        fraction: (self.task_duration_days.fract() * 100.0) as u8,
-                                    },
+                                    duration: self.task_duration_input(),
+                                    estimate: self.task_estimate_input(),
                                 }},
                                 Command { timestamp, details: CommandDetails::AssignTask {
                                     task_id: task_id,
-                                    resource_name: resource.name.clone(),
+                                    resource_id: *resource_id,
                                 }},
                             ];
                             for &label_id in &self.filtered_labels {
-                                if let Some(label) = self.project.flow_state().labels.get(&label_id) {
+                                if self.project.flow_state().labels.contains_key(&label_id) {
                                     commands.push(Command { timestamp, details: CommandDetails::AddLabelToTask {
                                         task_id,
-                                        label_name: label.name.clone(),
+                                        label_id,
                                     }});
                                 }
                             }
                             self.project.invoke_command(Command { timestamp, details: CommandDetails::CompoundCommand {
                                 commands,
                             }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                eprintln!("Failed to assign task: {e}");
+                                gui_notify!(self, "Failed to assign task: {e}");
                             });
                             self.task_title_input_text_buffer.clear();
                         }
@@ -678,30 +715,37 @@ impl Gui {
                     if can_create_resource {
                         ui.close_current_popup();
                         self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::RenameResource {
-                            old_name: resource.name.clone(),
+                            id: *resource_id,
                             new_name: self.resource_input_text_buffer.clone(),
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to rename resource: {e}");
+                            gui_notify!(self, "Failed to rename resource: {e}");
                         });
                         self.resource_input_text_buffer.clear();
                     }
                 }
             }
+            read_only.end();
+            if ui.menu_item("Inspect") {
+                self.start_resource_inspection(*resource_id);
+                ui.close_current_popup();
+            }
+            let _read_only = ui.begin_disabled(!self.can_edit_plan());
             if ui.menu_item("Delete Resource") {
                 self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::DeleteResource {
-                    name: resource.name.clone(),
+                    id: *resource_id,
                 }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                    eprintln!("Failed to delete resource: {e}");
+                    gui_notify!(self, "Failed to delete resource: {e}");
                 });
             }
         }
     }
 
-    pub(super) fn draw_gantt_chart_resources_team_resource_content_popup(&mut self, ui: &Ui, resource_id: &ResourceId, resource: &Resource, day: &NaiveDate) {
+    pub(super) fn draw_gantt_chart_resources_team_resource_content_popup(&mut self, ui: &Ui, resource_id: &ResourceId, _resource: &Resource, day: &NaiveDate) {
         let is_info_filled_in = |duration: f32| duration > 0.0;
         let add_or_update_absence_string;
         let mut show_remove_option = false;
         if let Some(_popup) = ui.begin_popup_context_item() {
+            let _read_only = ui.begin_disabled(!self.can_log_for(*resource_id));
             if self.project.flow_state().cache().resource_absence_rendering.get(resource_id).is_none() ||
                     self.project.flow_state().cache().resource_absence_rendering.get(resource_id).unwrap().get(day).is_none() ||
                     *self.project.flow_state().cache().resource_absence_rendering.get(resource_id).unwrap().get(day).unwrap() == 0 {
@@ -752,11 +796,11 @@ impl Gui {
                             fraction: (self.absence_duration_days.fract() * 100.0) as u8,
                         };
                         self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::SetAbsence {
-                            resource_name: resource.name.clone(),
+                            resource_id: *resource_id,
                             start_date: *day,
                             days: absence_duration,
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            gui_log!(self, "Failed to add Absence: {e}");
+                            gui_notify!(self, "Failed to add Absence: {e}");
                         });
                         self.absence_duration_days = 0.0;
                     }
@@ -765,14 +809,14 @@ impl Gui {
             if show_remove_option {
                 if ui.menu_item("Remove Absence") {
                     self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::SetAbsence {
-                        resource_name: resource.name.clone(),
+                        resource_id: *resource_id,
                         start_date: *day,
                         days: TaskDuration {
                             days: 0,
                             fraction: 0,
                         },
                     }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                        gui_log!(self, "Failed to remove Absence: {e}");
+                        gui_notify!(self, "Failed to remove Absence: {e}");
                     });
                 }
             }
@@ -781,13 +825,14 @@ impl Gui {
 
     fn draw_gantt_chart_resources_team_resource_task_popup(&mut self, ui: &Ui, task_id: &TaskId, task: &Task) {
         if let Some(_popup) = ui.begin_popup_context_item() {
+            let read_only = ui.begin_disabled(!self.can_edit_plan());
             if ui.menu_item("Move to top") {
                 ui.close_current_popup();
                 self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::PrioritizeTask {
                     task_id: *task_id,
                     to_top: true
                 }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                    gui_log!(self, "Failed to move task to top: {e}");
+                    gui_notify!(self, "Failed to move task to top: {e}");
                 });
             }
             if ui.menu_item("Move up") {
@@ -796,7 +841,7 @@ impl Gui {
                     task_id: *task_id,
                     to_top: false
                 }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                    gui_log!(self, "Failed to move task up: {e}");
+                    gui_notify!(self, "Failed to move task up: {e}");
                 });
             }
             if ui.menu_item("Move down") {
@@ -805,7 +850,7 @@ impl Gui {
                     task_id: *task_id,
                     to_bottom: false
                 }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                    gui_log!(self, "Failed to move task down: {e}");
+                    gui_notify!(self, "Failed to move task down: {e}");
                 });
             }
             if ui.menu_item("Move to bottom") {
@@ -814,16 +859,16 @@ impl Gui {
                     task_id: *task_id,
                     to_bottom: true
                 }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                    gui_log!(self, "Failed to move task to bottom: {e}");
+                    gui_notify!(self, "Failed to move task to bottom: {e}");
                 });
             }
             ui.separator();
             if let Some(_assign_to_menu) = ui.begin_menu("Assign to") {
-                let mut resources: Vec<_> = self.project.flow_state().resources.values().cloned().collect();
-                resources.sort_by(|alloc, b| alloc.name.cmp(&b.name));
-                for resource in resources {
+                let mut resources: Vec<_> = self.project.flow_state().resources.iter().map(|(id, resource)| (*id, resource.clone())).collect();
+                resources.sort_by(|(_, alloc), (_, b)| alloc.name.cmp(&b.name));
+                for (resource_id, resource) in resources {
                     if ui.menu_item(resource.name.clone()) {
-                        self.gantt_resources_assign_task_from_menu(*task_id, resource.name.clone());
+                        self.gantt_resources_assign_task_from_menu(*task_id, resource_id);
                     }
                 }
             }
@@ -831,30 +876,30 @@ impl Gui {
                 self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::UnassignTask {
                     task_id: *task_id,
                 }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                    gui_log!(self, "Failed to unassign task: {e}");
+                    gui_notify!(self, "Failed to unassign task: {e}");
                 });
                 ui.close_current_popup();
             }
             if let Some(_watchers_menu) = ui.begin_menu("Watchers") {
                 /* list all the resources as alloc menu item. If the resource is already alloc watcher, it should be checked */
-                let mut resources: Vec<_> = self.project.flow_state().resources.values().cloned().collect();
-                resources.sort_by(|alloc, b| alloc.name.cmp(&b.name));
-                for resource in resources {
+                let mut resources: Vec<_> = self.project.flow_state().resources.iter().map(|(id, resource)| (*id, resource.clone())).collect();
+                resources.sort_by(|(_, alloc), (_, b)| alloc.name.cmp(&b.name));
+                for (resource_id, resource) in resources {
                     let is_watching = resource.watched_tasks.contains(task_id);
                     if ui.menu_item_config(resource.name.clone()).selected(is_watching).build() {
                         if is_watching {
                             self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::RemoveWatcher {
                                 task_id: *task_id,
-                                resource_name: resource.name.clone(),
+                                resource_id,
                             }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                gui_log!(self, "Failed to unwatch task: {e}");
+                                gui_notify!(self, "Failed to unwatch task: {e}");
                             });
                         } else {
                             self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::AddWatcher {
                                 task_id: *task_id,
-                                resource_name: resource.name.clone(),
+                                resource_id,
                             }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                gui_log!(self, "Failed to watch task: {e}");
+                                gui_notify!(self, "Failed to watch task: {e}");
                             });
                         }
                     }
@@ -895,6 +940,7 @@ impl Gui {
                         .display_format("%.2f days")
                         .step(1.0)
                         .build();
+                    self.draw_task_estimate_inputs(ui);
                     if ui.button("Ok") {
                         can_update_task = is_info_filled_in(
                             &self.task_title_input_text_buffer,
@@ -907,31 +953,25 @@ impl Gui {
                             id: *task_id,
                             ticket: self.ticket_input_text_buffer.clone(),
                             title: self.task_title_input_text_buffer.clone(),
-                            duration: TaskDuration {
-                                days: self.task_duration_days as u64,
-                                fraction: (self.task_duration_days.fract() * 100.0) as u8,
-                            },
+                            duration: self.task_duration_input(),
+                            estimate: self.task_estimate_input(),
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                         self.task_title_input_text_buffer.clear();
                     }
                 }
             }
+            read_only.end();
             if ui.menu_item("Inspect") {
-                self.inspections.push(TaskInspection::from(
-                        *task_id,
-                        self.project.command_stack.iter()
-                            .map(|cr| cr.redo_command.clone())
-                            .collect::<Vec<_>>(),
-                        self.get_timestamp().date_naive())
-                );
+                self.start_task_inspection(*task_id);
                 ui.close_current_popup();
             }
             if ui.menu_item("Open in JIRA") {
                 self.open_task_in_jira(ui, &task);
                 ui.close_current_popup();
             }
+            let _read_only = ui.begin_disabled(!self.can_edit_plan());
             ui.separator();
             if let Some(_labels_menu) = ui.begin_menu("Labels") {
                 let labels: Vec<_> = self.project.flow_state().labels.iter().map(|(id, label)| (*id, label.clone())).collect();
@@ -941,16 +981,16 @@ impl Gui {
                         if is_selected {
                             self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::RemoveLabelFromTask {
                                 task_id: *task_id,
-                                label_name: label.name.clone(),
+                                label_id,
                             }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                gui_log!(self, "Failed to remove label from task: {e}");
+                                gui_notify!(self, "Failed to remove label from task: {e}");
                             });
                         } else {
                             self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::AddLabelToTask {
                                 task_id: *task_id,
-                                label_name: label.name.clone(),
+                                label_id,
                             }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                gui_log!(self, "Failed to add label to task: {e}");
+                                gui_notify!(self, "Failed to add label to task: {e}");
                             });
                         }
                     }
@@ -965,18 +1005,20 @@ impl Gui {
                             .build();
                         if ui.button("Ok") && !self.label_input_text_buffer.is_empty() {
                             let timestamp = self.get_timestamp();
+                            let label_id = self.project.flow_state_mut().next_label_id();
                             self.project.invoke_command(Command { timestamp, details: CommandDetails::CompoundCommand {
                                 commands: vec![
                                     Command { timestamp, details: CommandDetails::CreateLabel {
+                                        id: label_id,
                                         name: self.label_input_text_buffer.clone(),
                                     }},
                                     Command { timestamp, details: CommandDetails::AddLabelToTask {
                                         task_id: *task_id,
-                                        label_name: self.label_input_text_buffer.clone(),
+                                        label_id,
                                     }}
                                 ]
                             }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                gui_log!(self, "Failed to create label and add to task: {e}");
+                                gui_notify!(self, "Failed to create label and add to task: {e}");
                             });
                             self.label_input_text_buffer.clear();
                         }
@@ -997,12 +1039,10 @@ impl Gui {
                             id: *task_id,
                             ticket: task.ticket.clone(),
                             title: task.title.clone(),
-                            duration: TaskDuration {
-                                days: self.task_duration_days as u64,
-                                fraction: (self.task_duration_days.fract() * 100.0) as u8,
-                            },
+                            duration: self.task_duration_input(),
+                            estimate: task.estimate.map(|estimate| estimate.around(self.task_duration_input())),
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }
                     let mut new_duration_days = None;
@@ -1029,8 +1069,9 @@ impl Gui {
                             ticket: task.ticket.clone(),
                             title: task.title.clone(),
                             duration: new_duration_days,
+                            estimate: task.estimate.map(|estimate| estimate.around(new_duration_days)),
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }
                 }
@@ -1038,46 +1079,47 @@ impl Gui {
         }
     }
 
-    fn draw_gantt_chart_resources_team_resource_task_as_watcher_popup(&mut self, ui: &Ui, task_id: &TaskId, task: &Task, _resource_id: &ResourceId, resource: &Resource) {
+    fn draw_gantt_chart_resources_team_resource_task_as_watcher_popup(&mut self, ui: &Ui, task_id: &TaskId, task: &Task, resource_id: &ResourceId, _resource: &Resource) {
         if let Some(_popup) = ui.begin_popup_context_item() {
+            let read_only = ui.begin_disabled(!self.can_edit_plan());
             if let Some(_assign_to_menu) = ui.begin_menu("Assign to") {
-                let mut resources: Vec<_> = self.project.flow_state().resources.values().cloned().collect();
-                resources.sort_by(|alloc, b| alloc.name.cmp(&b.name));
-                for resource in resources {
+                let mut resources: Vec<_> = self.project.flow_state().resources.iter().map(|(id, resource)| (*id, resource.clone())).collect();
+                resources.sort_by(|(_, alloc), (_, b)| alloc.name.cmp(&b.name));
+                for (resource_id, resource) in resources {
                     if ui.menu_item(resource.name.clone()) {
-                        self.gantt_resources_assign_task_from_menu(*task_id, resource.name.clone());
+                        self.gantt_resources_assign_task_from_menu(*task_id, resource_id);
                     }
                 }
             }
             if ui.menu_item("Unwatch") {
                 self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::RemoveWatcher {
                     task_id: *task_id,
-                    resource_name: resource.name.clone(),
+                    resource_id: *resource_id,
                 }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                    gui_log!(self, "Failed to unwatch task: {e}");
+                    gui_notify!(self, "Failed to unwatch task: {e}");
                 });
                 ui.close_current_popup();
             }
             if let Some(_watchers_menu) = ui.begin_menu("Watchers") {
                 /* list all the resources as alloc menu item. If the resource is already alloc watcher, it should be checked */
-                let mut resources: Vec<_> = self.project.flow_state().resources.values().cloned().collect();
-                resources.sort_by(|alloc, b| alloc.name.cmp(&b.name));
-                for resource in resources {
+                let mut resources: Vec<_> = self.project.flow_state().resources.iter().map(|(id, resource)| (*id, resource.clone())).collect();
+                resources.sort_by(|(_, alloc), (_, b)| alloc.name.cmp(&b.name));
+                for (resource_id, resource) in resources {
                     let is_watching = resource.watched_tasks.contains(task_id);
                     if ui.menu_item_config(resource.name.clone()).selected(is_watching).build() {
                         if is_watching {
                             self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::RemoveWatcher {
                                 task_id: *task_id,
-                                resource_name: resource.name.clone(),
+                                resource_id,
                             }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                gui_log!(self, "Failed to unwatch task: {e}");
+                                gui_notify!(self, "Failed to unwatch task: {e}");
                             });
                         } else {
                             self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::AddWatcher {
                                 task_id: *task_id,
-                                resource_name: resource.name.clone(),
+                                resource_id,
                             }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                gui_log!(self, "Failed to watch task: {e}");
+                                gui_notify!(self, "Failed to watch task: {e}");
                             });
                         }
                     }
@@ -1118,6 +1160,7 @@ impl Gui {
                         .display_format("%.2f days")
                         .step(1.0)
                         .build();
+                    self.draw_task_estimate_inputs(ui);
                     if ui.button("Ok") {
                         can_update_task = is_info_filled_in(
                             &self.task_title_input_text_buffer,
@@ -1130,21 +1173,21 @@ impl Gui {
                             id: *task_id,
                             ticket: self.ticket_input_text_buffer.clone(),
                             title: self.task_title_input_text_buffer.clone(),
-                            duration: TaskDuration {
-                                days: self.task_duration_days as u64,
-                                fraction: (self.task_duration_days.fract() * 100.0) as u8,
-                            },
+                            duration: self.task_duration_input(),
+                            estimate: self.task_estimate_input(),
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                         self.task_title_input_text_buffer.clear();
                     }
                 }
             }
+            read_only.end();
             if ui.menu_item("Open in JIRA") {
                 self.open_task_in_jira(ui, &task);
                 ui.close_current_popup();
             }
+            let _read_only = ui.begin_disabled(!self.can_edit_plan());
             ui.separator();
             if let Some(_labels_menu) = ui.begin_menu("Labels") {
                 let labels: Vec<_> = self.project.flow_state().labels.iter().map(|(id, label)| (*id, label.clone())).collect();
@@ -1154,16 +1197,16 @@ impl Gui {
                         if is_selected {
                             self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::RemoveLabelFromTask {
                                 task_id: *task_id,
-                                label_name: label.name.clone(),
+                                label_id,
                             }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                gui_log!(self, "Failed to remove label from task: {e}");
+                                gui_notify!(self, "Failed to remove label from task: {e}");
                             });
                         } else {
                             self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::AddLabelToTask {
                                 task_id: *task_id,
-                                label_name: label.name.clone(),
+                                label_id,
                             }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                gui_log!(self, "Failed to add label to task: {e}");
+                                gui_notify!(self, "Failed to add label to task: {e}");
                             });
                         }
                     }
@@ -1178,18 +1221,20 @@ impl Gui {
                             .build();
                         if ui.button("Ok") && !self.label_input_text_buffer.is_empty() {
                             let timestamp = self.get_timestamp();
+                            let label_id = self.project.flow_state_mut().next_label_id();
                             self.project.invoke_command(Command { timestamp, details: CommandDetails::CompoundCommand {
                                 commands: vec![
                                     Command { timestamp, details: CommandDetails::CreateLabel {
+                                        id: label_id,
                                         name: self.label_input_text_buffer.clone(),
                                     }},
                                     Command { timestamp, details: CommandDetails::AddLabelToTask {
                                         task_id: *task_id,
-                                        label_name: self.label_input_text_buffer.clone(),
+                                        label_id,
                                     }}
                                 ]
                             }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                gui_log!(self, "Failed to create label and add to task: {e}");
+                                gui_notify!(self, "Failed to create label and add to task: {e}");
                             });
                             self.label_input_text_buffer.clear();
                         }
@@ -1211,12 +1256,10 @@ impl Gui {
                             id: *task_id,
                             ticket: task.ticket.clone(),
                             title: task.title.clone(),
-                            duration: TaskDuration {
-                                days: self.task_duration_days as u64,
-                                fraction: (self.task_duration_days.fract() * 100.0) as u8,
-                            },
+                            duration: self.task_duration_input(),
+                            estimate: task.estimate.map(|estimate| estimate.around(self.task_duration_input())),
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }
                     let mut new_duration_days = None;
@@ -1243,8 +1286,9 @@ impl Gui {
                             ticket: task.ticket.clone(),
                             title: task.title.clone(),
                             duration: new_duration_days,
+                            estimate: task.estimate.map(|estimate| estimate.around(new_duration_days)),
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }
                 }
@@ -1252,8 +1296,9 @@ impl Gui {
         }
     }
 
-    fn draw_gantt_chart_resources_team_resource_task_content_popup(&mut self, ui: &Ui, resource_id: &ResourceId, resource: &Resource, task_id: &TaskId, task: &Task, day: &NaiveDate) {
+    fn draw_gantt_chart_resources_team_resource_task_content_popup(&mut self, ui: &Ui, resource_id: &ResourceId, _resource: &Resource, task_id: &TaskId, task: &Task, day: &NaiveDate) {
         if let Some(_popup) = ui.begin_popup_context_item() {
+            let worklog_read_only = ui.begin_disabled(!self.can_log_for(*resource_id));
             if let Some(_worklog_menu) = ui.begin_menu("Set Worklog") {
                 if let Some(_child_window) = ui.child_window("##set_worklog")
                         .size(SET_WORKLOG_CHILD_WINDOW_SIZE)
@@ -1268,10 +1313,10 @@ impl Gui {
                         self.project.invoke_command(Command { timestamp, details: CommandDetails::SetWorklog {
                             task_id: *task_id,
                             date: *day,
-                            resource_name: resource.name.clone(),
+                            resource_id: *resource_id,
                             fraction: self.worklog_fraction,
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }
                     if ui.button("0%") {
@@ -1280,10 +1325,10 @@ impl Gui {
                         self.project.invoke_command(Command { timestamp, details: CommandDetails::SetWorklog {
                             task_id: *task_id,
                             date: *day,
-                            resource_name: resource.name.clone(),
+                            resource_id: *resource_id,
                             fraction: 0,
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }
                     ui.same_line();
@@ -1293,10 +1338,10 @@ impl Gui {
                         self.project.invoke_command(Command { timestamp, details: CommandDetails::SetWorklog {
                             task_id: *task_id,
                             date: *day,
-                            resource_name: resource.name.clone(),
+                            resource_id: *resource_id,
                             fraction: 10,
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }
                     ui.same_line();
@@ -1306,10 +1351,10 @@ impl Gui {
                         self.project.invoke_command(Command { timestamp, details: CommandDetails::SetWorklog {
                             task_id: *task_id,
                             date: *day,
-                            resource_name: resource.name.clone(),
+                            resource_id: *resource_id,
                             fraction: 25,
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }
                     ui.same_line();
@@ -1319,10 +1364,10 @@ impl Gui {
                         self.project.invoke_command(Command { timestamp, details: CommandDetails::SetWorklog {
                             task_id: *task_id,
                             date: *day,
-                            resource_name: resource.name.clone(),
+                            resource_id: *resource_id,
                             fraction: 50,
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }
                     ui.same_line();
@@ -1332,10 +1377,10 @@ impl Gui {
                         self.project.invoke_command(Command { timestamp, details: CommandDetails::SetWorklog {
                             task_id: *task_id,
                             date: *day,
-                            resource_name: resource.name.clone(),
+                            resource_id: *resource_id,
                             fraction: 75,
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }                    
                     ui.same_line();
@@ -1345,10 +1390,10 @@ impl Gui {
                         self.project.invoke_command(Command { timestamp, details: CommandDetails::SetWorklog {
                             task_id: *task_id,
                             date: *day,
-                            resource_name: resource.name.clone(),
+                            resource_id: *resource_id,
                             fraction: 100,
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }
                     if ui.button("Use all available") {
@@ -1356,17 +1401,9 @@ impl Gui {
                         let absence_fraction = self.project.flow_state().cache().resource_absence_rendering.get(resource_id)
                             .and_then(|r| r.get(day))
                             .cloned().unwrap_or(0);
-                        let total_worklogs_for_resource_for_day = {
-                            let mut total = 0;
-                            for task_allocs in self.project.flow_state().worklogs.values() {
-                                if let Some(resource_worklogs) = task_allocs.get(resource_id) {
-                                    if let Some(worklog) = resource_worklogs.get(day) {
-                                        total += worklog.fraction as u32;
-                                    }
-                                }
-                            }
-                            total
-                        };
+                        let total_worklogs_for_resource_for_day = self.project.flow_state().cache().worklog_totals.get(resource_id)
+                            .and_then(|r| r.get(day))
+                            .cloned().unwrap_or(0) as u32;
                         if let Some(remaining_fraction) = 100u32.checked_sub(absence_fraction as u32 + total_worklogs_for_resource_for_day) {
                             let current_worklog_fraction = self.project.flow_state().worklogs.get(task_id)
                                 .and_then(|task_allocs| task_allocs.get(resource_id))
@@ -1377,15 +1414,17 @@ impl Gui {
                             self.project.invoke_command(Command { timestamp, details: CommandDetails::SetWorklog {
                                 task_id: *task_id,
                                 date: *day,
-                                resource_name: resource.name.clone(),
+                                resource_id: *resource_id,
                                 fraction: current_worklog_fraction + remaining_fraction as u8,
                             }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                eprintln!("Failed to update task: {e}");
+                                gui_notify!(self, "Failed to update task: {e}");
                             });
                         }
                     }
                 }
             }
+            worklog_read_only.end();
+            let _read_only = ui.begin_disabled(!self.can_edit_plan());
             if let Some(_update_duration_menu) = ui.begin_menu("Update Duration") {
                 if let Some(_child_window) = ui.child_window("##update_duration_menu")
                         .size(UPDATE_TASK_CHILD_WINDOW_SIZE)
@@ -1399,12 +1438,10 @@ impl Gui {
                             id: *task_id,
                             ticket: task.ticket.clone(),
                             title: task.title.clone(),
-                            duration: TaskDuration {
-                                days: self.task_duration_days as u64,
-                                fraction: (self.task_duration_days.fract() * 100.0) as u8,
-                            },
+                            duration: self.task_duration_input(),
+                            estimate: task.estimate.map(|estimate| estimate.around(self.task_duration_input())),
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }
                     let mut new_duration_days = None;
@@ -1434,8 +1471,9 @@ impl Gui {
                             ticket: task.ticket.clone(),
                             title: task.title.clone(),
                             duration: new_duration_days,
+                            estimate: task.estimate.map(|estimate| estimate.around(new_duration_days)),
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }
                     if ui.button("Crop") {
@@ -1453,8 +1491,9 @@ impl Gui {
                                 ticket: task.ticket.clone(),
                                 title: task.title.clone(),
                                 duration,
+                                estimate: task.estimate.map(|estimate| estimate.around(duration)),
                             }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                eprintln!("Failed to crop task: {e}");
+                                gui_notify!(self, "Failed to crop task: {e}");
                             });
                         }
                     }
@@ -1470,8 +1509,9 @@ impl Gui {
                                 ticket: task.ticket.clone(),
                                 title: task.title.clone(),
                                 duration,
+                                estimate: task.estimate.map(|estimate| estimate.around(duration)),
                             }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                eprintln!("Failed to crop task: {e}");
+                                gui_notify!(self, "Failed to crop task: {e}");
                             });
                         }
                     }
@@ -1486,6 +1526,7 @@ impl Gui {
             !task_title.is_empty() && !ticket.is_empty() && duration > 0.0
         };
         if let Some(_popup) = ui.begin_popup_context_item() {
+            let _read_only = ui.begin_disabled(!self.can_edit_plan());
             if let Some(_create_task_menu) = ui.begin_menu("Create Task") {
                 if let Some(_child_window) = ui.child_window("##create_task_menu")
                         .size(CREATE_TASK_CHILD_WINDOW_SIZE)
@@ -1516,6 +1557,7 @@ impl Gui {
                         .display_format("%.2f days")
                         .step(1.0)
                         .build();
+                    self.draw_task_estimate_inputs(ui);
                     if ui.button("Ok") {
                         can_create_task = is_info_filled_in(
                             &self.task_title_input_text_buffer,
@@ -1530,24 +1572,22 @@ impl Gui {
                                 id: task_id,
                                 ticket: self.ticket_input_text_buffer.clone(),
                                 title: self.task_title_input_text_buffer.clone(),
-                                duration: TaskDuration {
-                                    days: self.task_duration_days as u64,
-                                    fraction: (self.task_duration_days.fract() * 100.0) as u8,
-                                },
+                                duration: self.task_duration_input(),
+                                estimate: self.task_estimate_input(),
                             }}
                         ];
                         for &label_id in &self.filtered_labels {
-                            if let Some(label) = self.project.flow_state().labels.get(&label_id) {
+                            if self.project.flow_state().labels.contains_key(&label_id) {
                                 commands.push(Command { timestamp: self.get_timestamp(), details: CommandDetails::AddLabelToTask {
                                     task_id,
-                                    label_name: label.name.clone(),
+                                    label_id,
                                 }});
                             }
                         }
                         self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::CompoundCommand {
                             commands,
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to create task: {e}");
+                            gui_notify!(self, "Failed to create task: {e}");
                         });
                         self.task_title_input_text_buffer.clear();
                     }
@@ -1558,35 +1598,36 @@ impl Gui {
 
     fn draw_gantt_chart_resources_team_unassigned_task_popup(&mut self, ui: &Ui, task_id: &TaskId, task: &Task) {
         if let Some(_popup) = ui.begin_popup_context_item() {
+            let read_only = ui.begin_disabled(!self.can_edit_plan());
             if let Some(_assign_to_menu) = ui.begin_menu("Assign to") {
-                let mut resources: Vec<_> = self.project.flow_state().resources.values().cloned().collect();
-                resources.sort_by(|alloc, b| alloc.name.cmp(&b.name));
-                for resource in resources {
+                let mut resources: Vec<_> = self.project.flow_state().resources.iter().map(|(id, resource)| (*id, resource.clone())).collect();
+                resources.sort_by(|(_, alloc), (_, b)| alloc.name.cmp(&b.name));
+                for (resource_id, resource) in resources {
                     if ui.menu_item(resource.name.clone()) {
-                        self.gantt_resources_assign_task_from_menu(*task_id, resource.name.clone());
+                        self.gantt_resources_assign_task_from_menu(*task_id, resource_id);
                     }
                 }
             }
             if let Some(_watchers_menu) = ui.begin_menu("Watchers") {
                 /* list all the resources as alloc menu item. If the resource is already alloc watcher, it should be checked */
-                let mut resources: Vec<_> = self.project.flow_state().resources.values().cloned().collect();
-                resources.sort_by(|alloc, b| alloc.name.cmp(&b.name));
-                for resource in resources {
+                let mut resources: Vec<_> = self.project.flow_state().resources.iter().map(|(id, resource)| (*id, resource.clone())).collect();
+                resources.sort_by(|(_, alloc), (_, b)| alloc.name.cmp(&b.name));
+                for (resource_id, resource) in resources {
                     let is_watching = resource.watched_tasks.contains(task_id);
                     if ui.menu_item_config(resource.name.clone()).selected(is_watching).build() {
                         if is_watching {
                             self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::RemoveWatcher {
                                 task_id: *task_id,
-                                resource_name: resource.name.clone(),
+                                resource_id,
                             }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                gui_log!(self, "Failed to unwatch task: {e}");
+                                gui_notify!(self, "Failed to unwatch task: {e}");
                             });
                         } else {
                             self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::AddWatcher {
                                 task_id: *task_id,
-                                resource_name: resource.name.clone(),
+                                resource_id,
                             }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                gui_log!(self, "Failed to watch task: {e}");
+                                gui_notify!(self, "Failed to watch task: {e}");
                             });
                         }
                     }
@@ -1597,7 +1638,7 @@ impl Gui {
                 self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::DeleteTask {
                     id: *task_id,
                 }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                    gui_log!(self, "Failed to delete task: {e}");
+                    gui_notify!(self, "Failed to delete task: {e}");
                 });
             }
             if let Some(_update_task_menu) = ui.begin_menu("Update Task") {
@@ -1634,6 +1675,7 @@ impl Gui {
                         .display_format("%.2f days")
                         .step(1.0)
                         .build();
+                    self.draw_task_estimate_inputs(ui);
                     if ui.button("Ok") {
                         can_update_task = is_info_filled_in(
                             &self.task_title_input_text_buffer,
@@ -1646,21 +1688,21 @@ impl Gui {
                             id: *task_id,
                             ticket: self.ticket_input_text_buffer.clone(),
                             title: self.task_title_input_text_buffer.clone(),
-                            duration: TaskDuration {
-                                days: self.task_duration_days as u64,
-                                fraction: (self.task_duration_days.fract() * 100.0) as u8,
-                            },
+                            duration: self.task_duration_input(),
+                            estimate: self.task_estimate_input(),
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                         self.task_title_input_text_buffer.clear();
                     }
                 }
             }
+            read_only.end();
             if ui.menu_item("Open in JIRA") {
                 self.open_task_in_jira(ui, &task);
                 ui.close_current_popup();
             }
+            let _read_only = ui.begin_disabled(!self.can_edit_plan());
             ui.separator();
             if let Some(_update_task_menu) = ui.begin_menu("Labels") {
                 if let Some(_add_label_menu) = ui.begin_menu("Add Label") {
@@ -1670,9 +1712,9 @@ impl Gui {
                             if ui.menu_item(&label.name) {
                                 self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::AddLabelToTask {
                                     task_id: *task_id,
-                                    label_name: label.name.clone(),
+                                    label_id,
                                 }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                    gui_log!(self, "Failed to add label to task: {e}");
+                                    gui_notify!(self, "Failed to add label to task: {e}");
                                 });
                             }
                         }
@@ -1686,18 +1728,20 @@ impl Gui {
                                 .build();
                             if ui.button("Ok") && !self.label_input_text_buffer.is_empty() {
                                 let timestamp = self.get_timestamp();
+                                let label_id = self.project.flow_state_mut().next_label_id();
                                 self.project.invoke_command(Command { timestamp, details: CommandDetails::CompoundCommand {
                                     commands: vec![
                                         Command { timestamp, details: CommandDetails::CreateLabel {
+                                            id: label_id,
                                             name: self.label_input_text_buffer.clone(),
                                         }},
                                         Command { timestamp, details: CommandDetails::AddLabelToTask {
                                             task_id: *task_id,
-                                            label_name: self.label_input_text_buffer.clone(),
+                                            label_id,
                                         }}
                                     ]
                                 }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                    gui_log!(self, "Failed to create label and add to task: {e}");
+                                    gui_notify!(self, "Failed to create label and add to task: {e}");
                                 });
                                 self.label_input_text_buffer.clear();
                             }
@@ -1711,9 +1755,9 @@ impl Gui {
                             if ui.menu_item(&label.name) {
                                 self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::RemoveLabelFromTask {
                                     task_id: *task_id,
-                                    label_name: label.name.clone(),
+                                    label_id,
                                 }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                                    gui_log!(self, "Failed to remove label from task: {e}");
+                                    gui_notify!(self, "Failed to remove label from task: {e}");
                                 });
                             }
                         }
@@ -1734,12 +1778,10 @@ impl Gui {
                             id: *task_id,
                             ticket: task.ticket.clone(),
                             title: task.title.clone(),
-                            duration: TaskDuration {
-                                days: self.task_duration_days as u64,
-                                fraction: (self.task_duration_days.fract() * 100.0) as u8,
-                            },
+                            duration: self.task_duration_input(),
+                            estimate: task.estimate.map(|estimate| estimate.around(self.task_duration_input())),
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }
                     let mut new_duration_days = None;
@@ -1766,8 +1808,9 @@ impl Gui {
                             ticket: task.ticket.clone(),
                             title: task.title.clone(),
                             duration: new_duration_days,
+                            estimate: task.estimate.map(|estimate| estimate.around(new_duration_days)),
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }
                 }
@@ -1777,6 +1820,7 @@ impl Gui {
 
     fn draw_gantt_chart_resources_team_unassigned_task_content_popup(&mut self, ui: &Ui, task_id: &TaskId, task: &Task, _day: &NaiveDate) {
         if let Some(_popup) = ui.begin_popup_context_item() {
+            let _read_only = ui.begin_disabled(!self.can_edit_plan());
             if let Some(_update_duration_menu) = ui.begin_menu("Update Duration") {
                 if let Some(_child_window) = ui.child_window("##update_duration_menu")
                         .size(UPDATE_TASK_CHILD_WINDOW_SIZE)
@@ -1790,12 +1834,10 @@ impl Gui {
                             id: *task_id,
                             ticket: task.ticket.clone(),
                             title: task.title.clone(),
-                            duration: TaskDuration {
-                                days: self.task_duration_days as u64,
-                                fraction: (self.task_duration_days.fract() * 100.0) as u8,
-                            },
+                            duration: self.task_duration_input(),
+                            estimate: task.estimate.map(|estimate| estimate.around(self.task_duration_input())),
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }
                     let mut new_duration_days = None;
@@ -1825,8 +1867,9 @@ impl Gui {
                             ticket: task.ticket.clone(),
                             title: task.title.clone(),
                             duration: new_duration_days,
+                            estimate: task.estimate.map(|estimate| estimate.around(new_duration_days)),
                         }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                            eprintln!("Failed to update task: {e}");
+                            gui_notify!(self, "Failed to update task: {e}");
                         });
                     }
                 }
@@ -1,11 +1,18 @@
 use crate::gui::*;
+use crate::gui::utils::GanttNode;
 
-enum RoleOfResourceInTask {
+#[derive(Clone, Copy)]
+pub(super) enum RoleOfResourceInTask {
     Assignee,
     WorklogContributor,
     Watcher,
 }
 
+pub(super) enum TasksGanttRow {
+    Task { task_id: TaskId, row_counter: usize },
+    Resource { task_id: TaskId, resource_id: ResourceId, role: RoleOfResourceInTask, row_counter: usize },
+}
+
 impl Gui {
     pub(super) fn draw_gantt_chart_tasks(&mut self, ui: &Ui) {
         if self.draw_gantt_chart_table(ui, "##tasks_gantt_chart") {
@@ -13,13 +20,41 @@ impl Gui {
             self.draw_gantt_chart_milestones_row(ui);
             self.draw_gantt_chart_tasks_contents(ui);
             unsafe {imgui::sys::igEndTable();}
+            self.draw_gantt_chart_day_scrollbar(ui);
         }
     }
 
     fn draw_gantt_chart_tasks_contents(&mut self, ui: &Ui) {
-        let task_ids: Vec<TaskId> = self.project.flow_state().tasks.keys().cloned().collect();
-        for (i, task_id) in task_ids.iter().enumerate() {
-            let task = self.project.flow_state().tasks.get(task_id).unwrap().clone();
+        let key = self.gantt_chart_rows_key();
+        if self.drawing_aids.tasks_gantt_rows.is_stale(&key) {
+            let rows = self.gantt_chart_tasks_rows();
+            self.drawing_aids.tasks_gantt_rows.set(key, rows);
+        }
+        let rows = self.drawing_aids.tasks_gantt_rows.rows();
+        let mut clipper = ListClipper::new(rows.len() as i32).begin(ui);
+        while clipper.step() {
+            for row in clipper.display_start()..clipper.display_end() {
+                match &rows[row as usize] {
+                    TasksGanttRow::Task { task_id, row_counter } => {
+                        self.drawing_aids.row_counter = *row_counter;
+                        let task = self.project.flow_state().tasks[task_id].clone();
+                        self.draw_gantt_chart_tasks_task(ui, task_id, &task);
+                    }
+                    TasksGanttRow::Resource { task_id, resource_id, role, row_counter } => {
+                        self.drawing_aids.row_counter = *row_counter;
+                        let task = self.project.flow_state().tasks[task_id].clone();
+                        let resource = self.project.flow_state().resources[resource_id].clone();
+                        self.draw_gantt_chart_tasks_task_resource(ui, task_id, &task, resource_id, &resource, *role);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Rows of the tasks that pass the filters, each followed by its resources if expanded.
+    fn gantt_chart_tasks_rows(&self) -> Vec<TasksGanttRow> {
+        let mut rows = Vec::new();
+        for (i, (task_id, task)) in self.project.flow_state().tasks.iter().enumerate() {
             let should_show = (
                 self.filtered_labels.is_empty()
                     || self.filtered_labels.iter().all(|label_id| task.label_ids.contains(label_id))
@@ -28,22 +63,44 @@ impl Gui {
                     || task.title.contains(&self.find_input_buffer)
                     || task.ticket.contains(&self.find_input_buffer)
             );
-            if should_show {
-                self.drawing_aids.row_counter = i;
-                self.draw_gantt_chart_tasks_task(ui, task_id, &task);
+            if !should_show {
+                continue;
+            }
+            rows.push(TasksGanttRow::Task { task_id: *task_id, row_counter: i });
+            if self.drawing_aids.collapsed_gantt_nodes.contains(&GanttNode::Task(*task_id)) {
+                continue;
+            }
+
+            let mut resource_data: BTreeMap<ResourceId, RoleOfResourceInTask> = self.project.flow_state().worklogs.get(task_id)
+                .map(|worklogs| {
+                    worklogs.keys()
+                        .map(|&resource_id| (resource_id, RoleOfResourceInTask::WorklogContributor))
+                        .collect()
+                })
+                .unwrap_or_else(BTreeMap::new);
+            if let Some(assignee) = task.assignee {
+                resource_data.insert(assignee, RoleOfResourceInTask::Assignee);
+            }
+            for watcher in &task.watchers {
+                resource_data.insert(*watcher, RoleOfResourceInTask::Watcher);
+            }
+            for (resource_id, role) in resource_data {
+                if self.project.flow_state().resources.contains_key(&resource_id) {
+                    rows.push(TasksGanttRow::Resource { task_id: *task_id, resource_id, role, row_counter: i });
+                }
             }
         }
+        rows
     }
 
     fn draw_gantt_chart_tasks_task(&mut self, ui: &Ui, task_id: &TaskId, task: &Task) {
         ui.table_next_row();
         ui.table_next_column();
+        let day_columns = self.drawing_aids.visible_day_columns.clone();
 
         let _task_token_id = ui.push_id_int(*task_id as i32);
         let task_repr = format!("{} - {}", task.ticket, task.title);
-        let task_repr_cstr = std::ffi::CString::new(task_repr.clone()).unwrap();
-        let flags = imgui::sys::ImGuiTreeNodeFlags_SpanFullWidth | imgui::sys::ImGuiTreeNodeFlags_DefaultOpen;
-        let expand_task = unsafe {
+        {
             let bold = self.bold_font.borrow().unwrap();
             let _h = ui.push_font(bold);
             let bg_color = if self.drawing_aids.row_counter % 2 == 0 {
@@ -52,84 +109,51 @@ impl Gui {
                 ui.style_color(StyleColor::TableRowBgAlt)
             };
             ui.table_set_bg_color(TableBgTarget::CELL_BG, bg_color);
-            imgui::sys::igTreeNodeEx_Str(task_repr_cstr.as_ptr(), flags as i32)
-        };
+            self.draw_gantt_chart_row_label(&task_repr, 0, Some(GanttNode::Task(*task_id)), 0);
+        }
         //self.draw_gantt_chart_tasks_task_popup(ui, task_id, task);
 
-        for i in 1..=self.project.flow_state().cache().num_days() {
-            if ui.table_next_column() {
-                let day = self.project.flow_state().cache().day(i - 1);
+        for i in day_columns {
+            if ui.table_set_column_index(i) {
+                let day = self.gantt_chart_day(i);
                 self.draw_cell_background(ui, &day);
                 self.draw_milestone(ui, &day);
             }
         }
-
-        if expand_task {
-            let mut resource_data: BTreeMap<ResourceId, RoleOfResourceInTask> = self.project.flow_state().worklogs.get(task_id)
-                .map(|worklogs| {
-                    worklogs.keys()
-                        .filter_map(|&resource_id| {
-                            self.project.flow_state().resources.get(&resource_id)
-                                .map(|_resource| (resource_id, RoleOfResourceInTask::WorklogContributor))
-                        })
-                        .collect()
-                })
-                .unwrap_or_else(BTreeMap::new);
-            if let Some(assignee) = task.assignee {
-                resource_data.insert(assignee, RoleOfResourceInTask::Assignee);
-            }
-            for watcher in &task.watchers {
-                resource_data.insert(*watcher, RoleOfResourceInTask::Watcher);
-            }
-
-            let resource_entries: BTreeMap<ResourceId, (Resource, RoleOfResourceInTask)> = resource_data
-                .into_iter()
-                .filter_map(|(resource_id, role)| {
-                    self.project.flow_state().resources.get(&resource_id)
-                        .map(|resource| (resource_id, (resource.clone(), role)))
-                })
-                .collect();
-            for (resource_id, (resource, role)) in resource_entries {
-                self.draw_gantt_chart_tasks_task_resource(ui, task_id, task, &resource_id, &resource, role);
-            }
-            unsafe {imgui::sys::igTreePop();}
-        }
     }
 
     fn draw_gantt_chart_tasks_task_resource(&mut self, ui: &Ui, task_id: &TaskId, task: &Task, resource_id: &ResourceId, resource: &Resource, role: RoleOfResourceInTask) {
         ui.table_next_row();
         ui.table_next_column();
-        
-        let resource_name_cstr = std::ffi::CString::new(resource.name.clone()).unwrap();
-        let flags = imgui::sys::ImGuiTreeNodeFlags_SpanFullWidth | imgui::sys::ImGuiTreeNodeFlags_DefaultOpen;
-        let expand_resource = unsafe {
-            let bg_color = if self.drawing_aids.row_counter % 2 == 0 {
-                ui.style_color(StyleColor::TableRowBg)
-            } else {
-                ui.style_color(StyleColor::TableRowBgAlt)
-            };
-            ui.table_set_bg_color(TableBgTarget::CELL_BG, bg_color);
-            match role {
-                RoleOfResourceInTask::Assignee => {
-                    let resource_name_cstr = std::ffi::CString::new(resource.name.clone()).unwrap();
-                    imgui::sys::igTreeNodeEx_Str(resource_name_cstr.as_ptr(), (flags | imgui::sys::ImGuiTreeNodeFlags_Bullet) as i32)
-                },
-                RoleOfResourceInTask::WorklogContributor => {
-                    imgui::sys::igTreeNodeEx_Str(resource_name_cstr.as_ptr(), (flags | imgui::sys::ImGuiTreeNodeFlags_Leaf) as i32)
-                },
-                RoleOfResourceInTask::Watcher => {
-                    let disabled_color = ui.style_color(StyleColor::TextDisabled);
-                    let _style = ui.push_style_color(imgui::StyleColor::Text, disabled_color);
-                    imgui::sys::igTreeNodeEx_Str(resource_name_cstr.as_ptr(), (flags | imgui::sys::ImGuiTreeNodeFlags_Leaf) as i32)
-                },
-            }
+        let day_columns = self.drawing_aids.visible_day_columns.clone();
+
+        let _task_token_id = ui.push_id_int(*task_id as i32);
+        let _resource_token_id = ui.push_id_int(*resource_id as i32);
+        let bg_color = if self.drawing_aids.row_counter % 2 == 0 {
+            ui.style_color(StyleColor::TableRowBg)
+        } else {
+            ui.style_color(StyleColor::TableRowBgAlt)
         };
+        ui.table_set_bg_color(TableBgTarget::CELL_BG, bg_color);
+        match role {
+            RoleOfResourceInTask::Assignee => {
+                self.draw_gantt_chart_row_label(&resource.name, 1, None, ImGuiTreeNodeFlags_Bullet);
+            },
+            RoleOfResourceInTask::WorklogContributor => {
+                self.draw_gantt_chart_row_label(&resource.name, 1, None, ImGuiTreeNodeFlags_Leaf);
+            },
+            RoleOfResourceInTask::Watcher => {
+                let disabled_color = ui.style_color(StyleColor::TextDisabled);
+                let _style = ui.push_style_color(imgui::StyleColor::Text, disabled_color);
+                self.draw_gantt_chart_row_label(&resource.name, 1, None, ImGuiTreeNodeFlags_Leaf);
+            },
+        }
         self.draw_gantt_chart_resources_team_resource_popup(ui, resource_id, &resource);
 
-        for i in 1..=self.project.flow_state().cache().num_days() {
-            if ui.table_next_column() {
-                let _id = ui.push_id_usize(i);
-                let day = self.project.flow_state().cache().day(i - 1);
+        for i in day_columns {
+            if ui.table_set_column_index(i) {
+                let _id = ui.push_id_usize(self.drawing_aids.first_visible_day + i);
+                let day = self.gantt_chart_day(i);
                 self.draw_cell_background(ui, &day);
                 self.draw_absence(ui, &day, resource_id, &resource);
                 if !self.gui_config.hide_worklogs {
@@ -140,8 +164,5 @@ impl Gui {
                 self.draw_gantt_chart_resources_team_resource_content_popup(ui, resource_id, &resource, &day);
             }
         }
-        if expand_resource {
-            unsafe {imgui::sys::igTreePop();}
-        }
     }
 }
\ No newline at end of file
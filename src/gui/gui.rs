@@ -1,6 +1,7 @@
 use crate::gui::*;
 use crate::gui::utils::*;
 use crate::gui_log;
+use crate::gui_notify;
 
 pub struct Gui {
     pub(super) gui_config: GuiConfig,
@@ -8,7 +9,10 @@ pub struct Gui {
 
     pub(super) filtered_labels: Vec<LabelId>,
     pub(super) selected_filter: Option<FilterId>,
-    pub(super) inspections: Vec<TaskInspection>,
+    pub(super) inspections: Vec<Inspection>,
+    pub(super) inspection_jobs: Vec<InspectionJob>,
+    pub(super) inspection_snapshots: std::sync::Arc<std::sync::Mutex<InspectionSnapshots>>,
+    pub(super) group_drill_down: Option<(NaiveDate, NaiveDate)>,
 
     pub(super) date_offset: i32,
 
@@ -20,13 +24,50 @@ pub struct Gui {
     pub(super) ticket_input_text_buffer: String,
     pub(super) task_title_input_text_buffer: String,
     pub(super) task_duration_days: f32,
+    pub(super) task_estimate_enabled: bool,
+    pub(super) task_optimistic_days: f32,
+    pub(super) task_pessimistic_days: f32,
     pub(super) absence_duration_days: f32,
     pub(super) worklog_fraction: u8,
     pub(super) milestone_input_text_buffer: String,
     pub(super) milestone_date_input_text_buffer: String,
     pub(super) label_input_text_buffer: String,
     pub(super) filter_input_text_buffer: String,
+    pub(super) slip_from_input_text_buffer: String,
+    pub(super) slip_to_input_text_buffer: String,
+    pub(super) as_of_input_text_buffer: String,
+    pub(super) scenario_input_text_buffer: String,
+    pub(super) show_scenario_diff: bool,
+    pub(super) author_input_text_buffer: String,
+    pub(super) show_audit_log: bool,
+    pub(super) audit_author: Option<String>,
+    pub(super) audit_entity_kind: Option<EntityKind>,
+    pub(super) audit_entity_input_text_buffer: String,
+    pub(super) audit_from_input_text_buffer: String,
+    pub(super) audit_to_input_text_buffer: String,
+    pub(super) member_input_text_buffer: String,
+    pub(super) member_role: Role,
+    pub(super) member_resource_id: Option<ResourceId>,
+    pub(super) query_input_text_buffer: String,
+    pub(super) query_result: Option<(String, QueryResult)>,
+    pub(super) show_leveling: bool,
+    pub(super) leveling_options: LevelingOptions,
+    pub(super) leveling_proposal: Option<LevelingProposal>,
+    pub(super) leveling_job: Option<BackgroundJob<LevelingProposal>>,
+    pub(super) show_priority_optimizer: bool,
+    pub(super) priority_milestone: Option<String>,
+    pub(super) priority_label_id: Option<LabelId>,
+    pub(super) priority_allow_reassignment: bool,
+    pub(super) priority_plan: Option<PriorityPlan>,
+    pub(super) priority_job: Option<BackgroundJob<PriorityPlan>>,
+    pub(super) show_forecast: bool,
+    pub(super) forecast_options: ForecastOptions,
+    pub(super) forecast: Option<Forecast>,
+    pub(super) forecast_job: Option<BackgroundJob<Forecast>>,
+    /// The milestone or task ticket whose distribution is charted.
+    pub(super) forecast_chart: Option<(String, DateDistribution)>,
     pub(super) logs: Vec<String>,
+    pub(super) notifications: Vec<(String, std::time::Instant)>,
     pub(super) drawing_aids: DrawingAids,
 }
 
@@ -34,16 +75,20 @@ impl Gui {
     pub fn new() -> Self {
         let gui_config = GuiConfig::load_from_yaml("config.yaml");
         let yaml_filename = gui_config.recent_project_files.first().cloned().unwrap_or_else(|| "database.yaml".to_string());
-        Gui {
+        let (project, load_error) = match Project::load(&yaml_filename, Utc::now().date_naive()) {
+            Ok(project) => (project, None),
+            Err(e) => (Project::new(&yaml_filename), Some(e)),
+        };
+        let mut gui = Gui {
             gui_config,
-            project: Project::load_from_yaml(&yaml_filename,  Utc::now().date_naive()).unwrap_or_else(|e| {
-                eprintln!("Failed to load project: {e}");
-                Project::new(&yaml_filename)
-            }),
+            project,
 
             filtered_labels: Vec::new(),
             selected_filter: None,
             inspections: Vec::new(),
+            inspection_jobs: Vec::new(),
+            inspection_snapshots: Default::default(),
+            group_drill_down: None,
 
             date_offset: 0,
 
@@ -55,15 +100,60 @@ impl Gui {
             ticket_input_text_buffer: "FCA_NRTRIC-".to_string(),
             task_title_input_text_buffer: String::new(),
             task_duration_days: 1.0,
+            task_estimate_enabled: false,
+            task_optimistic_days: 1.0,
+            task_pessimistic_days: 1.0,
             absence_duration_days: 0.0,
             worklog_fraction: 0,
             milestone_input_text_buffer: String::new(),
             milestone_date_input_text_buffer: String::new(),
             label_input_text_buffer: String::new(),
             filter_input_text_buffer: String::new(),
+            slip_from_input_text_buffer: String::new(),
+            slip_to_input_text_buffer: String::new(),
+            as_of_input_text_buffer: String::new(),
+            scenario_input_text_buffer: String::new(),
+            show_scenario_diff: false,
+            author_input_text_buffer: String::new(),
+            show_audit_log: false,
+            audit_author: None,
+            audit_entity_kind: None,
+            audit_entity_input_text_buffer: String::new(),
+            audit_from_input_text_buffer: String::new(),
+            audit_to_input_text_buffer: String::new(),
+            member_input_text_buffer: String::new(),
+            member_role: Role::Developer,
+            member_resource_id: None,
+            query_input_text_buffer: String::new(),
+            query_result: None,
+            show_leveling: false,
+            leveling_options: LevelingOptions { team_id: None, match_skills: false, objective: LevelingObjective::FinishDate },
+            leveling_proposal: None,
+            leveling_job: None,
+            show_priority_optimizer: false,
+            priority_milestone: None,
+            priority_label_id: None,
+            priority_allow_reassignment: false,
+            priority_plan: None,
+            priority_job: None,
+            show_forecast: false,
+            forecast_options: ForecastOptions::default(),
+            forecast: None,
+            forecast_job: None,
+            forecast_chart: None,
             logs: Vec::new(),
+            notifications: Vec::new(),
             drawing_aids: DrawingAids::new(),
+        };
+        gui.author_input_text_buffer = gui.gui_config.author.clone();
+        gui.project.set_author(gui.author());
+        if let Some(e) = load_error {
+            gui_notify!(gui, "Failed to load project: {e}");
         }
+        if let Some(warning) = gui.project.load_warning().map(str::to_string) {
+            gui_log!(gui, "{warning}");
+        }
+        gui
     }
 
     pub(super) fn log(&mut self, message: String) {
@@ -73,6 +163,44 @@ impl Gui {
         }
     }
 
+    /// Logs the message and also shows it as a toast, for failures the user should notice.
+    pub(super) fn notify(&mut self, message: String) {
+        self.log(message.clone());
+        self.notifications.push((message, std::time::Instant::now()));
+    }
+
+    fn draw_notifications(&mut self, ui: &Ui) {
+        self.notifications.retain(|(_, created)| created.elapsed() < NOTIFICATION_DURATION);
+        if self.notifications.is_empty() {
+            return;
+        }
+        let display_size = ui.io().display_size;
+        if let Some(_window) = ui.window("##notifications")
+            .position([display_size[0] - NOTIFICATION_MARGIN, display_size[1] - NOTIFICATION_MARGIN], Condition::Always)
+            .position_pivot([1.0, 1.0])
+            .size_constraints([0.0, 0.0], [NOTIFICATION_MAX_WIDTH, display_size[1]])
+            .always_auto_resize(true)
+            .title_bar(false)
+            .resizable(false)
+            .movable(false)
+            .collapsible(false)
+            .focus_on_appearing(false)
+            .nav_focus(false)
+            .begin()
+        {
+            let mut dismissed = None;
+            for (index, (message, _)) in self.notifications.iter().enumerate() {
+                ui.text_colored(NOTIFICATION_TEXT_COLOR, message);
+                if ui.is_item_clicked() {
+                    dismissed = Some(index);
+                }
+            }
+            if let Some(index) = dismissed {
+                self.notifications.remove(index);
+            }
+        }
+    }
+
     pub fn run(mut self) {
         let bold_font_for_init = self.bold_font.clone();
         support::init_with_startup(
@@ -109,6 +237,7 @@ impl Gui {
                 {
                     self.draw(ui);
                 }
+                self.draw_notifications(ui);
             }
         );
     }
@@ -116,6 +245,8 @@ impl Gui {
     fn draw(&mut self, ui: &Ui) {
         self.draw_menu_bar(ui);
         self.draw_ribbon(ui);
+        self.draw_time_travel_banner(ui);
+        self.draw_scenario_banner(ui);
         self.draw_tab_bar(ui);
         self.apply_pending_draws(ui);
     }
@@ -128,11 +259,66 @@ impl Gui {
     pub(super) fn open_task_in_jira(&mut self, _ui:& Ui, task: &Task) {
         let jira_url = format!("https://jiradc.ext.net.nokia.com/browse/{}", task.ticket);
         webbrowser::open(&jira_url).unwrap_or_else(|e| {
-            gui_log!(self, "Failed to open JIRA URL: {}", e);
+            gui_notify!(self, "Failed to open JIRA URL: {}", e);
         });
     }
 
+    /// Now, or the end of the day being looked at while time travelling.
     pub(super) fn get_timestamp(&self) -> DateTime<Utc> {
-        Utc::now() + chrono::Duration::days(self.date_offset as i64)
+        match self.project.as_of() {
+            Some(date) => date.and_hms_opt(23, 59, 59).unwrap().and_utc(),
+            None => Utc::now() + chrono::Duration::days(self.date_offset as i64),
+        }
+    }
+
+    pub(super) fn author(&self) -> Option<String> {
+        Some(self.gui_config.author.trim().to_string()).filter(|author| !author.is_empty())
+    }
+
+    /// Replaces the open project, which records commands under the configured author.
+    pub(super) fn set_project(&mut self, project: Project) {
+        self.project = project;
+        self.project.set_author(self.author());
+    }
+
+    /// Whether commands can be invoked, which is not the case for read-only projects and while
+    /// time travelling.
+    pub(super) fn is_editable(&self) -> bool {
+        !self.project.is_read_only() && self.project.as_of().is_none()
+    }
+
+    /// Whether the plan is editable and the author's role allows changing it.
+    pub(super) fn can_edit_plan(&self) -> bool {
+        self.is_editable() && self.project.permissions().can_edit_plan(self.project.author())
+    }
+
+    /// Whether the plan is editable and the author may log work and absences of `resource_id`.
+    pub(super) fn can_log_for(&self, resource_id: ResourceId) -> bool {
+        self.is_editable() && self.project.permissions().can_log_for(self.project.author(), resource_id)
+    }
+
+    pub(super) fn travel_to(&mut self, date: NaiveDate) {
+        match self.project.travel_to(date) {
+            Ok(()) => gui_log!(self, "Showing the project as of {date}"),
+            Err(e) => gui_notify!(self, "Failed to show the project as of {date}: {e}"),
+        }
+    }
+
+    pub(super) fn return_to_now(&mut self) {
+        self.project.return_to_now();
+        self.as_of_input_text_buffer.clear();
+        let date = self.get_timestamp().date_naive();
+        self.project.flow_state_mut().rebuild_cache(date);
+    }
+
+    fn draw_time_travel_banner(&mut self, ui: &Ui) {
+        let Some(date) = self.project.as_of() else {
+            return;
+        };
+        ui.text_colored(TIME_TRAVEL_BANNER_COLOR, format!("Showing the project as of {date}. Editing is disabled."));
+        ui.same_line();
+        if ui.button("Return to now") {
+            self.return_to_now();
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,103 @@
+use crate::gui::*;
+use crate::gui_log;
+use crate::gui_notify;
+
+impl Gui {
+    pub(super) fn draw_history_tab(&mut self, ui: &Ui) {
+        let Some(_tab_token) = ui.tab_item("History") else {
+            return;
+        };
+        let can_change = self.can_edit_plan() && self.project.active_scenario().is_none();
+        if !can_change {
+            ui.text_disabled("The history can only be changed in the plan, not while time travelling or in a scenario.");
+        }
+        let flags = TableFlags::BORDERS | TableFlags::ROW_BG | TableFlags::SCROLL_Y | TableFlags::RESIZABLE;
+        let Some(_table) = ui.begin_table_with_flags("##history_table", 4, flags) else {
+            return;
+        };
+        ui.table_setup_scroll_freeze(0, 1);
+        for (name, width) in [("#", HISTORY_INDEX_COLUMN_WIDTH), ("Timestamp", HISTORY_TIMESTAMP_COLUMN_WIDTH), ("Command", 0.0), ("##actions", HISTORY_ACTIONS_COLUMN_WIDTH)] {
+            let mut table_column_data = TableColumnSetup::new(name);
+            if width > 0.0 {
+                table_column_data.flags = TableColumnFlags::WIDTH_FIXED;
+                table_column_data.init_width_or_weight = width;
+            }
+            ui.table_setup_column_with(table_column_data);
+        }
+        ui.table_headers_row();
+
+        // Newest first, with the empty project as the last row.
+        let num_records = self.project.command_stack.len();
+        let num_applied = self.project.num_commands_applied();
+        let _disabled = ui.begin_disabled(!can_change);
+        let mut clipper = ListClipper::new(num_records as i32 + 1).begin(ui);
+        while clipper.step() {
+            for row in clipper.display_start()..clipper.display_end() {
+                let num_commands = num_records - row as usize;
+                let _id = ui.push_id_usize(num_commands);
+                ui.table_next_row();
+                if num_commands == num_applied {
+                    ui.table_set_bg_color(TableBgTarget::ROW_BG1, HISTORY_CURRENT_ROW_COLOR);
+                }
+                ui.table_next_column();
+                if num_commands > 0 {
+                    ui.text((num_commands - 1).to_string());
+                }
+                ui.table_next_column();
+                if num_commands == 0 {
+                    ui.table_next_column();
+                    ui.text_disabled("Empty project");
+                } else {
+                    let index = num_commands - 1;
+                    let command = &self.project.command_stack[index].redo_command;
+                    let description = command.details.describe(self.project.plan_flow_state());
+                    ui.text(command.timestamp.format("%Y-%m-%d %H:%M:%S").to_string());
+                    ui.table_next_column();
+                    if num_commands > num_applied {
+                        ui.text_disabled(&description);
+                    } else {
+                        ui.text(&description);
+                    }
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text(&description);
+                    }
+                }
+                ui.table_next_column();
+                if num_commands != num_applied {
+                    if ui.small_button("Jump here") {
+                        self.jump_to_history(num_commands);
+                    }
+                    ui.same_line();
+                }
+                if num_commands > 0 && num_commands <= num_applied {
+                    if ui.small_button("Revert") {
+                        self.revert_history_command(num_commands - 1);
+                    }
+                    if ui.is_item_hovered_with_flags(ItemHoveredFlags::ALLOW_WHEN_DISABLED) {
+                        ui.tooltip_text("Undo only this command, as a new command on top of the history");
+                    }
+                }
+            }
+        }
+    }
+
+    fn jump_to_history(&mut self, num_commands: usize) {
+        let position = match num_commands {
+            0 => "the empty project".to_string(),
+            _ => format!("command {}", num_commands - 1),
+        };
+        let timestamp = self.get_timestamp();
+        match self.project.jump_to(num_commands, timestamp, timestamp.date_naive()) {
+            Ok(()) => gui_log!(self, "Jumped to {position} of the history"),
+            Err(e) => gui_notify!(self, "Failed to jump to {position}: {e}"),
+        }
+    }
+
+    fn revert_history_command(&mut self, index: usize) {
+        let timestamp = self.get_timestamp();
+        match self.project.revert_command(index, timestamp, timestamp.date_naive()) {
+            Ok(()) => gui_log!(self, "Reverted command {index}"),
+            Err(e) => gui_notify!(self, "Failed to revert command {index}: {e}"),
+        }
+    }
+}
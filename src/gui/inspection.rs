@@ -1,7 +1,105 @@
 use crate::gui::*;
+use crate::gui_log;
+use crate::gui_notify;
 
 impl Gui {
-    pub(super) fn draw_inspection_table(&mut self, _ui: &Ui, inspection: &TaskInspection, id: &str) -> bool {
+    pub(super) fn start_task_inspection(&mut self, task_id: TaskId) {
+        self.start_inspection(InspectionSubject::Task(task_id));
+    }
+
+    pub(super) fn start_resource_inspection(&mut self, resource_id: ResourceId) {
+        self.start_inspection(InspectionSubject::Resource(resource_id));
+    }
+
+    pub(super) fn start_group_inspection(&mut self, group: TaskGroup) {
+        self.start_inspection(InspectionSubject::Group(group));
+    }
+
+    pub(super) fn start_slip_report(&mut self, from: Option<NaiveDate>, to: Option<NaiveDate>) {
+        self.start_inspection(InspectionSubject::SlipReport { from, to });
+    }
+
+    fn start_inspection(&mut self, subject: InspectionSubject) {
+        if self.inspection_jobs.iter().any(|job| job.subject == subject) {
+            return;
+        }
+        self.inspection_jobs.push(InspectionJob::spawn(
+            subject,
+            self.project.applied_commands(),
+            self.project.checkpoints().to_vec(),
+            self.get_timestamp().date_naive(),
+            self.inspection_snapshots.clone(),
+        ));
+    }
+
+    pub(super) fn poll_inspection_jobs(&mut self) {
+        let mut index = 0;
+        while index < self.inspection_jobs.len() {
+            let subject = self.inspection_jobs[index].subject;
+            match self.inspection_jobs[index].poll() {
+                InspectionJobStatus::Running => {
+                    index += 1;
+                    continue;
+                }
+                InspectionJobStatus::Finished(inspection) => {
+                    self.inspections.retain(|insp| insp.subject() != subject);
+                    self.inspections.push(*inspection);
+                }
+                InspectionJobStatus::Cancelled => {
+                    gui_log!(self, "Cancelled inspection of {}", self.inspection_subject_name(subject));
+                }
+                InspectionJobStatus::Failed(e) => {
+                    gui_notify!(self, "Failed to inspect {}: {e}", self.inspection_subject_name(subject));
+                }
+            }
+            self.inspection_jobs.remove(index);
+        }
+    }
+
+    fn inspection_subject_name(&self, subject: InspectionSubject) -> String {
+        let flow_state = self.project.flow_state();
+        match subject {
+            InspectionSubject::Task(task_id) => flow_state.tasks.get(&task_id)
+                .map(|task| task.ticket.clone())
+                .unwrap_or_else(|| format!("task {task_id}")),
+            InspectionSubject::Resource(resource_id) => flow_state.resources.get(&resource_id)
+                .map(|resource| resource.name.clone())
+                .unwrap_or_else(|| format!("resource {resource_id}")),
+            InspectionSubject::Group(TaskGroup::Label(label_id)) => flow_state.labels.get(&label_id)
+                .map(|label| label.name.clone())
+                .unwrap_or_else(|| format!("label {label_id}")),
+            InspectionSubject::Group(TaskGroup::Filter(filter_id)) => flow_state.filters.get(&filter_id)
+                .map(|filter| filter.name.clone())
+                .unwrap_or_else(|| format!("filter {filter_id}")),
+            InspectionSubject::SlipReport { .. } => "slip analytics".to_string(),
+        }
+    }
+
+    pub(super) fn draw_inspection_job_tab(&mut self, ui: &Ui, index: usize) {
+        let subject = self.inspection_jobs[index].subject;
+        let tab_label = format!("Inspecting {}...##inspection_job_{:?}", self.inspection_subject_name(subject), subject);
+        let job = &self.inspection_jobs[index];
+
+        let mut open = true;
+        if let Some(_tab_token) = TabItem::new(&tab_label)
+            .opened(&mut open)
+            .begin(ui)
+        {
+            ProgressBar::new(job.progress())
+                .overlay_text(format!("{:.0}%", job.progress() * 100.0))
+                .size(INSPECTION_PROGRESS_BAR_SIZE)
+                .build(ui);
+            if ui.button("Cancel") {
+                job.cancel();
+            }
+        }
+        if !open {
+            job.cancel();
+        }
+    }
+
+    /// `extra_columns` are headers of columns between the row label and the calendar days.
+    pub(super) fn draw_inspection_table(&mut self, _ui: &Ui, flow_state: &FlowState, id: &str, extra_columns: &[&str]) -> bool {
         let table_id = std::ffi::CString::new(id).unwrap();
         let flags = imgui::sys::ImGuiTableFlags_Borders
             | imgui::sys::ImGuiTableFlags_HighlightHoveredColumn
@@ -11,7 +109,7 @@ impl Gui {
             | imgui::sys::ImGuiTableFlags_Resizable
             | imgui::sys::ImGuiTableFlags_NoPadOuterX
             | imgui::sys::ImGuiTableFlags_NoPadInnerX;
-        let num_columns = inspection.flow_state.cache().num_days() + 1;
+        let num_columns = flow_state.cache().num_days() + 1 + extra_columns.len();
         unsafe {imgui::sys::igBeginTable(
             table_id.as_ptr(),
             num_columns as i32,
@@ -21,12 +119,17 @@ impl Gui {
         )}
     }
 
-    pub(super) fn draw_inspection_calendar_row(&mut self, ui: &Ui, inspection: &TaskInspection) {
+    pub(super) fn draw_inspection_calendar_row(&mut self, ui: &Ui, flow_state: &FlowState, extra_columns: &[&str]) {
         let mut table_column_data = TableColumnSetup::new("Calendar");
         table_column_data.flags = TableColumnFlags::NO_HIDE | TableColumnFlags::NO_REORDER;
         ui.table_setup_column_with(table_column_data);
-        for i in 0..inspection.flow_state.cache().num_days() {
-            let day: chrono::NaiveDate = inspection.flow_state.cache().day(i);
+        for extra_column in extra_columns {
+            let mut table_column_data = TableColumnSetup::new(*extra_column);
+            table_column_data.flags = TableColumnFlags::NO_HIDE | TableColumnFlags::NO_REORDER;
+            ui.table_setup_column_with(table_column_data);
+        }
+        for i in 0..flow_state.cache().num_days() {
+            let day: chrono::NaiveDate = flow_state.cache().day(i);
             let day_str = day.format("%m/%d").to_string();
             let day_cstr = std::ffi::CString::new(day_str).unwrap();
             unsafe {imgui::sys::igTableSetupColumn(
@@ -38,29 +141,32 @@ impl Gui {
                 0,
             );}
         }
-        unsafe {imgui::sys::igTableSetupScrollFreeze(1, 4);}
+        unsafe {imgui::sys::igTableSetupScrollFreeze(1 + extra_columns.len() as i32, 4);}
         unsafe {imgui::sys::igTableAngledHeadersRow();}
         ui.table_headers_row();
        
         let today = self.get_timestamp().date_naive();
-        for i in 0..inspection.flow_state.cache().num_days() {
-            let day: chrono::NaiveDate = inspection.flow_state.cache().day(i);
+        for i in 0..flow_state.cache().num_days() {
+            let day: chrono::NaiveDate = flow_state.cache().day(i);
             if day == today {
                 let pink = [1.0, 0.75, 0.8, 1.0];
-                ui.table_set_bg_color_with_column(TableBgTarget::CELL_BG, pink, i + 1);
+                ui.table_set_bg_color_with_column(TableBgTarget::CELL_BG, pink, i + 1 + extra_columns.len());
             }
         }
     }
 
-    pub(super) fn draw_inspection_milestones_row(&mut self, ui: &Ui, inspection: &TaskInspection) {
+    pub(super) fn draw_inspection_milestones_row(&mut self, ui: &Ui, flow_state: &FlowState, extra_columns: &[&str]) {
         ui.table_next_row();
         ui.table_next_column();
         ui.text("  Milestones");
-        for i in 1..=inspection.flow_state.cache().num_days() {
+        for _extra_column in extra_columns {
+            ui.table_next_column();
+        }
+        for i in 1..=flow_state.cache().num_days() {
             if ui.table_next_column() {
                 let _id = ui.push_id_usize(i);
-                let day = inspection.flow_state.cache().day(i - 1);
-                if let Some(milestones) = inspection.flow_state.cache().date_to_milestones.get(&day) {
+                let day = flow_state.cache().day(i - 1);
+                if let Some(milestones) = flow_state.cache().date_to_milestones.get(&day) {
                     for milestone in milestones {
                         let cursor_pos = ui.cursor_screen_pos();
 
@@ -78,6 +184,9 @@ impl Gui {
             }
         }
         ui.table_next_row();
+        for _extra_column in extra_columns {
+            ui.table_next_column();
+        }
         for _i in 1..=self.project.flow_state().cache().num_days() {
             if ui.table_next_column() {
                 let bg_color = ui.style_color(StyleColor::TableHeaderBg);
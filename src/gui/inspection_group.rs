@@ -0,0 +1,152 @@
+use crate::gui::*;
+
+impl Gui {
+    pub(super) fn draw_group_inspection_tab(&mut self, ui: &Ui, inspection: &GroupInspection) {
+        let group_name = match inspection.group {
+            TaskGroup::Label(label_id) => inspection.flow_state.labels.get(&label_id)
+                .map(|label| format!("Label {}", label.name)),
+            TaskGroup::Filter(filter_id) => inspection.flow_state.filters.get(&filter_id)
+                .map(|filter| format!("Filter {}", filter.name)),
+        }.unwrap_or_else(|| "Deleted group".to_string());
+        let tab_label = format!("{}##group_inspection_{:?}", group_name, inspection.group);
+
+        let mut open = true;
+        if let Some(_tab_token) = TabItem::new(&tab_label)
+            .opened(&mut open)
+            .begin(ui)
+        {
+            let _id = ui.push_id(format!("{:?}", inspection.group));
+            if self.draw_inspection_table(ui, &inspection.flow_state, "##inspection_gantt_chart", &[]) {
+                self.draw_inspection_calendar_row(ui, &inspection.flow_state, &[]);
+                self.draw_inspection_milestones_row(ui, &inspection.flow_state, &[]);
+                self.draw_group_inspection_content(ui, inspection);
+                unsafe { imgui::sys::igEndTable(); }
+            }
+            self.draw_group_inspection_drill_down(ui, inspection);
+        }
+
+        if !open {
+            self.inspections.retain(|insp| insp.subject() != InspectionSubject::Group(inspection.group));
+        }
+    }
+
+    fn draw_group_inspection_content(&mut self, ui: &Ui, inspection: &GroupInspection) {
+        for i in 0..inspection.flow_state.cache().num_days() {
+            let day = inspection.flow_state.cache().day(i);
+            if day < inspection.start_date {
+                continue;
+            }
+            if self.gui_config.hide_weekends_in_inspection
+                && (day.weekday() == chrono::Weekday::Sat || day.weekday() == chrono::Weekday::Sun)
+            {
+                continue;
+            }
+            if day > self.get_timestamp().date_naive() {
+                break;
+            }
+            self.draw_group_inspection_content_for_day(ui, inspection, &day);
+        }
+    }
+
+    fn draw_group_inspection_content_for_day(&mut self, ui: &Ui, inspection: &GroupInspection, date: &NaiveDate) {
+        let _vday_token_id = ui.push_id(date.to_string());
+        let (logged, remaining) = inspection.totals(*date);
+
+        ui.table_next_row();
+        ui.table_next_column();
+        ui.text(format!("  {}  {:.1} / {:.1} pd", date.format("%Y-%m-%d"), logged, remaining));
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Person-days logged / remaining as known on this date");
+        }
+
+        for i in 1..=inspection.flow_state.cache().num_days() {
+            let day = inspection.flow_state.cache().day(i - 1);
+            if ui.table_next_column() {
+                let _hday_token_id = ui.push_id_usize(i);
+                self.draw_cell_background(ui, &day);
+                if !inspection.contributions(*date, day).is_empty() {
+                    self.draw_group_inspection_cell(ui, inspection, *date, day);
+                }
+            }
+            self.draw_milestone(ui, &day);
+            if date == &day {
+                self.draw_task_inspection_current_day(ui, &day);
+            }
+        }
+    }
+
+    fn draw_group_inspection_cell(&mut self, ui: &Ui, inspection: &GroupInspection, date: NaiveDate, day: NaiveDate) {
+        let cell_height = unsafe { igGetTextLineHeight() };
+        let cell_padding = unsafe { ui.style().cell_padding };
+        let effective_cell_height = cell_height + (cell_padding[1]);
+        let effective_cell_width = ui.current_column_width();
+
+        let cursor_pos = unsafe {
+            let mut pos = ImVec2 { x: 0.0, y: 0.0 };
+            igGetCursorScreenPos(&mut pos);
+            pos.y -= cell_padding[1] / 2.0;
+            pos
+        };
+
+        let scale = effective_cell_height / inspection.peak_person_days.max(1.0);
+        let logged = inspection.logged_person_days(date, day);
+        let remaining = inspection.remaining_person_days(date, day);
+        let logged_height = logged * scale;
+        let remaining_height = remaining * scale;
+        let bottom = cursor_pos.y + effective_cell_height;
+
+        let draw_list = ui.get_window_draw_list();
+        if !self.gui_config.hide_worklogs && logged > 0.0 {
+            draw_list.add_rect([cursor_pos.x, bottom - logged_height], [cursor_pos.x + effective_cell_width, bottom], [0.32, 0.58, 0.83, 1.0])
+                .filled(true)
+                .build();
+        }
+        if remaining > 0.0 {
+            let top_left = [cursor_pos.x, bottom - logged_height - remaining_height];
+            let bottom_right = [cursor_pos.x + effective_cell_width, bottom - logged_height];
+            draw_list.add_rect(top_left, bottom_right, [1.0, 1.0, 1.0, 1.0])
+                .filled(true)
+                .build();
+            draw_list.add_rect(top_left, bottom_right, [0.0, 0.0, 0.0, 1.0])
+                .thickness(1.0)
+                .build();
+        }
+
+        let cell_max = [cursor_pos.x + effective_cell_width, bottom];
+        if ui.is_mouse_hovering_rect([cursor_pos.x, cursor_pos.y], cell_max) {
+            ui.tooltip_text(format!("{:.2} pd logged, {:.2} pd remaining\nClick for the contributing tasks", logged, remaining));
+            if ui.is_mouse_clicked(MouseButton::Left) {
+                self.drawing_aids.clicked_group_cell = Some((date, day));
+            }
+        }
+    }
+
+    fn draw_group_inspection_drill_down(&mut self, ui: &Ui, inspection: &GroupInspection) {
+        if let Some(cell) = self.drawing_aids.clicked_group_cell.take() {
+            self.group_drill_down = Some(cell);
+            ui.open_popup("##group_drill_down");
+        }
+        let Some((date, day)) = self.group_drill_down else {
+            return;
+        };
+        if let Some(_popup) = ui.begin_popup("##group_drill_down") {
+            ui.text(format!("{} as known on {}", day.format("%Y-%m-%d"), date.format("%Y-%m-%d")));
+            ui.separator();
+            for contribution in inspection.contributions(date, day) {
+                let task_label = inspection.flow_state.tasks.get(&contribution.task_id)
+                    .map(|task| format!("{} - {}", task.ticket, task.title))
+                    .unwrap_or_else(|| format!("Deleted task {}", contribution.task_id));
+                let item_label = format!("{}: {:.2} pd logged, {:.2} pd remaining",
+                    task_label,
+                    contribution.logged as f32 / 100.0,
+                    contribution.remaining as f32 / 100.0);
+                if ui.menu_item(item_label) {
+                    self.start_task_inspection(contribution.task_id);
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Inspect this task");
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,148 @@
 use crate::gui::*;
 
 impl Gui {
-    
-}
\ No newline at end of file
+    pub(super) fn draw_resource_inspection_tab(&mut self, ui: &Ui, inspection: &ResourceInspection) {
+        let resource_name = inspection.flow_state.resources.get(&inspection.resource_id)
+            .map(|resource| resource.name.clone())
+            .unwrap_or_else(|| format!("Resource {}", inspection.resource_id));
+        let tab_label = format!("{}##resource_inspection_{}", resource_name, inspection.resource_id);
+
+        let mut open = true;
+        if let Some(_tab_token) = TabItem::new(&tab_label)
+            .opened(&mut open)
+            .begin(ui)
+        {
+            let _id = ui.push_id(format!("resource_{}", inspection.resource_id));
+            self.draw_resource_inspection_legend(ui, inspection);
+            if self.draw_inspection_table(ui, &inspection.flow_state, "##inspection_gantt_chart", &[]) {
+                self.draw_inspection_calendar_row(ui, &inspection.flow_state, &[]);
+                self.draw_inspection_milestones_row(ui, &inspection.flow_state, &[]);
+                self.draw_resource_inspection_content(ui, inspection);
+                unsafe { imgui::sys::igEndTable(); }
+            }
+        }
+
+        if !open {
+            self.inspections.retain(|insp| insp.subject() != InspectionSubject::Resource(inspection.resource_id));
+        }
+    }
+
+    fn draw_resource_inspection_legend(&mut self, ui: &Ui, inspection: &ResourceInspection) {
+        let legend = inspection.tasks.iter()
+            .map(|task_id| {
+                let ticket = inspection.flow_state.tasks.get(task_id)
+                    .map(|task| task.ticket.clone())
+                    .unwrap_or_else(|| "deleted".to_string());
+                format!("{} = {}", inspection.task_label(*task_id), ticket)
+            })
+            .collect::<Vec<_>>()
+            .join(",  ");
+        ui.text_wrapped(legend);
+    }
+
+    fn draw_resource_inspection_content(&mut self, ui: &Ui, inspection: &ResourceInspection) {
+        for i in 0..inspection.flow_state.cache().num_days() {
+            let day = inspection.flow_state.cache().day(i);
+            if day < inspection.start_date {
+                continue;
+            }
+            if self.gui_config.hide_weekends_in_inspection
+                && (day.weekday() == chrono::Weekday::Sat || day.weekday() == chrono::Weekday::Sun)
+            {
+                continue;
+            }
+            if day > self.get_timestamp().date_naive() {
+                break;
+            }
+            self.draw_resource_inspection_content_for_day(ui, inspection, &day);
+        }
+    }
+
+    fn draw_resource_inspection_content_for_day(&mut self, ui: &Ui, inspection: &ResourceInspection, date: &NaiveDate) {
+        let _vday_token_id = ui.push_id(date.to_string());
+        let worklogs = inspection.worklogs_history.get(date);
+        let allocs = inspection.allocations_history.get(date);
+        let absences = inspection.absences_history.get(date);
+
+        ui.table_next_row();
+        ui.table_next_column();
+        ui.text(date.format("  %Y-%m-%d").to_string());
+
+        for i in 1..=inspection.flow_state.cache().num_days() {
+            let day = inspection.flow_state.cache().day(i - 1);
+            if ui.table_next_column() {
+                let _hday_token_id = ui.push_id_usize(i);
+                self.draw_cell_background(ui, &day);
+                let worklog = worklogs.and_then(|wl_map| wl_map.get(&day)).copied();
+                if !self.gui_config.hide_worklogs {
+                    if let Some(worklog) = worklog {
+                        self.draw_task_inspection_worklog(ui, worklog);
+                    }
+                }
+                if let Some(absence) = absences.and_then(|abs_map| abs_map.get(&day)).copied() {
+                    self.draw_task_inspection_absence(ui, absence);
+                }
+                if let Some(tasks) = allocs.and_then(|alloc_map| alloc_map.get(&day)) {
+                    self.draw_resource_inspection_alloc(ui, inspection, worklog, tasks);
+                }
+            }
+            self.draw_milestone(ui, &day);
+            if date == &day {
+                self.draw_task_inspection_current_day(ui, &day);
+            }
+        }
+    }
+
+    fn draw_resource_inspection_alloc(&mut self, ui: &Ui, inspection: &ResourceInspection, worklog: Option<u8>, tasks: &[(TaskId, Fraction)]) {
+        let cell_height = unsafe { igGetTextLineHeight() };
+        let cell_padding = unsafe { ui.style().cell_padding };
+        let effective_cell_height = cell_height + (cell_padding[1]);
+        let effective_cell_width = ui.current_column_width();
+
+        let cursor_pos = unsafe {
+            let mut pos = ImVec2 { x: 0.0, y: 0.0 };
+            igGetCursorScreenPos(&mut pos);
+            pos.y -= cell_padding[1] / 2.0;
+            pos
+        };
+
+        let alloc: u32 = tasks.iter().map(|(_, fraction)| *fraction as u32).sum();
+        let alloc_height = effective_cell_height * (alloc.min(100) as f32 / 100.0);
+        let worklog_height = effective_cell_height * (worklog.unwrap_or(0) as f32) / 100.0;
+
+        let draw_list = ui.get_window_draw_list();
+        let top_left = [cursor_pos.x, cursor_pos.y + effective_cell_height - worklog_height - alloc_height];
+        let bottom_right = [cursor_pos.x + effective_cell_width, cursor_pos.y + effective_cell_height - worklog_height];
+        let alloc_color = [1.0, 1.0, 1.0, 1.0];
+        let border_color = [0.0, 0.0, 0.0, 1.0];
+        draw_list.add_rect(top_left, bottom_right, alloc_color)
+            .filled(true)
+            .build();
+        draw_list.add_rect(top_left, bottom_right, border_color)
+            .thickness(1.0)
+            .build();
+
+        let labels = tasks.iter()
+            .map(|(task_id, _)| inspection.task_label(*task_id))
+            .collect::<Vec<_>>()
+            .join("/");
+        let text_size = ui.calc_text_size(&labels);
+        let text_pos = [
+            cursor_pos.x + (effective_cell_width - text_size[0]) * 0.5,
+            cursor_pos.y + cell_padding[1] / 2.0,
+        ];
+        draw_list.add_text(text_pos, ui.style_color(StyleColor::Text), &labels);
+
+        let cell_max = [cursor_pos.x + effective_cell_width, cursor_pos.y + effective_cell_height];
+        if ui.is_mouse_hovering_rect([cursor_pos.x, cursor_pos.y], cell_max) {
+            ui.tooltip(|| {
+                for (task_id, fraction) in tasks {
+                    let ticket = inspection.flow_state.tasks.get(task_id)
+                        .map(|task| format!("{} - {}", task.ticket, task.title))
+                        .unwrap_or_else(|| "deleted".to_string());
+                    ui.text(format!("{}: {} ({}%)", inspection.task_label(*task_id), ticket, fraction));
+                }
+            });
+        }
+    }
+}
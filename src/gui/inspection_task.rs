@@ -14,9 +14,10 @@ impl Gui {
             .begin(ui)
         {
             let _id = ui.push_id_usize(inspection.task_id as usize);
-            if self.draw_inspection_table(ui, inspection, "##inspection_gantt_chart") {
-                self.draw_inspection_calendar_row(ui, inspection);
-                self.draw_inspection_milestones_row(ui, inspection);
+            let extra_columns = ["Estimate"];
+            if self.draw_inspection_table(ui, &inspection.flow_state, "##inspection_gantt_chart", &extra_columns) {
+                self.draw_inspection_calendar_row(ui, &inspection.flow_state, &extra_columns);
+                self.draw_inspection_milestones_row(ui, &inspection.flow_state, &extra_columns);
                 self.draw_task_inspection_content(ui, inspection);
                 unsafe { imgui::sys::igEndTable(); }
             }
@@ -24,7 +25,7 @@ impl Gui {
         }
 
         if !open {
-            self.inspections.retain(|insp| insp.task_id != inspection.task_id);
+            self.inspections.retain(|insp| insp.subject() != InspectionSubject::Task(inspection.task_id));
         }
     }
 
@@ -72,7 +73,8 @@ impl Gui {
                     Some(abs) => abs,
                     None => 0,
                 };
-                if (w == 100 && a == 0) || (w == 0 && a == 100) {
+                let changed = inspection.changes_history.contains_key(date);
+                if !changed && ((w == 100 && a == 0) || (w == 0 && a == 100)) {
                     return;
                 }
             }
@@ -98,6 +100,9 @@ impl Gui {
         };
         self.drawing_aids.previous_assignee_in_inspection = assignee;
 
+        ui.table_next_column();
+        self.draw_task_inspection_estimate(ui, inspection, date);
+
         for i in 1..=inspection.flow_state.cache().num_days() {
             let day = inspection.flow_state.cache().day(i - 1);
             if ui.table_next_column() {
@@ -125,7 +130,30 @@ impl Gui {
         }
     }
 
-    fn draw_task_inspection_worklog(&mut self, ui: &Ui, worklog: u8) {
+    fn draw_task_inspection_estimate(&mut self, ui: &Ui, inspection: &TaskInspection, date: &NaiveDate) {
+        if let Some(estimate) = inspection.estimate_history.get(date) {
+            ui.text(estimate.to_string());
+        }
+        if let Some(changes) = inspection.changes_history.get(date) {
+            ui.same_line();
+            let radius = ui.text_line_height() / 3.0;
+            let cursor_pos = ui.cursor_screen_pos();
+            let center = [cursor_pos[0] + radius, cursor_pos[1] + ui.text_line_height() / 2.0];
+            ui.get_window_draw_list().add_circle(center, radius, INSPECTION_CHANGE_ICON_COLOR)
+                .filled(true)
+                .build();
+            ui.dummy([2.0 * radius, ui.text_line_height()]);
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    for change in changes {
+                        ui.text(change);
+                    }
+                });
+            }
+        }
+    }
+
+    pub(super) fn draw_task_inspection_worklog(&mut self, ui: &Ui, worklog: u8) {
         let cell_height = unsafe { igGetTextLineHeight() };
         let cell_padding = unsafe { ui.style().cell_padding };
         let effective_cell_height = cell_height + 1.5 * cell_padding[1];
@@ -151,7 +179,7 @@ impl Gui {
             .build();
     }
 
-    fn draw_task_inspection_absence(&mut self, ui: &Ui, absence: u8) {
+    pub(super) fn draw_task_inspection_absence(&mut self, ui: &Ui, absence: u8) {
         let cell_height = unsafe { igGetTextLineHeight() };
         let cell_padding = unsafe { ui.style().cell_padding };
         let effective_cell_height = cell_height + 1.5 * cell_padding[1];
@@ -260,7 +288,7 @@ impl Gui {
         }
     }
 
-    fn draw_task_inspection_current_day(&mut self, ui: &Ui, _day: &NaiveDate) {
+    pub(super) fn draw_task_inspection_current_day(&mut self, ui: &Ui, _day: &NaiveDate) {
         let cell_height = unsafe { igGetTextLineHeight() };
         let cell_padding = unsafe { ui.style().cell_padding };
         let effective_cell_height = cell_height + (2.0 * cell_padding[1]);
@@ -0,0 +1,158 @@
+use crate::gui::*;
+use crate::gui_log;
+use crate::gui_notify;
+
+impl Gui {
+    pub(super) fn draw_leveling_tab(&mut self, ui: &Ui) {
+        if !self.show_leveling {
+            return;
+        }
+        self.poll_leveling_job();
+        let mut open = true;
+        if let Some(_tab_token) = TabItem::new("Assignment Suggestions")
+            .opened(&mut open)
+            .begin(ui)
+        {
+            self.draw_leveling_options(ui);
+            if let Some(proposal) = self.leveling_proposal.clone() {
+                self.draw_leveling_proposal(ui, &proposal);
+            }
+        }
+        if !open {
+            self.show_leveling = false;
+            self.leveling_proposal = None;
+            if let Some(job) = self.leveling_job.take() {
+                job.cancel();
+            }
+        }
+    }
+
+    fn start_leveling(&mut self) {
+        let flow_state = self.project.flow_state().clone();
+        let options = self.leveling_options;
+        let date = self.get_timestamp().date_naive();
+        self.leveling_job = Some(BackgroundJob::spawn("assignment suggestions", move |control| {
+            propose_leveling(&flow_state, &options, date, control)
+        }));
+    }
+
+    fn poll_leveling_job(&mut self) {
+        let Some(job) = &self.leveling_job else {
+            return;
+        };
+        match job.poll() {
+            BackgroundJobStatus::Running => return,
+            BackgroundJobStatus::Finished(proposal) => {
+                self.leveling_proposal = Some(proposal);
+            }
+            BackgroundJobStatus::Cancelled => {
+                gui_log!(self, "Cancelled assignment suggestions");
+            }
+            BackgroundJobStatus::Failed(e) => {
+                gui_notify!(self, "Failed to propose assignments: {e}");
+            }
+        }
+        self.leveling_job = None;
+    }
+
+    fn draw_leveling_options(&mut self, ui: &Ui) {
+        let flow_state = self.project.flow_state();
+        ui.set_next_item_width(AUDIT_LOG_COMBO_WIDTH);
+        let team_preview = self.leveling_options.team_id
+            .and_then(|team_id| flow_state.teams.get(&team_id))
+            .map(|team| team.name.clone())
+            .unwrap_or_else(|| "Any team".to_string());
+        if let Some(_combo) = ui.begin_combo("##leveling_team", &team_preview) {
+            if ui.selectable_config("Any team").selected(self.leveling_options.team_id.is_none()).build() {
+                self.leveling_options.team_id = None;
+            }
+            for (team_id, team) in &flow_state.teams {
+                if ui.selectable_config(&team.name).selected(self.leveling_options.team_id == Some(*team_id)).build() {
+                    self.leveling_options.team_id = Some(*team_id);
+                }
+            }
+        }
+        ui.same_line();
+        ui.checkbox("Match skills", &mut self.leveling_options.match_skills);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Only suggest resources who have worked on tasks with all the labels of the task");
+        }
+        for objective in LevelingObjective::ALL {
+            ui.same_line();
+            ui.radio_button(objective.name(), &mut self.leveling_options.objective, objective);
+        }
+        ui.same_line();
+        if let Some(job) = &self.leveling_job {
+            ProgressBar::new(job.progress())
+                .overlay_text(format!("{:.0}%", job.progress() * 100.0))
+                .size(INSPECTION_PROGRESS_BAR_SIZE)
+                .build(ui);
+            ui.same_line();
+            if ui.button("Cancel") {
+                job.cancel();
+            }
+        } else if ui.button("Propose") {
+            self.start_leveling();
+        }
+    }
+
+    fn draw_leveling_proposal(&mut self, ui: &Ui, proposal: &LevelingProposal) {
+        let format_date = |date: Option<NaiveDate>| date.map(|date| date.to_string()).unwrap_or_else(|| "-".to_string());
+        ui.text(format!(
+            "Finish date {} -> {}, {} task-days late -> {}",
+            format_date(proposal.score_before.finish_date),
+            format_date(proposal.score_after.finish_date),
+            proposal.score_before.lateness_days,
+            proposal.score_after.lateness_days));
+        if !proposal.unplaced.is_empty() {
+            let tickets: Vec<&str> = proposal.unplaced.iter()
+                .filter_map(|task_id| self.project.flow_state().tasks.get(task_id))
+                .map(|task| task.ticket.as_str())
+                .collect();
+            ui.text_disabled(format!("No resource qualifies for: {}", tickets.join(", ")));
+        }
+        ui.same_line();
+        let read_only = ui.begin_disabled(!self.can_edit_plan() || proposal.assignments.is_empty());
+        if ui.button("Apply") {
+            self.apply_leveling_proposal(proposal);
+        }
+        read_only.end();
+
+        let flags = TableFlags::BORDERS | TableFlags::ROW_BG | TableFlags::SCROLL_Y | TableFlags::RESIZABLE;
+        let Some(_table) = ui.begin_table_with_flags("##leveling_table", 5, flags) else {
+            return;
+        };
+        ui.table_setup_scroll_freeze(0, 1);
+        for name in ["Ticket", "Title", "Assignee", "Queue Position", "End"] {
+            ui.table_setup_column(name);
+        }
+        ui.table_headers_row();
+        for assignment in &proposal.assignments {
+            ui.table_next_row();
+            ui.table_next_column();
+            ui.text(&assignment.ticket);
+            ui.table_next_column();
+            ui.text(&assignment.title);
+            ui.table_next_column();
+            ui.text(&assignment.resource_name);
+            ui.table_next_column();
+            ui.text((assignment.position + 1).to_string());
+            ui.table_next_column();
+            ui.text(format_date(assignment.end_date));
+        }
+    }
+
+    fn apply_leveling_proposal(&mut self, proposal: &LevelingProposal) {
+        let timestamp = self.get_timestamp();
+        let Some(command) = proposal.to_command(timestamp) else {
+            return;
+        };
+        match self.project.invoke_command(command, timestamp.date_naive()) {
+            Ok(()) => {
+                gui_log!(self, "Assigned {} tasks as suggested", proposal.assignments.len());
+                self.leveling_proposal = None;
+            }
+            Err(e) => gui_notify!(self, "Failed to apply the suggested assignments: {e}"),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::gui::*;
+use crate::gui_log;
+use crate::gui_notify;
+
+impl Gui {
+    pub(super) fn draw_members_menu(&mut self, ui: &Ui) {
+        let can_manage_members = self.is_editable()
+            && self.project.permissions().can_manage_members(self.project.author());
+        let Some(_members_menu) = ui.begin_menu_with_enabled("Members", can_manage_members) else {
+            return;
+        };
+        let members = self.project.permissions().members.clone();
+        if members.is_empty() {
+            ui.text_disabled("No members, everyone may change everything");
+        }
+        for (name, member) in &members {
+            let label = match member.resource_id.and_then(|id| self.project.plan_flow_state().resources.get(&id)) {
+                Some(resource) => format!("{name} ({}, {})", member.role.name(), resource.name),
+                None => format!("{name} ({})", member.role.name()),
+            };
+            if let Some(_member_menu) = ui.begin_menu(&label) {
+                for role in Role::ALL {
+                    if ui.menu_item_config(role.name()).selected(member.role == role).build() && member.role != role {
+                        self.set_member(name, Some(Member { role, ..member.clone() }));
+                    }
+                }
+                ui.separator();
+                if ui.menu_item("Remove") {
+                    self.set_member(name, None);
+                }
+            }
+        }
+        ui.separator();
+        self.draw_add_member_form(ui);
+    }
+
+    fn draw_add_member_form(&mut self, ui: &Ui) {
+        ui.set_next_item_width(MEMBER_COMBO_WIDTH);
+        ui.input_text("##member_name", &mut self.member_input_text_buffer)
+            .hint("Member name")
+            .build();
+
+        ui.same_line();
+        ui.set_next_item_width(MEMBER_COMBO_WIDTH);
+        if let Some(_combo) = ui.begin_combo("##member_role", self.member_role.name()) {
+            for role in Role::ALL {
+                if ui.selectable_config(role.name()).selected(self.member_role == role).build() {
+                    self.member_role = role;
+                }
+            }
+        }
+
+        ui.same_line();
+        ui.set_next_item_width(MEMBER_COMBO_WIDTH);
+        let flow_state = self.project.plan_flow_state();
+        let resource_preview = self.member_resource_id
+            .and_then(|id| flow_state.resources.get(&id))
+            .map(|resource| resource.name.clone())
+            .unwrap_or_else(|| "No resource".to_string());
+        if let Some(_combo) = ui.begin_combo("##member_resource", &resource_preview) {
+            if ui.selectable_config("No resource").selected(self.member_resource_id.is_none()).build() {
+                self.member_resource_id = None;
+            }
+            for (resource_id, resource) in &flow_state.resources {
+                if ui.selectable_config(&resource.name).selected(self.member_resource_id == Some(*resource_id)).build() {
+                    self.member_resource_id = Some(*resource_id);
+                }
+            }
+        }
+
+        ui.same_line();
+        if ui.button("Add") {
+            let name = self.member_input_text_buffer.trim().to_string();
+            let member = Member { role: self.member_role, resource_id: self.member_resource_id };
+            if self.set_member(&name, Some(member)) {
+                self.member_input_text_buffer.clear();
+                self.member_resource_id = None;
+            }
+        }
+    }
+
+    fn set_member(&mut self, name: &str, member: Option<Member>) -> bool {
+        let description = match &member {
+            Some(member) => format!("Set '{name}' to {}", member.role.name()),
+            None => format!("Removed '{name}'"),
+        };
+        match self.project.set_member(name, member) {
+            Ok(()) => {
+                gui_log!(self, "{description}");
+                true
+            }
+            Err(e) => {
+                gui_notify!(self, "Failed to update member: {e}");
+                false
+            }
+        }
+    }
+}
@@ -1,17 +1,20 @@
 use crate::gui::*;
 use crate::gui_log;
+use crate::gui_notify;
 
 impl Gui {
     pub(super) fn draw_menu_bar(&mut self, ui: &Ui) {
-        if ui.is_key_pressed(Key::Z) && ui.io().key_ctrl {
+        if ui.is_key_pressed(Key::Z) && ui.io().key_ctrl && self.is_editable() {
             /* let date = today as NaiveDate */
-            self.project.undo(self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                gui_log!(self, "Failed to undo: {e}");
+            let timestamp = self.get_timestamp();
+            self.project.undo(timestamp, timestamp.date_naive()).unwrap_or_else(|e| {
+                gui_notify!(self, "Failed to undo: {e}");
             });
         }
-        if ui.is_key_pressed(Key::Y) && ui.io().key_ctrl {
-            self.project.redo(self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                gui_log!(self, "Failed to redo: {e}");
+        if ui.is_key_pressed(Key::Y) && ui.io().key_ctrl && self.is_editable() {
+            let timestamp = self.get_timestamp();
+            self.project.redo(timestamp, timestamp.date_naive()).unwrap_or_else(|e| {
+                gui_notify!(self, "Failed to redo: {e}");
             });
         }
         if let Some(_menu_bar) = ui.begin_menu_bar() {
@@ -27,7 +30,7 @@ impl Gui {
                         ui.same_line();
                         if ui.button("Ok") {
                             ui.close_current_popup();
-                            self.project = Project::new(&self.new_project_input_text_buffer);
+                            self.set_project(Project::new(&self.new_project_input_text_buffer));
                             gui_log!(self, "Created new project");
                             if !self.gui_config.recent_project_files.contains(&self.new_project_input_text_buffer) {
                                 self.gui_config.recent_project_files.push(self.new_project_input_text_buffer.clone());
@@ -40,21 +43,25 @@ impl Gui {
                 if ui.menu_item("Open Project...") {
                     if let Some(file_path) = rfd::FileDialog::new()
                         .add_filter("YAML files", &["yaml", "yml"])
+                        .add_filter("SQLite files", &SQLITE_EXTENSIONS)
                         .set_directory(".")
                         .pick_file() 
                     {
                         let file_path_str = file_path.to_string_lossy().to_string();
-                        match Project::load_from_yaml(&file_path_str, self.get_timestamp().date_naive()) {
+                        match Project::load(&file_path_str, self.get_timestamp().date_naive()) {
                             Ok(project) => {
                                 if !self.gui_config.recent_project_files.contains(&file_path_str) {
                                     self.gui_config.recent_project_files.push(file_path_str.clone());
                                     self.gui_config.save_to_file();
                                 }
-                                self.project = project;
+                                self.set_project(project);
                                 gui_log!(self, "Opened project from {file_path_str}");
+                                if let Some(warning) = self.project.load_warning().map(str::to_string) {
+                                    gui_log!(self, "{warning}");
+                                }
                             },
                             Err(e) => {
-                                gui_log!(self, "Failed to open project from {file_path_str}: {e}");
+                                gui_notify!(self, "Failed to open project from {file_path_str}: {e}");
                             }
                         }
                     }
@@ -63,10 +70,13 @@ impl Gui {
                     let recent_files = self.gui_config.recent_project_files.clone();
                     for recent_file in &recent_files {
                         if ui.menu_item(recent_file) {
-                            match Project::load_from_yaml(recent_file, self.get_timestamp().date_naive()) {
+                            match Project::load(recent_file, self.get_timestamp().date_naive()) {
                                 Ok(project) => {
-                                    self.project = project;
+                                    self.set_project(project);
                                     gui_log!(self, "Opened project from {recent_file}");
+                                    if let Some(warning) = self.project.load_warning().map(str::to_string) {
+                                        gui_log!(self, "{warning}");
+                                    }
                                     if let Some(pos) = self.gui_config.recent_project_files.iter().position(|f| f == recent_file) {
                                         self.gui_config.recent_project_files.remove(pos);
                                         self.gui_config.recent_project_files.insert(0, recent_file.clone());
@@ -74,30 +84,104 @@ impl Gui {
                                     }
                                 },
                                 Err(e) => {
-                                    gui_log!(self, "Failed to open project from {recent_file}: {e}");
+                                    gui_notify!(self, "Failed to open project from {recent_file}: {e}");
                                 }
                             }
                         }
                     }
                 }
+                if ui.menu_item("Convert Project...") {
+                    if let Some(source) = self.project.filename().map(str::to_string) {
+                        if let Some(file_path) = rfd::FileDialog::new()
+                            .add_filter("SQLite files", &SQLITE_EXTENSIONS)
+                            .add_filter("YAML files", &["yaml", "yml"])
+                            .set_directory(".")
+                            .save_file()
+                        {
+                            let destination = file_path.to_string_lossy().to_string();
+                            match convert_project_file(&source, &destination, self.get_timestamp().date_naive()) {
+                                Ok(()) => gui_log!(self, "Converted project {source} to {destination}"),
+                                Err(e) => gui_notify!(self, "Failed to convert project to {destination}: {e}"),
+                            }
+                        }
+                    }
+                }
+                if let Some(_author_menu) = ui.begin_menu("Author") {
+                    if let Some(_child_window) = ui.child_window("##author_menu")
+                            .size(NEW_PROJECT_CHILD_WINDOW_SIZE)
+                            .begin() {
+                        let mut can_set_author = ui.input_text("##author", &mut self.author_input_text_buffer)
+                            .enter_returns_true(true)
+                            .hint("Your name")
+                            .build();
+                        ui.same_line();
+                        if ui.button("Ok") {
+                            can_set_author = true;
+                        }
+                        if can_set_author {
+                            ui.close_current_popup();
+                            self.gui_config.author = self.author_input_text_buffer.trim().to_string();
+                            self.gui_config.save_to_file();
+                            self.project.set_author(self.author());
+                            gui_log!(self, "Commands are now recorded as authored by '{}'", self.gui_config.author);
+                        }
+                    }
+                }
+                self.draw_members_menu(ui);
                 ui.separator();
                 if ui.menu_item("Exit") {
                     std::process::exit(0);
                 }
             };
             if let Some(_edit_menu) = ui.begin_menu("Edit") {
-                if ui.menu_item_config("Undo").shortcut("Ctrl+Z").build() {
-                    self.project.undo(self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                        gui_log!(self, "Failed to undo: {e}");
+                if ui.menu_item_config("Undo").shortcut("Ctrl+Z").enabled(self.is_editable()).build() {
+                    let timestamp = self.get_timestamp();
+                    self.project.undo(timestamp, timestamp.date_naive()).unwrap_or_else(|e| {
+                        gui_notify!(self, "Failed to undo: {e}");
                     });
                 }
-                if ui.menu_item_config("Redo").shortcut("Ctrl+Y").build() {
-                    self.project.redo(self.get_timestamp().date_naive()).unwrap_or_else(|e| {
-                        gui_log!(self, "Failed to redo: {e}");
+                if ui.menu_item_config("Redo").shortcut("Ctrl+Y").enabled(self.is_editable()).build() {
+                    let timestamp = self.get_timestamp();
+                    self.project.redo(timestamp, timestamp.date_naive()).unwrap_or_else(|e| {
+                        gui_notify!(self, "Failed to redo: {e}");
                     });
                 }
+                ui.separator();
+                let can_switch_branch = self.can_edit_plan() && self.project.active_scenario().is_none() && !self.project.branches().is_empty();
+                if let Some(_branches_menu) = ui.begin_menu_with_enabled("Branches", can_switch_branch) {
+                    let flow_state = self.project.plan_flow_state();
+                    let branch_labels: Vec<(String, String)> = self.project.branches().iter().enumerate()
+                        .map(|(index, branch)| {
+                            let fork = match branch.fork_index {
+                                0 => "the empty project".to_string(),
+                                fork_index => format!("command {}", fork_index - 1),
+                            };
+                            let last = branch.records.last()
+                                .map(|record| record.redo_command.details.describe(flow_state))
+                                .unwrap_or_default();
+                            let label = format!("{} commands after {fork}, ending with: {last}##branch_{index}", branch.records.len());
+                            let tooltip = branch.records.iter()
+                                .map(|record| record.redo_command.details.describe(flow_state))
+                                .collect::<Vec<_>>()
+                                .join("\n");
+                            (label, tooltip)
+                        })
+                        .collect();
+                    for (index, (label, tooltip)) in branch_labels.into_iter().enumerate() {
+                        if ui.menu_item(&label) {
+                            let timestamp = self.get_timestamp();
+                            match self.project.switch_branch(index, timestamp, timestamp.date_naive()) {
+                                Ok(()) => gui_log!(self, "Switched to undo branch {index}"),
+                                Err(e) => gui_notify!(self, "Failed to switch undo branch: {e}"),
+                            }
+                        }
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(tooltip);
+                        }
+                    }
+                }
             };
-            if let Some(_action_menu) = ui.begin_menu("Command") {
+            if let Some(_action_menu) = ui.begin_menu_with_enabled("Command", self.can_edit_plan()) {
                 if let Some(_team_menu) = ui.begin_menu("Team") {
                     if let Some(_child_window) = ui.child_window("##team_menu")
                             .size(CREATE_TEAM_CHILD_WINDOW_SIZE)
@@ -115,9 +199,13 @@ impl Gui {
                         }
                         if can_create_team {
                             ui.close_current_popup();
+                            let team_id = self.project.flow_state_mut().next_team_id();
                             self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::CreateTeam {
+                                id: team_id,
                                 name: self.team_input_text_buffer.clone(),
-                            }}, self.get_timestamp().date_naive()).unwrap();
+                            }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
+                                gui_notify!(self, "Failed to create team: {e}");
+                            });
                             self.team_input_text_buffer.clear();
                         }
                     }
@@ -155,10 +243,19 @@ impl Gui {
                             }
                             if can_create_milestone {
                                 ui.close_current_popup();
-                                self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::AddMilestone {
-                                    title: self.milestone_input_text_buffer.clone(),
-                                    date: NaiveDate::parse_from_str(&self.milestone_date_input_text_buffer, "%Y-%m-%d").unwrap(),
-                                }}, self.get_timestamp().date_naive()).unwrap();
+                                match NaiveDate::parse_from_str(&self.milestone_date_input_text_buffer, "%Y-%m-%d") {
+                                    Ok(date) => {
+                                        self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::AddMilestone {
+                                            title: self.milestone_input_text_buffer.clone(),
+                                            date,
+                                        }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
+                                            gui_notify!(self, "Failed to add milestone: {e}");
+                                        });
+                                    },
+                                    Err(e) => {
+                                        gui_notify!(self, "Invalid milestone date '{}': {e}", self.milestone_date_input_text_buffer);
+                                    }
+                                }
                                 self.milestone_input_text_buffer.clear();
                             }
                         }
@@ -170,11 +267,14 @@ impl Gui {
                             if ui.menu_item(&milestone_label) {
                                 self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::RemoveMilestone {
                                     title: milestone.title.clone(),
-                                }}, self.get_timestamp().date_naive()).unwrap();
+                                }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
+                                    gui_notify!(self, "Failed to remove milestone: {e}");
+                                });
                             }
                         }
                     }
                 }
+                self.draw_estimate_basis_menu(ui);
             };
             if let Some(_label_menu) = ui.begin_menu("Label") {
                 let labels: Vec<_> = self.project.flow_state().labels.iter().map(|(id, label)| (*id, label.clone())).collect();
@@ -193,6 +293,14 @@ impl Gui {
                     self.filtered_labels.clear();
                     self.selected_filter = None;
                 }
+                ui.separator();
+                if let Some(_inspect_label_menu) = ui.begin_menu("Inspect") {
+                    for (label_id, label) in self.project.flow_state().labels.clone() {
+                        if ui.menu_item(&label.name) {
+                            self.start_group_inspection(TaskGroup::Label(label_id));
+                        }
+                    }
+                }
             }
             if let Some(_filters_menu) = ui.begin_menu("Filter") {
                 let filters: Vec<_> = self.project.flow_state().filters.iter().map(|(id, filter)| (*id, filter.clone())).collect();
@@ -209,51 +317,55 @@ impl Gui {
                     }
                 }
                 ui.separator();
-                if let Some(_save_filter_menu) = ui.begin_menu("Save") {
+                if let Some(_save_filter_menu) = ui.begin_menu_with_enabled("Save", self.can_edit_plan()) {
                     for (filter_id, filter) in &filters {
                         if ui.menu_item(&filter.name) {
-                            let label_names: Vec<String> = self.filtered_labels.iter()
-                                .filter_map(|&label_id| self.project.flow_state().labels.get(&label_id))
-                                .map(|label| label.name.clone())
-                                .collect();
                             self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::CreateModifyFilter {
+                                id: *filter_id,
                                 name: filter.name.clone(),
-                                labels: label_names,
+                                labels: self.filtered_labels.clone(),
                                 is_favorite: filter.is_favorite,
-                            }}, self.get_timestamp().date_naive()).unwrap();
+                            }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
+                                gui_notify!(self, "Failed to save filter: {e}");
+                            });
                             self.selected_filter = Some(*filter_id);
                         }
                     }
                 }
-                if let Some(_save_as_filter_menu) = ui.begin_menu("Save as...") {
+                if let Some(_save_as_filter_menu) = ui.begin_menu_with_enabled("Save as...", self.can_edit_plan()) {
                     ui.input_text("##filter_name", &mut self.filter_input_text_buffer)
                         .enter_returns_true(true)
                         .hint("Enter filter name")
                         .build();
                     if ui.button("Ok") {
                         ui.close_current_popup();
+                        let existing_filter = self.project.flow_state().get_filter_id(&self.filter_input_text_buffer)
+                            .and_then(|filter_id| self.project.flow_state().filters.get(&filter_id).map(|filter| (filter_id, filter.is_favorite)));
+                        let (filter_id, is_favorite) = match existing_filter {
+                            Some(existing_filter) => existing_filter,
+                            None => (self.project.flow_state_mut().next_filter_id(), false),
+                        };
                         self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::CreateModifyFilter {
+                            id: filter_id,
                             name: self.filter_input_text_buffer.clone(),
-                            labels: self.filtered_labels.iter()
-                                .filter_map(|&label_id| self.project.flow_state().labels.get(&label_id))
-                                .map(|label| label.name.clone())
-                                .collect(),
-                            is_favorite: false,
-                        }}, self.get_timestamp().date_naive()).unwrap();
-                        let filter_id = self.project.flow_state().filters.iter()
-                            .find(|(_, f)| f.name == self.filter_input_text_buffer)
-                            .map(|(id, _)| *id);
-                        self.selected_filter = filter_id;
+                            labels: self.filtered_labels.clone(),
+                            is_favorite,
+                        }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
+                            gui_notify!(self, "Failed to save filter: {e}");
+                        });
+                        self.selected_filter = Some(filter_id);
                         self.filter_input_text_buffer.clear();
                     }
                 }
-                if let Some(_delete_filter_menu) = ui.begin_menu("Delete") {
+                if let Some(_delete_filter_menu) = ui.begin_menu_with_enabled("Delete", self.can_edit_plan()) {
                     for (filter_id, filter) in &filters {
                         let is_selected = self.selected_filter == Some(*filter_id);
                         if ui.menu_item(&filter.name) {
                             self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::DeleteFilter {
-                                name: filter.name.clone(),
-                            }}, self.get_timestamp().date_naive()).unwrap();
+                                id: *filter_id,
+                            }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
+                                gui_notify!(self, "Failed to delete filter: {e}");
+                            });
                             if is_selected {
                                 self.selected_filter = None;
                                 self.filtered_labels.clear();
@@ -261,18 +373,25 @@ impl Gui {
                         }
                     }
                 }
-                if let Some(_favorite_filter_menu) = ui.begin_menu("Favorites") {
-                    for (_filter_id, filter) in &filters {
+                if let Some(_favorite_filter_menu) = ui.begin_menu_with_enabled("Favorites", self.can_edit_plan()) {
+                    for (filter_id, filter) in &filters {
                         let is_favorite = filter.is_favorite;
                         if ui.menu_item_config(&filter.name).selected(is_favorite).build() {
                             self.project.invoke_command(Command { timestamp: self.get_timestamp(), details: CommandDetails::CreateModifyFilter {
+                                id: *filter_id,
                                 name: filter.name.clone(),
-                                labels: filter.labels.iter()
-                                    .filter_map(|&label_id| self.project.flow_state().labels.get(&label_id))
-                                    .map(|label| label.name.clone())
-                                    .collect(),
+                                labels: filter.labels.iter().cloned().collect(),
                                 is_favorite: !is_favorite,
-                            }}, self.get_timestamp().date_naive()).unwrap();
+                            }}, self.get_timestamp().date_naive()).unwrap_or_else(|e| {
+                                gui_notify!(self, "Failed to update favorite filters: {e}");
+                            });
+                        }
+                    }
+                }
+                if let Some(_inspect_filter_menu) = ui.begin_menu("Inspect") {
+                    for (filter_id, filter) in &filters {
+                        if ui.menu_item(&filter.name) {
+                            self.start_group_inspection(TaskGroup::Filter(*filter_id));
                         }
                     }
                 }
@@ -290,7 +409,66 @@ impl Gui {
                     self.gui_config.hide_non_deviations_in_inspection = !self.gui_config.hide_non_deviations_in_inspection;
                     self.gui_config.save_to_file();
                 }
+                ui.separator();
+                if ui.menu_item("Slip Analytics") {
+                    self.start_slip_report(None, None);
+                }
+                if ui.menu_item("Audit Log") {
+                    self.show_audit_log = true;
+                }
+                if ui.menu_item("Assignment Suggestions") {
+                    self.show_leveling = true;
+                }
+                if ui.menu_item("Priority Optimizer") {
+                    self.show_priority_optimizer = true;
+                }
+                if ui.menu_item("Forecast") {
+                    self.show_forecast = true;
+                }
+            }
+            if let Some(_scenario_menu) = ui.begin_menu_with_enabled("Scenario", self.can_edit_plan()) {
+                if let Some(_new_scenario_menu) = ui.begin_menu("New Scenario") {
+                    if let Some(_child_window) = ui.child_window("##new_scenario_menu")
+                            .size(NEW_SCENARIO_CHILD_WINDOW_SIZE)
+                            .begin() {
+                        let mut can_fork = ui.input_text("##scenario_name", &mut self.scenario_input_text_buffer)
+                            .enter_returns_true(true)
+                            .hint("Enter scenario name")
+                            .build();
+                        ui.same_line();
+                        if ui.button("Ok") {
+                            can_fork = true;
+                        }
+                        if can_fork {
+                            ui.close_current_popup();
+                            self.fork_scenario();
+                        }
+                    }
+                }
+                ui.separator();
+                let active_scenario = self.project.active_scenario().map(|scenario| scenario.name.clone());
+                if ui.menu_item_config("Plan").selected(active_scenario.is_none()).build() {
+                    self.switch_scenario(None);
+                }
+                let names: Vec<String> = self.project.scenarios().iter().map(|scenario| scenario.name.clone()).collect();
+                for name in names {
+                    let is_active = active_scenario.as_ref() == Some(&name);
+                    if ui.menu_item_config(&name).selected(is_active).build() {
+                        self.switch_scenario(Some(name));
+                    }
+                }
+                ui.separator();
+                if ui.menu_item_config("Compare with Plan").enabled(active_scenario.is_some()).build() {
+                    self.show_scenario_diff = true;
+                }
+                if ui.menu_item_config("Merge into Plan").enabled(active_scenario.is_some()).build() {
+                    self.merge_active_scenario();
+                }
+                if ui.menu_item_config("Discard").enabled(active_scenario.is_some()).build() {
+                    self.discard_active_scenario();
+                }
             }
+            self.draw_scripts_menu(ui);
             if let Some(_help_menu) = ui.begin_menu("Help") {
                 if ui.menu_item("About") {
 
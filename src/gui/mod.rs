@@ -12,6 +12,18 @@ mod gantt_chart_tasks;
 mod inspection;
 mod inspection_task;
 mod inspection_resource;
+mod inspection_group;
+mod slip_analytics;
+mod scenario;
+mod history;
+mod audit_log;
+mod members;
+mod scripts;
+mod query;
+mod leveling;
+mod estimates;
+mod forecast;
+mod priority_optimizer;
 mod config;
 use config::GuiConfig;
 mod utils;
@@ -25,6 +37,13 @@ macro_rules! gui_log {
     };
 }
 
+#[macro_export]
+macro_rules! gui_notify {
+    ($gui:expr, $($arg:tt)*) => {
+        $gui.notify(format!($($arg)*))
+    };
+}
+
 use imgui::*;
 use imgui::sys::*;
 use chrono::{Utc, DateTime, Datelike, NaiveDate};
@@ -0,0 +1,142 @@
+use crate::gui::*;
+use crate::gui_log;
+use crate::gui_notify;
+
+impl Gui {
+    pub(super) fn draw_priority_optimizer_tab(&mut self, ui: &Ui) {
+        if !self.show_priority_optimizer {
+            return;
+        }
+        self.poll_priority_job();
+        let mut open = true;
+        if let Some(_tab_token) = TabItem::new("Priority Optimizer")
+            .opened(&mut open)
+            .begin(ui)
+        {
+            self.draw_priority_goal(ui);
+            if let Some(plan) = self.priority_plan.clone() {
+                self.draw_priority_plan(ui, &plan);
+            }
+        }
+        if !open {
+            self.show_priority_optimizer = false;
+            self.priority_plan = None;
+            if let Some(job) = self.priority_job.take() {
+                job.cancel();
+            }
+        }
+    }
+
+    fn start_priority_optimizer(&mut self, goal: PriorityGoal) {
+        let flow_state = self.project.flow_state().clone();
+        let date = self.get_timestamp().date_naive();
+        self.priority_job = Some(BackgroundJob::spawn("priority optimizer", move |control| {
+            optimize_priorities(&flow_state, &goal, date, control)
+        }));
+    }
+
+    fn poll_priority_job(&mut self) {
+        let Some(job) = &self.priority_job else {
+            return;
+        };
+        match job.poll() {
+            BackgroundJobStatus::Running => return,
+            BackgroundJobStatus::Finished(plan) => {
+                self.priority_plan = Some(plan);
+            }
+            BackgroundJobStatus::Cancelled => {
+                gui_log!(self, "Cancelled priority optimization");
+            }
+            BackgroundJobStatus::Failed(e) => {
+                gui_notify!(self, "Failed to optimize priorities: {e}");
+            }
+        }
+        self.priority_job = None;
+    }
+
+    fn draw_priority_goal(&mut self, ui: &Ui) {
+        let flow_state = self.project.flow_state();
+        ui.set_next_item_width(AUDIT_LOG_COMBO_WIDTH);
+        let milestone_preview = self.priority_milestone.clone().unwrap_or_else(|| "Milestone".to_string());
+        if let Some(_combo) = ui.begin_combo("##priority_milestone", &milestone_preview) {
+            for milestone in &flow_state.milestones {
+                let is_selected = self.priority_milestone.as_ref() == Some(&milestone.title);
+                if ui.selectable_config(format!("{} ({})", milestone.title, milestone.date)).selected(is_selected).build() {
+                    self.priority_milestone = Some(milestone.title.clone());
+                }
+            }
+        }
+        ui.same_line();
+        ui.set_next_item_width(AUDIT_LOG_COMBO_WIDTH);
+        let label_preview = self.priority_label_id
+            .and_then(|label_id| flow_state.labels.get(&label_id))
+            .map(|label| label.name.clone())
+            .unwrap_or_else(|| "Must-have label".to_string());
+        if let Some(_combo) = ui.begin_combo("##priority_label", &label_preview) {
+            for (label_id, label) in &flow_state.labels {
+                if ui.selectable_config(&label.name).selected(self.priority_label_id == Some(*label_id)).build() {
+                    self.priority_label_id = Some(*label_id);
+                }
+            }
+        }
+        ui.same_line();
+        ui.checkbox("Reassign within team", &mut self.priority_allow_reassignment);
+        ui.same_line();
+        let (Some(milestone), Some(label_id)) = (self.priority_milestone.clone(), self.priority_label_id) else {
+            ui.text_disabled("Choose a milestone and a label");
+            return;
+        };
+        if let Some(job) = &self.priority_job {
+            ProgressBar::new(job.progress())
+                .overlay_text(format!("{:.0}%", job.progress() * 100.0))
+                .size(INSPECTION_PROGRESS_BAR_SIZE)
+                .build(ui);
+            ui.same_line();
+            if ui.button("Cancel") {
+                job.cancel();
+            }
+        } else if ui.button("Optimize") {
+            let goal = PriorityGoal { milestone, label_id, allow_reassignment: self.priority_allow_reassignment };
+            self.start_priority_optimizer(goal);
+        }
+    }
+
+    fn draw_priority_plan(&mut self, ui: &Ui, plan: &PriorityPlan) {
+        let flow_state = self.project.flow_state();
+        let tickets = |task_ids: &[TaskId]| task_ids.iter()
+            .filter_map(|task_id| flow_state.tasks.get(task_id))
+            .map(|task| task.ticket.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        ui.text(format!(
+            "{} must-haves, late for {}: {} before, {} after",
+            plan.must_haves.len(),
+            plan.milestone_date,
+            plan.late_before.len(),
+            plan.late_after.len()));
+        if !plan.late_after.is_empty() {
+            ui.text_colored(SCENARIO_DIFF_WORSE_COLOR, format!("Still late: {}", tickets(&plan.late_after)));
+        }
+        let read_only = ui.begin_disabled(!self.can_edit_plan() || plan.to_command(Utc::now()).is_none());
+        if ui.button("Apply") {
+            self.apply_priority_plan(plan);
+        }
+        read_only.end();
+        self.draw_scenario_diff_milestones(ui, &plan.diff, ["Current", "Optimized"]);
+        self.draw_scenario_diff_tasks(ui, &plan.diff, ["Current End Date", "Optimized End Date"]);
+    }
+
+    fn apply_priority_plan(&mut self, plan: &PriorityPlan) {
+        let timestamp = self.get_timestamp();
+        let Some(command) = plan.to_command(timestamp) else {
+            return;
+        };
+        match self.project.invoke_command(command, timestamp.date_naive()) {
+            Ok(()) => {
+                gui_log!(self, "Reprioritized {} tasks for {}", plan.diff.tasks.len(), plan.milestone_date);
+                self.priority_plan = None;
+            }
+            Err(e) => gui_notify!(self, "Failed to apply the priorities: {e}"),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::gui::*;
+use crate::gui_notify;
+
+impl Gui {
+    /// Runs the query typed in the ribbon against what is shown and opens its results.
+    pub(super) fn run_query(&mut self) {
+        let text = self.query_input_text_buffer.trim().to_string();
+        if text.is_empty() {
+            self.query_result = None;
+            return;
+        }
+        match run_query(&text, self.project.flow_state(), self.get_timestamp().date_naive()) {
+            Ok(result) => self.query_result = Some((text, result)),
+            Err(e) => gui_notify!(self, "{e}"),
+        }
+    }
+
+    pub(super) fn draw_query_tab(&mut self, ui: &Ui) {
+        let Some((text, result)) = &self.query_result else {
+            return;
+        };
+        let mut open = true;
+        if let Some(_tab_token) = TabItem::new("Query")
+            .opened(&mut open)
+            .begin(ui)
+        {
+            ui.text(format!("{} rows for: {text}", result.rows.len()));
+            let flags = TableFlags::BORDERS | TableFlags::ROW_BG | TableFlags::SCROLL_Y | TableFlags::RESIZABLE;
+            if let Some(_table) = ui.begin_table_with_flags("##query_table", result.columns.len(), flags) {
+                ui.table_setup_scroll_freeze(0, 1);
+                for column in &result.columns {
+                    ui.table_setup_column(column);
+                }
+                ui.table_headers_row();
+                let mut clipper = ListClipper::new(result.rows.len() as i32).begin(ui);
+                while clipper.step() {
+                    for row in clipper.display_start()..clipper.display_end() {
+                        ui.table_next_row();
+                        for cell in &result.rows[row as usize] {
+                            ui.table_next_column();
+                            ui.text(cell);
+                        }
+                    }
+                }
+            }
+        }
+        if !open {
+            self.query_result = None;
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::gui::*;
+use crate::gui_notify;
 
 impl Gui {
     pub(super)fn draw_ribbon(&mut self, ui: &Ui) {
@@ -28,6 +29,37 @@ impl Gui {
             }
         }
 
+        ui.same_line();
+        ui.text("As of");
+        ui.same_line();
+        ui.set_next_item_width(AS_OF_INPUT_WIDTH);
+        if ui.input_text("##as_of", &mut self.as_of_input_text_buffer)
+            .enter_returns_true(true)
+            .hint("YYYY-MM-DD")
+            .build()
+        {
+            if self.as_of_input_text_buffer.trim().is_empty() {
+                self.return_to_now();
+            } else {
+                match NaiveDate::parse_from_str(self.as_of_input_text_buffer.trim(), "%Y-%m-%d") {
+                    Ok(date) => self.travel_to(date),
+                    Err(e) => gui_notify!(self, "Invalid date '{}': {e}", self.as_of_input_text_buffer),
+                }
+            }
+        }
+
+        ui.same_line();
+        ui.text("Query");
+        ui.same_line();
+        ui.set_next_item_width(QUERY_INPUT_WIDTH);
+        if ui.input_text("##query", &mut self.query_input_text_buffer)
+            .enter_returns_true(true)
+            .hint("tasks where end > milestone(\"Release\")")
+            .build()
+        {
+            self.run_query();
+        }
+
         for (filter_id, filter) in &self.project.flow_state().filters {
             if filter.is_favorite {
                 ui.same_line();
@@ -37,5 +69,23 @@ impl Gui {
                 }
             }
         }
+
+        if self.project.is_read_only() {
+            ui.same_line();
+            ui.text_colored([1.0, 0.6, 0.0, 1.0], "Read-only");
+            if ui.is_item_hovered() {
+                if let Some(warning) = self.project.load_warning() {
+                    ui.tooltip_text(warning);
+                }
+            }
+        }
+
+        if let Some(error) = self.project.checkpoint_error() {
+            ui.same_line();
+            ui.text_colored([1.0, 0.3, 0.3, 1.0], "History broken");
+            if ui.is_item_hovered() {
+                ui.tooltip_text(error.to_string());
+            }
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,167 @@
+use crate::gui::*;
+use crate::gui_log;
+use crate::gui_notify;
+
+impl Gui {
+    pub(super) fn fork_scenario(&mut self) {
+        let name = self.scenario_input_text_buffer.trim().to_string();
+        match self.project.fork_scenario(&name) {
+            Ok(()) => {
+                gui_log!(self, "Forked scenario '{name}' from the plan");
+                self.scenario_input_text_buffer.clear();
+            }
+            Err(e) => gui_notify!(self, "Failed to fork scenario '{name}': {e}"),
+        }
+    }
+
+    /// Switches to the scenario called `name`, or back to the plan for `None`.
+    pub(super) fn switch_scenario(&mut self, name: Option<String>) {
+        match self.project.switch_scenario(name.as_deref()) {
+            Ok(()) => {
+                let date = self.get_timestamp().date_naive();
+                self.project.flow_state_mut().rebuild_cache(date);
+                match name {
+                    Some(name) => gui_log!(self, "Switched to scenario '{name}'"),
+                    None => gui_log!(self, "Switched back to the plan"),
+                }
+            }
+            Err(e) => gui_notify!(self, "Failed to switch scenario: {e}"),
+        }
+    }
+
+    pub(super) fn merge_active_scenario(&mut self) {
+        let Some(name) = self.project.active_scenario().map(|scenario| scenario.name.clone()) else {
+            return;
+        };
+        let timestamp = self.get_timestamp();
+        match self.project.merge_scenario(&name, timestamp, timestamp.date_naive()) {
+            Ok(()) => gui_log!(self, "Merged scenario '{name}' into the plan"),
+            Err(e) => gui_notify!(self, "Failed to merge scenario '{name}': {e}"),
+        }
+    }
+
+    pub(super) fn discard_active_scenario(&mut self) {
+        let Some(name) = self.project.active_scenario().map(|scenario| scenario.name.clone()) else {
+            return;
+        };
+        match self.project.discard_scenario(&name) {
+            Ok(()) => gui_log!(self, "Discarded scenario '{name}'"),
+            Err(e) => gui_notify!(self, "Failed to discard scenario '{name}': {e}"),
+        }
+    }
+
+    pub(super) fn draw_scenario_banner(&mut self, ui: &Ui) {
+        let Some(scenario) = self.project.active_scenario() else {
+            return;
+        };
+        ui.text_colored(SCENARIO_BANNER_COLOR, format!(
+            "Working in scenario '{}' ({} commands). The plan is not changed until the scenario is merged.",
+            scenario.name, scenario.commands().len()));
+        ui.same_line();
+        if ui.button("Compare with Plan") {
+            self.show_scenario_diff = true;
+        }
+        ui.same_line();
+        if ui.button("Merge") {
+            self.merge_active_scenario();
+        }
+        ui.same_line();
+        if ui.button("Discard") {
+            self.discard_active_scenario();
+        }
+        ui.same_line();
+        if ui.button("Back to Plan") {
+            self.switch_scenario(None);
+        }
+    }
+
+    pub(super) fn draw_scenario_diff_tab(&mut self, ui: &Ui) {
+        if !self.show_scenario_diff {
+            return;
+        }
+        let mut open = true;
+        if let Some(_tab_token) = TabItem::new("Scenario Diff")
+            .opened(&mut open)
+            .begin(ui)
+        {
+            match self.project.active_scenario() {
+                Some(scenario) => {
+                    let diff = ScenarioDiff::between(self.project.plan_flow_state(), scenario.flow_state());
+                    ui.text(format!("Scenario '{}' compared with the plan", scenario.name));
+                    self.draw_scenario_diff_milestones(ui, &diff, ["Plan", "Scenario"]);
+                    self.draw_scenario_diff_tasks(ui, &diff, ["Plan End Date", "Scenario End Date"]);
+                }
+                None => ui.text("Switch to a scenario to compare it with the plan."),
+            }
+        }
+        if !open {
+            self.show_scenario_diff = false;
+        }
+    }
+
+    /// `sides` name the plan and the scenario columns.
+    pub(super) fn draw_scenario_diff_milestones(&self, ui: &Ui, diff: &ScenarioDiff, sides: [&str; 2]) {
+        if diff.milestones.is_empty() {
+            return;
+        }
+        ui.separator();
+        ui.text("Tasks done by each milestone");
+        if let Some(_table) = ui.begin_table_with_flags("##scenario_diff_milestones", 4, TableFlags::BORDERS | TableFlags::ROW_BG) {
+            for name in ["Milestone", "Date", sides[0], sides[1]] {
+                ui.table_setup_column(name);
+            }
+            ui.table_headers_row();
+            for impact in &diff.milestones {
+                ui.table_next_row();
+                ui.table_next_column();
+                ui.text(&impact.title);
+                ui.table_next_column();
+                ui.text(impact.date.to_string());
+                ui.table_next_column();
+                ui.text(impact.tasks_done_in_plan.to_string());
+                ui.table_next_column();
+                let color = match impact.tasks_done_in_scenario.cmp(&impact.tasks_done_in_plan) {
+                    std::cmp::Ordering::Less => SCENARIO_DIFF_WORSE_COLOR,
+                    std::cmp::Ordering::Greater => SCENARIO_DIFF_BETTER_COLOR,
+                    std::cmp::Ordering::Equal => [1.0, 1.0, 1.0, 1.0],
+                };
+                ui.text_colored(color, impact.tasks_done_in_scenario.to_string());
+            }
+        }
+    }
+
+    pub(super) fn draw_scenario_diff_tasks(&self, ui: &Ui, diff: &ScenarioDiff, sides: [&str; 2]) {
+        ui.separator();
+        if diff.tasks.is_empty() {
+            ui.text("No task end date changes.");
+            return;
+        }
+        ui.text(format!("{} tasks end on a different date", diff.tasks.len()));
+        let flags = TableFlags::BORDERS | TableFlags::ROW_BG | TableFlags::SCROLL_Y | TableFlags::RESIZABLE;
+        if let Some(_table) = ui.begin_table_with_flags("##scenario_diff_tasks", 5, flags) {
+            ui.table_setup_scroll_freeze(0, 1);
+            for name in ["Ticket", "Title", sides[0], sides[1], "Shift (days)"] {
+                ui.table_setup_column(name);
+            }
+            ui.table_headers_row();
+            let end_date = |date: Option<NaiveDate>| date.map(|date| date.to_string()).unwrap_or_else(|| "-".to_string());
+            for change in &diff.tasks {
+                ui.table_next_row();
+                ui.table_next_column();
+                ui.text(&change.ticket);
+                ui.table_next_column();
+                ui.text(&change.title);
+                ui.table_next_column();
+                ui.text(end_date(change.plan_end_date));
+                ui.table_next_column();
+                ui.text(end_date(change.scenario_end_date));
+                ui.table_next_column();
+                match change.shift_days() {
+                    Some(shift) if shift > 0 => ui.text_colored(SCENARIO_DIFF_WORSE_COLOR, format!("+{shift}")),
+                    Some(shift) => ui.text_colored(SCENARIO_DIFF_BETTER_COLOR, shift.to_string()),
+                    None => ui.text("-"),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use crate::gui::*;
+use crate::gui_log;
+use crate::gui_notify;
+
+impl Gui {
+    pub(super) fn draw_scripts_menu(&mut self, ui: &Ui) {
+        let Some(_scripts_menu) = ui.begin_menu("Scripts") else {
+            return;
+        };
+        let scripts = self.project.filename().map(Script::discover).unwrap_or_default();
+        if scripts.is_empty() {
+            ui.text_disabled(format!("No .{SCRIPT_EXTENSION} scripts next to the project"));
+        }
+        for script in scripts {
+            if ui.menu_item(&script.name) {
+                self.run_script(&script);
+            }
+        }
+    }
+
+    /// Runs `script`, logs what it printed and invokes the commands it emitted as one command.
+    fn run_script(&mut self, script: &Script) {
+        let timestamp = self.get_timestamp();
+        let output = match script.run(self.project.flow_state(), timestamp.date_naive()) {
+            Ok(output) => output,
+            Err(e) => {
+                gui_notify!(self, "{e}");
+                return;
+            }
+        };
+        for line in &output.printed {
+            gui_log!(self, "[{}] {line}", script.name);
+        }
+        let num_commands = output.commands.len();
+        let Some(command) = output.into_command(timestamp) else {
+            return;
+        };
+        match self.project.invoke_command(command, timestamp.date_naive()) {
+            Ok(()) => gui_log!(self, "Script '{}' applied {num_commands} commands", script.name),
+            Err(e) => gui_notify!(self, "Failed to apply the commands of script '{}': {e}", script.name),
+        }
+    }
+}
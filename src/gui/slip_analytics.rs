@@ -0,0 +1,111 @@
+use crate::gui::*;
+use crate::gui::utils::*;
+use crate::gui_log;
+use crate::gui_notify;
+
+impl Gui {
+    pub(super) fn draw_slip_report_tab(&mut self, ui: &Ui, report: &SlipReport) {
+        let subject = InspectionSubject::SlipReport { from: report.from, to: report.to };
+        let range = match (report.from, report.to) {
+            (None, None) => String::new(),
+            (from, to) => format!(" {} - {}",
+                from.map(|date| date.to_string()).unwrap_or_default(),
+                to.map(|date| date.to_string()).unwrap_or_default()),
+        };
+        let tab_label = format!("Slip Analytics{}##slip_report_{:?}", range, subject);
+
+        let mut open = true;
+        if let Some(_tab_token) = TabItem::new(&tab_label)
+            .opened(&mut open)
+            .begin(ui)
+        {
+            let _id = ui.push_id(format!("{:?}", subject));
+            self.draw_slip_report_controls(ui, report, subject);
+            self.draw_slip_report_table(ui, report, subject);
+        }
+
+        if !open {
+            self.inspections.retain(|insp| insp.subject() != subject);
+        }
+    }
+
+    fn draw_slip_report_controls(&mut self, ui: &Ui, report: &SlipReport, subject: InspectionSubject) {
+        ui.set_next_item_width(SLIP_REPORT_DATE_INPUT_WIDTH);
+        ui.input_text("##slip_from", &mut self.slip_from_input_text_buffer)
+            .hint("From (YYYY-MM-DD)")
+            .build();
+        ui.same_line();
+        ui.set_next_item_width(SLIP_REPORT_DATE_INPUT_WIDTH);
+        ui.input_text("##slip_to", &mut self.slip_to_input_text_buffer)
+            .hint("To (YYYY-MM-DD)")
+            .build();
+        ui.same_line();
+        if ui.button("Recompute") {
+            match (parse_optional_date(&self.slip_from_input_text_buffer), parse_optional_date(&self.slip_to_input_text_buffer)) {
+                (Ok(from), Ok(to)) => {
+                    self.inspections.retain(|insp| insp.subject() != subject);
+                    self.start_slip_report(from, to);
+                }
+                (Err(e), _) | (_, Err(e)) => {
+                    gui_notify!(self, "Invalid slip analytics date range: {e}");
+                }
+            }
+        }
+        ui.same_line();
+        if ui.button("Export CSV...") {
+            if let Some(file_path) = rfd::FileDialog::new()
+                .add_filter("CSV files", &["csv"])
+                .set_directory(".")
+                .save_file()
+            {
+                let filename = file_path.to_string_lossy().to_string();
+                match report.export_csv(&filename) {
+                    Ok(()) => gui_log!(self, "Exported slip analytics to {filename}"),
+                    Err(e) => gui_notify!(self, "Failed to export slip analytics: {e}"),
+                }
+            }
+        }
+    }
+
+    fn draw_slip_report_table(&mut self, ui: &Ui, report: &SlipReport, subject: InspectionSubject) {
+        let flags = TableFlags::BORDERS
+            | TableFlags::ROW_BG
+            | TableFlags::SCROLL_Y
+            | TableFlags::RESIZABLE
+            | TableFlags::SORTABLE;
+        if let Some(_table) = ui.begin_table_with_flags("##slip_report_table", SlipColumn::ALL.len(), flags) {
+            ui.table_setup_scroll_freeze(0, 1);
+            for column in SlipColumn::ALL {
+                ui.table_setup_column(column.name());
+            }
+            ui.table_headers_row();
+
+            if let Some(sort_specs) = ui.table_sort_specs_mut() {
+                sort_specs.conditional_sort(|specs| {
+                    if let Some(spec) = specs.iter().next() {
+                        let ascending = spec.sort_direction() != Some(TableSortDirection::Descending);
+                        let column = SlipColumn::ALL[spec.column_idx()];
+                        // `report` is this frame's copy; sort the stored one for the next frames.
+                        for inspection in self.inspections.iter_mut().filter(|insp| insp.subject() == subject) {
+                            if let Inspection::SlipReport(stored) = inspection {
+                                stored.sort_by(column, ascending);
+                            }
+                        }
+                    }
+                });
+            }
+
+            let mut clipper = ListClipper::new(report.slips.len() as i32).begin(ui);
+            while clipper.step() {
+                for row in clipper.display_start()..clipper.display_end() {
+                    let slip = &report.slips[row as usize];
+                    ui.table_next_row();
+                    for column in SlipColumn::ALL {
+                        ui.table_next_column();
+                        ui.text(column.value(slip));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -9,9 +9,25 @@ impl Gui {
             if let Some(_task_tab_item) = ui.tab_item("Tasks") {
                 self.draw_gantt_chart_tasks(ui);
             }
+            self.poll_inspection_jobs();
             for inspection in self.inspections.clone() {
-                self.draw_task_inspection_tab(ui, &inspection);
+                match &inspection {
+                    Inspection::Task(inspection) => self.draw_task_inspection_tab(ui, inspection),
+                    Inspection::Resource(inspection) => self.draw_resource_inspection_tab(ui, inspection),
+                    Inspection::Group(inspection) => self.draw_group_inspection_tab(ui, inspection),
+                    Inspection::SlipReport(report) => self.draw_slip_report_tab(ui, report),
+                }
+            }
+            for index in 0..self.inspection_jobs.len() {
+                self.draw_inspection_job_tab(ui, index);
             }
+            self.draw_scenario_diff_tab(ui);
+            self.draw_history_tab(ui);
+            self.draw_audit_log_tab(ui);
+            self.draw_query_tab(ui);
+            self.draw_leveling_tab(ui);
+            self.draw_priority_optimizer_tab(ui);
+            self.draw_forecast_tab(ui);
             if let Some(_debug_tab_item) = ui.tab_item("Debug") {
                 self.draw_debug(ui);
             }
@@ -1,4 +1,7 @@
 use crate::gui::*;
+use crate::gui::gantt_chart::GanttRows;
+use crate::gui::gantt_chart_resources::ResourcesGanttRow;
+use crate::gui::gantt_chart_tasks::TasksGanttRow;
 
 impl Gui {
     pub(super) fn draw_cell_background(&mut self, ui: &Ui, day: &NaiveDate) {
@@ -127,6 +130,25 @@ impl Gui {
         }
     }
 
+    /// A faint cell continuing the bar of a task over the days its pessimistic estimate adds.
+    pub(super) fn draw_spread(&mut self, ui: &Ui) {
+        let cell_height = unsafe { igGetTextLineHeight() };
+        let cell_padding = unsafe { ui.style().cell_padding };
+        let effective_cell_height = cell_height + (cell_padding[1]);
+        let effective_cell_width = ui.current_column_width();
+        let cursor_pos = unsafe {
+            let mut pos = ImVec2 { x: 0.0, y: 0.0 };
+            igGetCursorScreenPos(&mut pos);
+            pos.y -= cell_padding[1] / 2.0;
+            pos
+        };
+        let top_left = [cursor_pos.x, cursor_pos.y];
+        let bottom_right = [cursor_pos.x + effective_cell_width, cursor_pos.y + effective_cell_height];
+        ui.get_window_draw_list().add_rect(top_left, bottom_right, GANTT_SPREAD_COLOR)
+            .filled(true)
+            .build();
+    }
+
     pub(super) fn draw_alloc_as_watcher(&mut self, ui: &Ui, day: &NaiveDate, resource_id: Option<&ResourceId>, task_id: &TaskId, _task: &Task) {
         let cell_height = unsafe { igGetTextLineHeight() };
         let cell_padding = unsafe { ui.style().cell_padding };
@@ -372,10 +394,36 @@ pub(super) struct DrawingAids {
     pub(super) row_counter: usize,
     pub(super) pending_draws: Vec<([f32; 2], [f32; 4], String)>,
     pub(super) previous_assignee_in_inspection: Option<ResourceId>,
+    pub(super) visible_day_columns: std::ops::Range<usize>,
+    /// Index of the day shown in the first day column of the gantt charts.
+    pub(super) first_visible_day: usize,
+    /// Snapshot date and day of the group inspection cell clicked this frame.
+    pub(super) clicked_group_cell: Option<(NaiveDate, NaiveDate)>,
+    pub(super) collapsed_gantt_nodes: std::collections::HashSet<GanttNode>,
+    pub(super) tasks_gantt_rows: GanttRows<TasksGanttRow>,
+    pub(super) resources_gantt_rows: GanttRows<ResourcesGanttRow>,
+}
+
+/// A collapsible row of the gantt charts.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum GanttNode {
+    Task(TaskId),
+    Team(TeamId),
+    Resource(ResourceId),
+    Unassigned,
 }
 
 impl DrawingAids {
     pub fn new() -> Self {
-        DrawingAids { previous_rect: None, row_counter: 0, pending_draws: Vec::new(), previous_assignee_in_inspection: None }
+        DrawingAids { previous_rect: None, row_counter: 0, pending_draws: Vec::new(), previous_assignee_in_inspection: None, visible_day_columns: 1..1, first_visible_day: 0, clicked_group_cell: None, collapsed_gantt_nodes: std::collections::HashSet::new(), tasks_gantt_rows: GanttRows::new(), resources_gantt_rows: GanttRows::new() }
+    }
+}
+
+/// Parses a `YYYY-MM-DD` date, where an empty text means no date.
+pub(super) fn parse_optional_date(text: &str) -> Result<Option<NaiveDate>, chrono::ParseError> {
+    if text.trim().is_empty() {
+        Ok(None)
+    } else {
+        NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d").map(Some)
     }
-}
\ No newline at end of file
+}
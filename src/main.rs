@@ -1,6 +1,34 @@
+use flowstate::app::{run_query, Project};
 use flowstate::gui::Gui;
 
 fn main() {
-    let gui = Gui::new();
-    gui.run();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("query") => std::process::exit(query(&args[1..])),
+        _ => {
+            let gui = Gui::new();
+            gui.run();
+        }
+    }
+}
+
+/// `flowstate query <project file> <query>` prints the rows matching the query as a table.
+fn query(args: &[String]) -> i32 {
+    let [filename, text] = args else {
+        eprintln!("Usage: flowstate query <project file> <query>");
+        return 2;
+    };
+    let date = chrono::Utc::now().date_naive();
+    let result = Project::load(filename, date)
+        .and_then(|project| run_query(text, project.flow_state(), date));
+    match result {
+        Ok(result) => {
+            print!("{}", result.to_text());
+            0
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            1
+        }
+    }
 }